@@ -4,7 +4,11 @@ use crate::{
     errors::Error,
     logic::execute,
     logic::query,
-    storage::{CommissionConfig, ConfigDataKey, SaleListingDataKey, ShareDataKey},
+    storage::{
+        CancelPenalty, CommissionConfig, CommissionFloor, ConfigDataKey, EscrowDataKey,
+        FixedPayee, ListingFee, SaleListingDataKey, SettingsDataKey, ShareDataKey,
+        TransferCommission, DEFAULT_SHARE_DENOMINATOR,
+    },
 };
 
 contractmeta!(
@@ -16,7 +20,10 @@ pub trait SplitterTrait {
     /// Initializes the contract with the admin and the shareholders
     ///
     /// This method can only be called once.
-    /// Runs the `check_shares` function to make sure the shares sum up to 10000.
+    /// Runs the `check_shares` function to make sure the shares sum up to 10000,
+    /// the default share denominator.
+    /// Shareholders with a zero share are rejected, as are the default commission
+    /// address and the contract itself.
     ///
     /// ## Arguments
     ///
@@ -30,6 +37,46 @@ pub trait SplitterTrait {
         mutable: bool,
     ) -> Result<(), Error>;
 
+    /// Initializes the contract with a custom share denominator for fractional shares
+    ///
+    /// This method can only be called once, instead of `init`.
+    /// The shares must sum up to the share denominator, which is fixed afterwards.
+    /// Commission rates stay in basis points regardless of the denominator.
+    ///
+    /// ## Arguments
+    ///
+    /// * `admin` - The admin address for the contract
+    /// * `shares` - The shareholders with their shares
+    /// * `mutable` - Whether the contract is mutable or not
+    /// * `share_denominator` - The total of all the shares, must be positive
+    fn init_with_share_denominator(
+        env: Env,
+        admin: Address,
+        shares: Vec<ShareDataKey>,
+        mutable: bool,
+        share_denominator: i128,
+    ) -> Result<(), Error>;
+
+    /// Initializes the contract with the commission config set upfront
+    ///
+    /// This method can only be called once, instead of `init`.
+    /// Sets the commission recipient and rates atomically, so the default commission
+    /// address never applies. Both rates must be between 0 and 5000 basis points.
+    ///
+    /// ## Arguments
+    ///
+    /// * `admin` - The admin address for the contract
+    /// * `shares` - The shareholders with their shares
+    /// * `mutable` - Whether the contract is mutable or not
+    /// * `commission` - The commission recipient and rates
+    fn init_with_commission(
+        env: Env,
+        admin: Address,
+        shares: Vec<ShareDataKey>,
+        mutable: bool,
+        commission: CommissionConfig,
+    ) -> Result<(), Error>;
+
     // ========== Execute Functions ==========
 
     /// **ADMIN ONLY FUNCTION**
@@ -38,6 +85,7 @@ pub trait SplitterTrait {
     ///
     /// Unused tokens are defined as the tokens that are not distributed to the shareholders.
     /// Meaning token balance - sum of all the allocations.
+    /// When a transfer allowlist is set, the recipient must be on it.
     ///
     /// ## Arguments
     ///
@@ -51,6 +99,23 @@ pub trait SplitterTrait {
         amount: i128,
     ) -> Result<(), Error>;
 
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Transfers all of the unused tokens to the recipient.
+    ///
+    /// Does nothing when there are no unused tokens.
+    /// When a transfer allowlist is set, the recipient must be on it.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_address` - The address of the token to transfer
+    /// * `recipient` - The address of the recipient
+    fn transfer_all_unused(
+        env: Env,
+        token_address: Address,
+        recipient: Address,
+    ) -> Result<(), Error>;
+
     /// Distributes tokens to the shareholders.
     ///
     /// All of the available token balance is distributed on execution.
@@ -60,245 +125,1688 @@ pub trait SplitterTrait {
     /// * `token_address` - The address of the token to distribute
     fn distribute_tokens(env: Env, token_address: Address) -> Result<(), Error>;
 
+    /// Distributes tokens to the shareholders on behalf of a distributor.
+    ///
+    /// Works like `distribute_tokens`, but is authorized by a distributor set by the admin
+    /// instead of the admin.
+    ///
+    /// ## Arguments
+    ///
+    /// * `distributor` - The address of the distributor (must authorize)
+    /// * `token_address` - The address of the token to distribute
+    fn distribute_as(
+        env: Env,
+        distributor: Address,
+        token_address: Address,
+    ) -> Result<(), Error>;
+
     /// **ADMIN ONLY FUNCTION**
     ///
-    /// Updates the shares of the shareholders.
+    /// Distributes part of the available token balance to the shareholders.
     ///
-    /// All of the shares and shareholders are updated on execution.
+    /// The commission is taken from `amount`, the rest of the balance stays unused.
     ///
     /// ## Arguments
     ///
-    /// * `shares` - The updated shareholders with their shares
-    fn update_shares(env: Env, shares: Vec<ShareDataKey>) -> Result<(), Error>;
+    /// * `token_address` - The address of the token to distribute
+    /// * `amount` - The amount of tokens to distribute
+    fn distribute_amount(env: Env, token_address: Address, amount: i128) -> Result<(), Error>;
 
     /// **ADMIN ONLY FUNCTION**
     ///
-    /// Locks the contract for further shares updates.
+    /// Distributes the available token balance pro-rata to the time-weighted shares.
     ///
-    /// Locking the contract does not affect the distribution of tokens.
-    fn lock_contract(env: Env) -> Result<(), Error>;
+    /// Each shareholder is weighted by share * ledgers held since the last time-weighted
+    /// distribution, so holding over the whole period counts, not only the share at
+    /// distribution time. The weights are reset afterwards. Addresses that no longer
+    /// hold shares don't take part.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_address` - The address of the token to distribute
+    fn distribute_time_weighted(env: Env, token_address: Address) -> Result<(), Error>;
 
-    /// Withdraws the allocation of the shareholder for the token.
+    /// **ADMIN ONLY FUNCTION**
     ///
-    /// A shareholder can withdraw their allocation for a token if they have any.
+    /// Distributes part of the available token balance to a subset of the shareholders.
+    ///
+    /// The commission is taken from `amount`, the rest is split pro-rata to the shares
+    /// of the subset. Shareholders outside the subset get nothing. An empty subset fails
+    /// with `NoShareholders`, an address without shares with `NotAShareholder`.
     ///
     /// ## Arguments
     ///
-    /// * `token_address` - The address of the token to withdraw
-    /// * `shareholder` - The address of the shareholder
-    /// * `amount` - The amount of tokens to withdraw
-    fn withdraw_allocation(
+    /// * `token_address` - The address of the token to distribute
+    /// * `subset` - The shareholders to distribute to, each must hold shares
+    /// * `amount` - The amount of tokens to distribute
+    fn distribute_to_subset(
         env: Env,
         token_address: Address,
-        shareholder: Address,
+        subset: Vec<Address>,
         amount: i128,
     ) -> Result<(), Error>;
 
-    /// Transfers shares from one shareholder to another.
+    /// **ADMIN ONLY FUNCTION**
     ///
-    /// Any shareholder can transfer part or all of their shares to another address.
-    /// The sender must authorize the transaction.
+    /// Starts an epoch vesting part of the unused token balance to the shareholders.
+    ///
+    /// The commission is taken upfront, the rest vests linearly over `duration_ledgers`
+    /// and can be claimed with `claim_epoch`. Only one epoch per token can run at a time,
+    /// whatever was left unclaimed in an ended epoch is moved to the allocations.
+    /// The epoch vests to the shares held at its start, shareholders opted out of
+    /// distributions at that point are left out and the others absorb their portion.
     ///
     /// ## Arguments
     ///
-    /// * `from` - The address of the sender (must authorize)
-    /// * `to` - The address of the recipient
-    /// * `amount` - The number of shares to transfer
-    fn transfer_shares(
+    /// * `token_address` - The address of the token to vest
+    /// * `amount` - The amount of tokens to vest
+    /// * `duration_ledgers` - The number of ledgers the amount vests over
+    fn start_epoch(
         env: Env,
-        from: Address,
-        to: Address,
+        token_address: Address,
         amount: i128,
+        duration_ledgers: u32,
     ) -> Result<(), Error>;
 
-    // ========== Query Functions ==========
-
-    /// Gets the share of a shareholder.
+    /// Claims the part of the token epoch vested to the shareholder so far.
     ///
     /// ## Arguments
     ///
     /// * `shareholder` - The address of the shareholder
+    /// * `token_address` - The address of the vesting token
     ///
     /// ## Returns
     ///
-    /// * `Option<i128>` - The share of the shareholder if it exists
-    fn get_share(env: Env, shareholder: Address) -> Result<Option<i128>, Error>;
+    /// * `i128` - The claimed amount
+    fn claim_epoch(env: Env, shareholder: Address, token_address: Address) -> Result<i128, Error>;
 
-    /// Lists all of the shareholders with their shares.
+    /// **ADMIN ONLY FUNCTION**
     ///
-    /// ## Returns
+    /// Updates the shares of the shareholders.
     ///
-    /// * `Vec<ShareDataKey>` - The list of shareholders with their shares
-    fn list_shares(env: Env) -> Result<Vec<ShareDataKey>, Error>;
-
-    /// Gets the contract configuration.
+    /// All of the shares and shareholders are updated on execution.
     ///
-    /// ## Returns
+    /// Removing shareholders with pending allocations fails, unless `clear_removed_allocations`
+    /// is set, in which case they're paid out to them first, or `force` is set, in which case
+    /// they're kept in storage and stay withdrawable. Allocations of frozen shareholders are
+    /// never paid out, they stay withdrawable once the shareholder is unfrozen.
     ///
-    /// * `ConfigDataKey` - The contract configuration
-    fn get_config(env: Env) -> Result<ConfigDataKey, Error>;
-
-    /// Gets the allocation of a shareholder for a token.
+    /// Open listings offering more shares than their seller holds afterwards are reduced,
+    /// or removed when the seller holds less than a lot. Updates leaving a seller with fewer
+    /// shares than reserved by escrowed purchases fail with `EscrowActive`.
     ///
     /// ## Arguments
     ///
-    /// * `shareholder` - The address of the shareholder
-    /// * `token` - The address of the token
+    /// * `shares` - The updated shareholders with their shares
+    /// * `clear_removed_allocations` - Whether to pay out the allocations of removed shareholders
+    /// * `force` - Whether to remove shareholders with pending allocations without paying them out
+    fn update_shares(
+        env: Env,
+        shares: Vec<ShareDataKey>,
+        clear_removed_allocations: bool,
+        force: bool,
+    ) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
     ///
-    /// ## Returns
+    /// Updates a few shareholders and assigns the rest of the shares to a remainder shareholder.
     ///
-    /// * `i128` - The allocation of the shareholder for the token
-    fn get_allocation(env: Env, shareholder: Address, token: Address) -> Result<i128, Error>;
+    /// Shareholders that aren't in `changes` keep their shares. A change to 0 removes the
+    /// shareholder, as does a remainder of 0. Removed shareholders cannot have pending allocations.
+    ///
+    /// ## Arguments
+    ///
+    /// * `changes` - The shareholders with their new shares
+    /// * `remainder` - The shareholder that gets whatever is needed to reach the share denominator
+    fn update_shares_with_remainder(
+        env: Env,
+        changes: Vec<ShareDataKey>,
+        remainder: Address,
+    ) -> Result<(), Error>;
 
-    // ========== Share Marketplace Functions ==========
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Locks the contract for further shares updates.
+    ///
+    /// Locking the contract does not affect the distribution of tokens.
+    fn lock_contract(env: Env) -> Result<(), Error>;
 
-    /// Lists shares for sale
+    /// **ADMIN ONLY FUNCTION**
     ///
-    /// A shareholder can list a portion or all of their shares for sale.
-    /// The shareholder must have enough shares to sell.
+    /// Locks shares of a shareholder until a ledger, locked shares cannot be listed for sale.
+    ///
+    /// Replaces the current lock of the shareholder, locking 0 shares removes it.
     ///
     /// ## Arguments
     ///
-    /// * `seller` - The address of the seller (must authorize)
-    /// * `shares_amount` - The number of shares to sell
-    /// * `price_per_share` - The price per share in payment token units
-    /// * `payment_token` - The token address to receive as payment
-    fn list_shares_for_sale(
+    /// * `shareholder` - The address of the shareholder
+    /// * `amount` - The number of shares to lock
+    /// * `unlock_ledger` - The ledger from which the shares are free again
+    fn lock_shares(
         env: Env,
-        seller: Address,
-        shares_amount: i128,
-        price_per_share: i128,
-        payment_token: Address,
+        shareholder: Address,
+        amount: i128,
+        unlock_ledger: u32,
     ) -> Result<(), Error>;
 
-    /// Cancels an active share listing
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Replaces the admin set at initialization, e.g. when it was pointed at a temporary key.
     ///
-    /// Only the seller can cancel their own listing.
+    /// Only works once, and only before any distribution or marketplace action happened.
     ///
     /// ## Arguments
     ///
-    /// * `seller` - The address of the seller (must authorize)
-    fn cancel_listing(env: Env, seller: Address) -> Result<(), Error>;
+    /// * `new_admin` - The address of the new admin
+    fn claim_initial_admin(env: Env, new_admin: Address) -> Result<(), Error>;
 
-    /// Buys shares from a seller
+    /// **ADMIN ONLY FUNCTION**
     ///
-    /// Transfers payment to seller and shares to buyer.
-    /// Total shares remain 10,000 (shares transfer between parties).
+    /// Migrates the storage to the current layout and updates the stored version.
+    fn migrate(env: Env) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Moves a shareholder's shares and pending allocations to a new address.
+    ///
+    /// Used to rotate the key of a shareholder who lost access to their address.
+    /// Percentages are unchanged, so this works on locked contracts too.
+    /// An active listing of the old address is removed, a freeze moves to the new address,
+    /// as do the share checkpoints and the time weight. Shareholders with locked shares
+    /// fail with `SharesLocked` and those with an escrowed purchase fail with `EscrowActive`.
     ///
     /// ## Arguments
     ///
-    /// * `buyer` - The address of the buyer (must authorize)
-    /// * `seller` - The address of the seller
-    /// * `shares_amount` - The number of shares to buy
-    fn buy_shares(
-        env: Env,
-        buyer: Address,
-        seller: Address,
-        shares_amount: i128,
-    ) -> Result<(), Error>;
+    /// * `old` - The current address of the shareholder
+    /// * `new` - The new address of the shareholder, must not be a shareholder
+    fn migrate_shareholder(env: Env, old: Address, new: Address) -> Result<(), Error>;
 
-    /// Gets a specific sale listing
+    /// **ADMIN ONLY FUNCTION**
     ///
-    /// ## Arguments
+    /// Repairs the shareholders list by pruning entries without a share and duplicates.
     ///
-    /// * `seller` - The address of the seller
+    /// Shares missing from the list cannot be recovered this way, use `update_shares` for those.
     ///
     /// ## Returns
     ///
-    /// * `Option<SaleListingDataKey>` - The listing if it exists
-    fn get_listing(env: Env, seller: Address) -> Result<Option<SaleListingDataKey>, Error>;
+    /// * `u32` - The number of pruned entries
+    fn rebuild_shareholders(env: Env) -> Result<u32, Error>;
 
-    /// Lists all active share sales
+    /// **ADMIN ONLY FUNCTION**
     ///
-    /// ## Returns
+    /// Sets the minimum number of shareholders the contract must keep.
     ///
-    /// * `Vec<SaleListingDataKey>` - All active listings
-    fn list_all_sales(env: Env) -> Result<Vec<SaleListingDataKey>, Error>;
-
-    // ========== Commission Functions ==========
+    /// Share purchases and transfers that would remove a shareholder below this count are rejected.
+    /// Setting it to 0 disables the check.
+    ///
+    /// ## Arguments
+    ///
+    /// * `min_shareholders` - The minimum number of shareholders
+    fn set_min_shareholders(env: Env, min_shareholders: u32) -> Result<(), Error>;
 
-    /// **COMMISSION RECIPIENT ONLY FUNCTION**
+    /// **ADMIN ONLY FUNCTION**
     ///
-    /// Updates the commission recipient address.
+    /// Sets the treasury receiving distribution dust when no shareholder qualifies for it.
     ///
-    /// Only the current commission recipient can call this function.
+    /// Defaults to the commission recipient.
     ///
     /// ## Arguments
     ///
-    /// * `new_recipient` - The new address to receive commissions
-    fn set_commission_recipient(env: Env, new_recipient: Address) -> Result<(), Error>;
+    /// * `treasury` - The address of the treasury
+    fn set_treasury(env: Env, treasury: Address) -> Result<(), Error>;
 
-    /// **COMMISSION RECIPIENT ONLY FUNCTION**
+    /// **ADMIN ONLY FUNCTION**
     ///
-    /// Updates the buy commission rate (applied on share purchases).
+    /// Restricts the recipients of `transfer_tokens` and `transfer_all_unused`.
     ///
-    /// Only the current commission recipient can call this function.
-    /// Rate is in basis points (e.g., 150 = 1.5%).
-    /// Maximum rate is 5000 (50%).
+    /// Replaces the current allowlist. An empty list lets tokens go to any recipient.
     ///
     /// ## Arguments
     ///
-    /// * `new_rate_bps` - The new commission rate in basis points
-    fn set_buy_commission_rate(env: Env, new_rate_bps: i128) -> Result<(), Error>;
+    /// * `recipients` - The allowed recipients, at most 20
+    fn set_transfer_allowlist(env: Env, recipients: Vec<Address>) -> Result<(), Error>;
 
-    /// **COMMISSION RECIPIENT ONLY FUNCTION**
+    /// **ADMIN ONLY FUNCTION**
     ///
-    /// Updates the distribution commission rate (applied on token distributions).
+    /// Sets the maximum number of active listings in the marketplace.
     ///
-    /// Only the current commission recipient can call this function.
-    /// Rate is in basis points (e.g., 50 = 0.5%).
-    /// Maximum rate is 5000 (50%).
+    /// Sellers with an active listing can still replace it when the limit is reached.
+    /// Setting it to 0 disables the limit.
     ///
     /// ## Arguments
     ///
-    /// * `new_rate_bps` - The new commission rate in basis points
-    fn set_distribution_commission_rate(env: Env, new_rate_bps: i128) -> Result<(), Error>;
+    /// * `max_active_listings` - The maximum number of active listings
+    fn set_max_active_listings(env: Env, max_active_listings: u32) -> Result<(), Error>;
 
-    /// Gets the current commission configuration.
+    /// **ADMIN ONLY FUNCTION**
     ///
-    /// ## Returns
+    /// Sets the most a single distribution can allocate to one shareholder.
     ///
-    /// * `CommissionConfig` - The current commission configuration
-    fn get_commission_config(env: Env) -> Result<CommissionConfig, Error>;
-}
-
-#[contract]
-pub struct Splitter;
-
-#[contractimpl]
-impl SplitterTrait for Splitter {
-    // ========== Execute Functions ==========
+    /// Amounts above the cap are not redistributed to the other shareholders, they stay
+    /// in the contract as unused balance. Setting it to 0 disables the cap.
+    ///
+    /// ## Arguments
+    ///
+    /// * `max_allocation` - The maximum allocation per shareholder and distribution
+    fn set_max_allocation_per_dist(env: Env, max_allocation: i128) -> Result<(), Error>;
 
-    fn init(
-        env: Env,
-        admin: Address,
-        shares: Vec<ShareDataKey>,
-        mutable: bool,
-    ) -> Result<(), Error> {
-        execute::init(env, admin, shares, mutable)
-    }
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Sets the part of every distribution kept for reinvestment.
+    ///
+    /// The slice is taken after the commission and the fixed payee, and stays in the
+    /// contract earmarked for the token instead of being allocated, until it is withdrawn with
+    /// `withdraw_reinvest_pool`. Setting it to 0 disables it.
+    ///
+    /// ## Arguments
+    ///
+    /// * `bps` - The basis points kept for reinvestment
+    fn set_reinvest_bps(env: Env, bps: i128) -> Result<(), Error>;
 
-    fn transfer_tokens(
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Transfers tokens kept for reinvestment to the recipient, lowering the pool.
+    ///
+    /// The pool is not part of the unused balance, so this is the only way it leaves the contract.
+    /// When a transfer allowlist is set, the recipient must be on it.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_address` - The address of the token to transfer
+    /// * `recipient` - The address of the recipient
+    /// * `amount` - The amount of tokens to transfer, at most the reinvestment pool
+    fn withdraw_reinvest_pool(
         env: Env,
         token_address: Address,
         recipient: Address,
         amount: i128,
-    ) -> Result<(), Error> {
-        execute::transfer_tokens(env, token_address, recipient, amount)
-    }
+    ) -> Result<(), Error>;
 
-    fn distribute_tokens(env: Env, token_address: Address) -> Result<(), Error> {
-        execute::distribute_tokens(env, token_address)
-    }
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Enables or disables the per-shareholder `distrib` events of distributions.
+    ///
+    /// Large pools can disable them to keep the event output small, the commission, dust
+    /// and summary events are always emitted. Enabled by default.
+    ///
+    /// ## Arguments
+    ///
+    /// * `enabled` - Whether an event is emitted for every shareholder
+    fn set_emit_per_shareholder_events(env: Env, enabled: bool) -> Result<(), Error>;
 
-    fn update_shares(env: Env, shares: Vec<ShareDataKey>) -> Result<(), Error> {
-        execute::update_shares(env, shares)
-    }
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Sets the penalty charged to sellers cancelling a listing too early.
+    ///
+    /// The penalty is a part of the listing value, paid in its payment token to the
+    /// commission recipient. Setting it to 0 bps removes the penalty.
+    ///
+    /// ## Arguments
+    ///
+    /// * `bps` - The penalty in basis points of the listing value, maximum is 10000
+    /// * `min_age_ledgers` - The age in ledgers a listing must reach to be cancelled for free
+    fn set_cancel_penalty(env: Env, bps: i128, min_age_ledgers: u32) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Sets the fee charged to sellers for creating a listing.
+    ///
+    /// The fee is a part of the listing value, paid in its payment token and held in the
+    /// contract for the commission recipient, apart from the buy commission. Cancelling within
+    /// the refund window refunds a pro-rated part of it in the token it was paid in. The
+    /// recipient sweeps the rest with `sweep_buy_commission`. Setting it to 0 bps removes the fee.
+    ///
+    /// ## Arguments
+    ///
+    /// * `bps` - The fee in basis points of the listing value, maximum is 10000
+    /// * `refund_window_ledgers` - The age in ledgers until which cancelling refunds part of the fee
+    fn set_listing_fee(env: Env, bps: i128, refund_window_ledgers: u32) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Blocks or unblocks an address from listing shares for sale.
+    ///
+    /// Blocked sellers fail with `SellerBlocked` when listing or pooling shares.
+    /// An already open listing of a blocked seller can still be bought or cancelled.
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller
+    /// * `blocked` - Whether the seller is blocked
+    fn set_seller_blocked(env: Env, seller: Address, blocked: bool) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Freezes or unfreezes an address for a compliance hold.
+    ///
+    /// A frozen address can't send, list, buy or sell shares, nor withdraw its allocations.
+    /// Distributions keep accruing allocations for it until it is unfrozen.
+    /// Those actions fail with `ShareholderFrozen` while it is frozen.
+    ///
+    /// ## Arguments
+    ///
+    /// * `shareholder` - The address to freeze
+    /// * `frozen` - Whether the address is frozen
+    fn freeze_shareholder(env: Env, shareholder: Address, frozen: bool) -> Result<(), Error>;
+
+    /// Opts a shareholder out of or back into distributions
+    ///
+    /// Opted out shareholders get no allocations, their portion is split pro-rata
+    /// among the other shareholders so the full amount is still distributed.
+    ///
+    /// ## Arguments
+    ///
+    /// * `shareholder` - The address of the shareholder (must authorize)
+    /// * `opted_out` - Whether the shareholder declines distributions
+    fn set_distribution_optout(env: Env, shareholder: Address, opted_out: bool) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Links an address to an identity, addresses of the same identity cannot buy
+    /// shares from each other and fail with `SameIdentity`.
+    ///
+    /// ## Arguments
+    ///
+    /// * `address` - The address to link
+    /// * `identity` - The identity id, 0 unlinks the address
+    fn set_identity(env: Env, address: Address, identity: u64) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Allows or disallows an address to trigger distributions with `distribute_as`.
+    ///
+    /// Distributors can't call any other admin function.
+    ///
+    /// ## Arguments
+    ///
+    /// * `distributor` - The address of the distributor
+    /// * `enabled` - Whether the address is allowed to trigger distributions
+    fn set_distributor(env: Env, distributor: Address, enabled: bool) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Sets the number of shares that listed, bought and transferred amounts must be a multiple of.
+    ///
+    /// Defaults to 1, which allows any amount.
+    ///
+    /// ## Arguments
+    ///
+    /// * `share_lot_size` - The share lot size, between 1 and the share denominator
+    fn set_share_lot_size(env: Env, share_lot_size: i128) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Sets the ledgers a shareholder must wait between two share transfers.
+    ///
+    /// Transfers within the cooldown fail with `TransferCooldownActive`. Defaults to 0, which disables it.
+    ///
+    /// ## Arguments
+    ///
+    /// * `cooldown_ledgers` - The cooldown in ledgers
+    fn set_transfer_cooldown(env: Env, cooldown_ledgers: u32) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Sets whether the commission recipient pays the buy commission when it buys shares.
+    ///
+    /// Disabled by default, in which case the recipient buys without commission.
+    /// When enabled, the recipient's commission goes to the treasury, or stays in the
+    /// contract as unused balance when no treasury is set.
+    ///
+    /// ## Arguments
+    ///
+    /// * `enabled` - Whether the recipient pays commission
+    fn set_recipient_pays_commission(env: Env, enabled: bool) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Sets the ledgers that must pass between two distributions of a token.
+    ///
+    /// Distributions within the cooldown fail with `DistributionCooldownActive`. Defaults to 0, which disables it.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_address` - The address of the token
+    /// * `cooldown_ledgers` - The cooldown in ledgers
+    fn set_distribution_cooldown(
+        env: Env,
+        token_address: Address,
+        cooldown_ledgers: u32,
+    ) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Sets an external payee receiving a fixed part of every distribution.
+    ///
+    /// The payee's part is taken after the commission, the rest goes to the shareholders.
+    /// Setting it to 0 bps removes the payee.
+    ///
+    /// ## Arguments
+    ///
+    /// * `payee` - The address of the payee
+    /// * `bps` - The part of every distribution in basis points, maximum is 10000
+    fn set_fixed_payee(env: Env, payee: Address, bps: i128) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Sets the commission charged to senders of `transfer_shares`.
+    ///
+    /// The fee is a rate of the transferred shares, paid in units of the fee token to the
+    /// commission recipient. Setting it to 0 bps keeps transfers free.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token` - The address of the fee token
+    /// * `transfer_commission_bps` - The fee in basis points of the transferred shares,
+    ///   maximum is 10000
+    fn set_transfer_commission(
+        env: Env,
+        token: Address,
+        transfer_commission_bps: i128,
+    ) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Sets the smallest partial withdrawal allowed for the token.
+    ///
+    /// Withdrawing the whole remaining allocation is always allowed.
+    /// Setting it to 0 removes the minimum.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_address` - The address of the token
+    /// * `min_claimable` - The minimum withdrawal amount
+    fn set_min_claimable(
+        env: Env,
+        token_address: Address,
+        min_claimable: i128,
+    ) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Sets the least a seller must receive from a sale paid in the token, after commission.
+    ///
+    /// Purchases and pool fills leaving the seller with less fail with `SellerProceedsTooLow`.
+    /// Defaults to 1, so the commission can never consume the whole payment.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_address` - The address of the payment token
+    /// * `min_proceeds` - The minimum seller proceeds, at least 1
+    fn set_min_seller_proceeds(
+        env: Env,
+        token_address: Address,
+        min_proceeds: i128,
+    ) -> Result<(), Error>;
+
+    /// Withdraws the allocation of the shareholder for the token.
+    ///
+    /// A shareholder can withdraw their allocation for a token if they have any.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_address` - The address of the token to withdraw
+    /// * `shareholder` - The address of the shareholder
+    /// * `amount` - The amount of tokens to withdraw, `-1` withdraws the whole allocation
+    fn withdraw_allocation(
+        env: Env,
+        token_address: Address,
+        shareholder: Address,
+        amount: i128,
+    ) -> Result<(), Error>;
+
+    /// Allows the operator to withdraw part of the shareholder's allocation for them.
+    ///
+    /// Replaces the current allowance, 0 revokes it.
+    ///
+    /// ## Arguments
+    ///
+    /// * `shareholder` - The address of the shareholder (must authorize)
+    /// * `operator` - The address of the operator
+    /// * `token_address` - The address of the token
+    /// * `allowance` - The amount the operator can withdraw
+    fn approve_withdraw_operator(
+        env: Env,
+        shareholder: Address,
+        operator: Address,
+        token_address: Address,
+        allowance: i128,
+    ) -> Result<(), Error>;
+
+    /// Withdraws the allocations of several shareholders to them in one batch.
+    ///
+    /// The operator needs an allowance from every shareholder for every token.
+    /// Every claim is validated before any of them executes.
+    ///
+    /// ## Arguments
+    ///
+    /// * `operator` - The address of the operator (must authorize)
+    /// * `claims` - The shareholders, tokens and amounts to withdraw, at most 10
+    fn withdraw_allocations_for(
+        env: Env,
+        operator: Address,
+        claims: Vec<(Address, Address, i128)>,
+    ) -> Result<(), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Transfers the whole allocation of a shareholder to them without their signature.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_address` - The address of the token
+    /// * `shareholder` - The address of the shareholder to pay out
+    ///
+    /// ## Returns
+    ///
+    /// * `i128` - The pushed amount
+    fn push_allocation(env: Env, token_address: Address, shareholder: Address)
+        -> Result<i128, Error>;
+
+    /// Transfers shares from one shareholder to another.
+    ///
+    /// Any shareholder can transfer part or all of their shares to another address.
+    /// The sender must authorize the transaction.
+    ///
+    /// ## Arguments
+    ///
+    /// * `from` - The address of the sender (must authorize)
+    /// * `to` - The address of the recipient
+    /// * `amount` - The number of shares to transfer
+    fn transfer_shares(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), Error>;
+
+    /// Transfers shares from one shareholder to another, like `transfer_shares`.
+    ///
+    /// A transfer to self succeeds without changing the shares or emitting events,
+    /// instead of failing with `CannotTransferToSelf`.
+    ///
+    /// ## Arguments
+    ///
+    /// * `from` - The address of the sender (must authorize)
+    /// * `to` - The address of the recipient
+    /// * `amount` - The number of shares to transfer
+    fn consolidate_shares(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), Error>;
+
+    // ========== Query Functions ==========
+
+    /// Gets the share of a shareholder.
+    ///
+    /// ## Arguments
+    ///
+    /// * `shareholder` - The address of the shareholder
+    ///
+    /// ## Returns
+    ///
+    /// * `Option<i128>` - The share of the shareholder if it exists
+    fn get_share(env: Env, shareholder: Address) -> Result<Option<i128>, Error>;
+
+    /// Gets the share a shareholder held at a past ledger.
+    ///
+    /// Every share change is checkpointed, but only the 20 most recent checkpoints
+    /// of each shareholder are kept. Ledgers before the oldest kept checkpoint, or
+    /// before the shareholder ever held shares, return 0.
+    ///
+    /// ## Arguments
+    ///
+    /// * `shareholder` - The address of the shareholder
+    /// * `ledger` - The ledger to look up
+    ///
+    /// ## Returns
+    ///
+    /// * `i128` - The share of the shareholder as of the ledger
+    fn get_share_at(env: Env, shareholder: Address, ledger: u32) -> Result<i128, Error>;
+
+    /// Gets the share of a shareholder, treating non-shareholders as holding 0.
+    ///
+    /// ## Arguments
+    ///
+    /// * `shareholder` - The address of the shareholder
+    ///
+    /// ## Returns
+    ///
+    /// * `i128` - The share of the shareholder, 0 if it doesn't exist
+    fn get_share_or_zero(env: Env, shareholder: Address) -> Result<i128, Error>;
+
+    /// Gets the share of a shareholder split into locked and free shares.
+    ///
+    /// Shares are only locked until the unlock ledger of their lock.
+    ///
+    /// ## Arguments
+    ///
+    /// * `shareholder` - The address of the shareholder
+    ///
+    /// ## Returns
+    ///
+    /// * `(i128, i128, i128)` - The total, locked and free shares of the shareholder
+    fn get_effective_share(env: Env, shareholder: Address) -> Result<(i128, i128, i128), Error>;
+
+    /// Checks if an address is a shareholder.
+    ///
+    /// ## Arguments
+    ///
+    /// * `address` - The address to check
+    ///
+    /// ## Returns
+    ///
+    /// * `bool` - True if the address holds shares
+    fn is_shareholder(env: Env, address: Address) -> Result<bool, Error>;
+
+    /// Lists all of the shareholders with their shares.
+    ///
+    /// ## Returns
+    ///
+    /// * `Vec<ShareDataKey>` - The list of shareholders with their shares
+    fn list_shares(env: Env) -> Result<Vec<ShareDataKey>, Error>;
+
+    /// Lists all of the shareholders with their shares, ordered by share descending.
+    ///
+    /// Shareholders with equal shares are kept in their stored order.
+    /// Sorting is O(n²), so this is only meant for small pools.
+    ///
+    /// ## Returns
+    ///
+    /// * `Vec<ShareDataKey>` - The sorted list of shareholders with their shares
+    fn list_shares_sorted(env: Env) -> Result<Vec<ShareDataKey>, Error>;
+
+    /// Lists the shareholders with their shares and their allocations for the token.
+    ///
+    /// Only the first 100 shareholders are returned.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token` - The address of the token
+    ///
+    /// ## Returns
+    ///
+    /// * `Vec<(Address, i128, i128)>` - The shareholders with their shares and allocations
+    fn list_shares_with_allocation(
+        env: Env,
+        token: Address,
+    ) -> Result<Vec<(Address, i128, i128)>, Error>;
+
+    /// Gets the contract configuration.
+    ///
+    /// ## Returns
+    ///
+    /// * `ConfigDataKey` - The contract configuration
+    fn get_config(env: Env) -> Result<ConfigDataKey, Error>;
+
+    /// Gets the storage layout version of the contract.
+    ///
+    /// ## Returns
+    ///
+    /// * `u32` - The storage layout version
+    fn get_version(env: Env) -> Result<u32, Error>;
+
+    /// Gets the external payee receiving a fixed part of every distribution.
+    ///
+    /// ## Returns
+    ///
+    /// * `Option<FixedPayee>` - The fixed payee if it is set
+    fn get_fixed_payee(env: Env) -> Result<Option<FixedPayee>, Error>;
+
+    /// Gets the commission charged on share transfers.
+    ///
+    /// ## Returns
+    ///
+    /// * `Option<TransferCommission>` - The transfer commission if it is set
+    fn get_transfer_commission(env: Env) -> Result<Option<TransferCommission>, Error>;
+
+    /// Gets the penalty charged for cancelling a listing too early.
+    ///
+    /// ## Returns
+    ///
+    /// * `Option<CancelPenalty>` - The cancel penalty if it is set
+    fn get_cancel_penalty(env: Env) -> Result<Option<CancelPenalty>, Error>;
+
+    /// Gets the fee charged for creating a listing.
+    ///
+    /// ## Returns
+    ///
+    /// * `Option<ListingFee>` - The listing fee if it is set
+    fn get_listing_fee(env: Env) -> Result<Option<ListingFee>, Error>;
+
+    /// Gets the recipients unused tokens can be transferred to.
+    ///
+    /// ## Returns
+    ///
+    /// * `Vec<Address>` - The allowed recipients, empty if any recipient is allowed
+    fn get_transfer_allowlist(env: Env) -> Result<Vec<Address>, Error>;
+
+    /// Gets the amount of a token earmarked for reinvestment.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_address` - The address of the token
+    ///
+    /// ## Returns
+    ///
+    /// * `i128` - The reinvestment pool of the token
+    fn get_reinvest_pool(env: Env, token_address: Address) -> Result<i128, Error>;
+
+    /// Gets the allocation of a shareholder for a token.
+    ///
+    /// ## Arguments
+    ///
+    /// * `shareholder` - The address of the shareholder
+    /// * `token` - The address of the token
+    ///
+    /// ## Returns
+    ///
+    /// * `i128` - The allocation of the shareholder for the token
+    fn get_allocation(env: Env, shareholder: Address, token: Address) -> Result<i128, Error>;
+
+    /// Lists the non-zero allocations of all the shareholders for a token.
+    ///
+    /// Only the first 100 allocations are returned.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token` - The address of the token
+    ///
+    /// ## Returns
+    ///
+    /// * `Vec<(Address, i128)>` - The shareholders with their allocation for the token
+    fn list_allocations(env: Env, token: Address) -> Result<Vec<(Address, i128)>, Error>;
+
+    /// Sums the allocations of the shareholders for a token and returns it with the tracked total.
+    ///
+    /// Both values match unless the allocation tracking drifted.
+    /// Allocations kept by removed shareholders are only part of the tracked total.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token` - The address of the token
+    ///
+    /// ## Returns
+    ///
+    /// * `(i128, i128)` - The sum of the shareholder allocations and the tracked total allocation
+    fn reconcile_allocations(env: Env, token: Address) -> Result<(i128, i128), Error>;
+
+    /// Checks that the shares of all the shareholders sum up to the share denominator.
+    ///
+    /// ## Returns
+    ///
+    /// * `bool` - True if the total shares are intact
+    fn verify_integrity(env: Env) -> Result<bool, Error>;
+
+    /// Gets the token balance that can be transferred out with `transfer_tokens`.
+    ///
+    /// Allocations, escrowed payments, accrued buy commission, vesting epochs and
+    /// the reinvestment pool are not part of the unused balance.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token` - The address of the token
+    ///
+    /// ## Returns
+    ///
+    /// * `i128` - The unused balance, 0 if there is none
+    fn get_unused_balance(env: Env, token: Address) -> Result<i128, Error>;
+
+    /// Gets the raw token balance held by the contract.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token` - The address of the token
+    ///
+    /// ## Returns
+    ///
+    /// * `i128` - The balance of the contract, including allocated and escrowed tokens
+    fn get_contract_balance(env: Env, token: Address) -> Result<i128, Error>;
+
+    // ========== Share Marketplace Functions ==========
+
+    /// Lists shares for sale
+    ///
+    /// A shareholder can list a portion or all of their shares for sale.
+    /// The shareholder must have enough shares to sell.
+    /// An existing listing of the seller is replaced.
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller (must authorize)
+    /// * `shares_amount` - The number of shares to sell
+    /// * `price_per_share` - The price per share in payment token units
+    /// * `payment_token` - The token address to receive as payment
+    fn list_shares_for_sale(
+        env: Env,
+        seller: Address,
+        shares_amount: i128,
+        price_per_share: i128,
+        payment_token: Address,
+    ) -> Result<(), Error>;
+
+    /// Creates a new share listing
+    ///
+    /// Same as `list_shares_for_sale`, but fails if the seller already has an active listing
+    /// instead of replacing it.
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller (must authorize)
+    /// * `shares_amount` - The number of shares to sell
+    /// * `price_per_share` - The price per share in payment token units
+    /// * `payment_token` - The token address to receive as payment
+    fn create_listing(
+        env: Env,
+        seller: Address,
+        shares_amount: i128,
+        price_per_share: i128,
+        payment_token: Address,
+    ) -> Result<(), Error>;
+
+    /// Cancels an active share listing
+    ///
+    /// Only the seller can cancel their own listing. Cancelling within the listing fee
+    /// refund window refunds a pro-rated part of the fee, see `set_listing_fee`.
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller (must authorize)
+    fn cancel_listing(env: Env, seller: Address) -> Result<(), Error>;
+
+    /// Cancels all of the seller's marketplace positions in one call
+    ///
+    /// Cancels the listing like `cancel_listing`, leaves the given pools and refunds the
+    /// escrowed purchases of the given buyers. Pools and escrows are not indexed by seller,
+    /// so they must be named. Positions that don't exist are skipped.
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller (must authorize)
+    /// * `pools` - The payment tokens and prices per share of the pools to leave, at most 10
+    /// * `buyers` - The buyers whose escrowed purchases are refunded, at most 10
+    fn cancel_all_my_orders(
+        env: Env,
+        seller: Address,
+        pools: Vec<(Address, i128)>,
+        buyers: Vec<Address>,
+    ) -> Result<(), Error>;
+
+    /// Buys shares from a seller
+    ///
+    /// Transfers payment to seller and shares to buyer.
+    /// Total shares remain 10,000 (shares transfer between parties).
+    ///
+    /// ## Arguments
+    ///
+    /// * `buyer` - The address of the buyer (must authorize)
+    /// * `seller` - The address of the seller
+    /// * `shares_amount` - The number of shares to buy
+    fn buy_shares(
+        env: Env,
+        buyer: Address,
+        seller: Address,
+        shares_amount: i128,
+    ) -> Result<(), Error>;
+
+    /// Buys shares from the listing with the given id
+    ///
+    /// Fails with `ListingChanged` if the seller relisted or the listing was removed
+    /// since the id was read, so the buyer never buys at terms they didn't see.
+    ///
+    /// ## Arguments
+    ///
+    /// * `buyer` - The address of the buyer (must authorize)
+    /// * `listing_id` - The id of the listing
+    /// * `shares_amount` - The number of shares to buy
+    fn buy_shares_by_id(
+        env: Env,
+        buyer: Address,
+        listing_id: u64,
+        shares_amount: i128,
+    ) -> Result<(), Error>;
+
+    /// Buys shares from several sellers at once
+    ///
+    /// Every order is validated before any of them executes,
+    /// so the whole batch fails if one order is invalid.
+    ///
+    /// ## Arguments
+    ///
+    /// * `buyer` - The address of the buyer (must authorize)
+    /// * `orders` - The sellers and the number of shares to buy from each
+    fn buy_shares_multi(
+        env: Env,
+        buyer: Address,
+        orders: Vec<(Address, i128)>,
+    ) -> Result<(), Error>;
+
+    /// Adds shares to the pooled listing of the payment token and price
+    ///
+    /// Any shareholder can contribute, the shares stay with them until a buyer fills them.
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller (must authorize)
+    /// * `payment_token` - The token accepted as payment
+    /// * `price_per_share` - The price per share of the pool
+    /// * `shares_amount` - The number of shares to contribute
+    fn contribute_to_pool(
+        env: Env,
+        seller: Address,
+        payment_token: Address,
+        price_per_share: i128,
+        shares_amount: i128,
+    ) -> Result<(), Error>;
+
+    /// Removes all of the seller's contributions from the pooled listing
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller (must authorize)
+    /// * `payment_token` - The token accepted as payment
+    /// * `price_per_share` - The price per share of the pool
+    fn leave_pool(
+        env: Env,
+        seller: Address,
+        payment_token: Address,
+        price_per_share: i128,
+    ) -> Result<(), Error>;
+
+    /// Buys shares from the pooled listing of the payment token and price
+    ///
+    /// Contributions are filled oldest first, each contributor is paid for the shares
+    /// filled from them. The whole order fails if the pool cannot fill it.
+    ///
+    /// ## Arguments
+    ///
+    /// * `buyer` - The address of the buyer (must authorize)
+    /// * `payment_token` - The token accepted as payment
+    /// * `price_per_share` - The price per share of the pool
+    /// * `shares_amount` - The number of shares to buy
+    fn buy_from_pool(
+        env: Env,
+        buyer: Address,
+        payment_token: Address,
+        price_per_share: i128,
+        shares_amount: i128,
+    ) -> Result<(), Error>;
+
+    /// Restricts who can buy from the seller's listing
+    ///
+    /// An empty list makes the listing public again.
+    /// The whitelist is kept when the seller replaces their listing.
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller (must authorize)
+    /// * `allowed_buyers` - The buyers allowed to buy from the listing
+    fn set_allowed_buyers(
+        env: Env,
+        seller: Address,
+        allowed_buyers: Vec<Address>,
+    ) -> Result<(), Error>;
+
+    /// Sets whether the proceeds of the seller's listing are credited to their allocation
+    ///
+    /// When enabled, the payment minus the commission stays in the contract and is added to
+    /// the seller's allocation for the payment token instead of being transferred to them.
+    /// The setting is kept when the seller replaces their listing.
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller (must authorize)
+    /// * `credit_proceeds` - Whether the proceeds are credited to the seller's allocation
+    fn set_credit_proceeds(
+        env: Env,
+        seller: Address,
+        credit_proceeds: bool,
+    ) -> Result<(), Error>;
+
+    /// Sets the ledger after which the seller's listing can no longer be bought
+    ///
+    /// The expiration is kept when the seller replaces their listing.
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller (must authorize)
+    /// * `expiration_ledger` - The last ledger the listing can be bought in, 0 to never expire
+    fn set_listing_expiration(
+        env: Env,
+        seller: Address,
+        expiration_ledger: u32,
+    ) -> Result<(), Error>;
+
+    /// Sets the ledger from which the seller's listing can be bought
+    ///
+    /// The delay is kept when the seller replaces their listing.
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller (must authorize)
+    /// * `activation_ledger` - The first ledger the listing can be bought in, 0 to activate right away
+    fn set_listing_activation(
+        env: Env,
+        seller: Address,
+        activation_ledger: u32,
+    ) -> Result<(), Error>;
+
+    /// Emits the `listing_active` event of a delayed listing once its activation ledger is reached
+    ///
+    /// Anyone can call this, e.g. a keeper, the event is only emitted once per delay.
+    /// Otherwise the first buy of the listing emits it.
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller
+    fn activate_listing(env: Env, seller: Address) -> Result<(), Error>;
+
+    /// Sets how many shares a single buyer can buy from the seller's listing across all of their buys
+    ///
+    /// The cap is kept when the seller replaces their listing.
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller (must authorize)
+    /// * `max_per_buyer` - The maximum shares per buyer, 0 for no cap
+    fn set_max_per_buyer(env: Env, seller: Address, max_per_buyer: i128) -> Result<(), Error>;
+
+    /// Removes expired listings from the marketplace
+    ///
+    /// Anyone can call this, only listings that already expired are removed.
+    ///
+    /// ## Arguments
+    ///
+    /// * `limit` - The maximum number of active listings to scan
+    ///
+    /// ## Returns
+    ///
+    /// * `u32` - The number of listings removed
+    fn prune_expired_listings(env: Env, limit: u32) -> Result<u32, Error>;
+
+    /// Escrows the payment for a purchase of listed shares
+    ///
+    /// The total price at the listing's current price is moved from the buyer into the contract.
+    /// Shares only move once the seller confirms with `release_purchase`.
+    /// The buyer can reclaim the funds with `refund_purchase` once the escrow expires.
+    ///
+    /// ## Arguments
+    ///
+    /// * `buyer` - The address of the buyer (must authorize)
+    /// * `seller` - The address of the seller
+    /// * `shares_amount` - The number of shares to buy
+    fn deposit_for_purchase(
+        env: Env,
+        buyer: Address,
+        seller: Address,
+        shares_amount: i128,
+    ) -> Result<(), Error>;
+
+    /// Completes an escrowed purchase
+    ///
+    /// Transfers the shares to the buyer and releases the escrowed funds to the seller,
+    /// minus the buy commission.
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller (must authorize)
+    /// * `buyer` - The address of the buyer
+    fn release_purchase(env: Env, seller: Address, buyer: Address) -> Result<(), Error>;
+
+    /// Refunds an expired escrowed purchase to the buyer
+    ///
+    /// The full escrowed total is returned. The escrow is removed before the transfer,
+    /// so a purchase can only be refunded once and never after it was released.
+    ///
+    /// ## Arguments
+    ///
+    /// * `buyer` - The address of the buyer (must authorize)
+    /// * `seller` - The address of the seller
+    fn refund_purchase(env: Env, buyer: Address, seller: Address) -> Result<(), Error>;
+
+    /// Gets a specific sale listing
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller
+    ///
+    /// ## Returns
+    ///
+    /// * `Option<SaleListingDataKey>` - The listing if it exists
+    fn get_listing(env: Env, seller: Address) -> Result<Option<SaleListingDataKey>, Error>;
+
+    /// Gets the sale listings of several sellers
+    ///
+    /// ## Arguments
+    ///
+    /// * `sellers` - The addresses of the sellers, at most 50
+    ///
+    /// ## Returns
+    ///
+    /// * `Vec<Option<SaleListingDataKey>>` - The listing of every seller in order, if it exists
+    fn get_listings(
+        env: Env,
+        sellers: Vec<Address>,
+    ) -> Result<Vec<Option<SaleListingDataKey>>, Error>;
+
+    /// Gets how far a seller's listing has been filled
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller
+    ///
+    /// ## Returns
+    ///
+    /// * `Option<(i128, i128)>` - The originally listed and the remaining shares if the listing exists
+    fn get_listing_fill(env: Env, seller: Address) -> Result<Option<(i128, i128)>, Error>;
+
+    /// Gets the payment token of a seller's listing
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller
+    ///
+    /// ## Returns
+    ///
+    /// * `Option<Address>` - The payment token if the listing exists
+    fn get_listing_payment_token(env: Env, seller: Address) -> Result<Option<Address>, Error>;
+
+    /// Gets the total value of a seller's listing
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller
+    ///
+    /// ## Returns
+    ///
+    /// * `Option<i128>` - The shares for sale times the price per share if the listing exists
+    fn get_listing_total(env: Env, seller: Address) -> Result<Option<i128>, Error>;
+
+    /// Lists all active share sales
+    ///
+    /// ## Returns
+    ///
+    /// * `Vec<SaleListingDataKey>` - All active listings
+    fn list_all_sales(env: Env) -> Result<Vec<SaleListingDataKey>, Error>;
+
+    /// Lists the active share sales together with the shares each seller currently holds
+    ///
+    /// Only the first 50 listings are returned.
+    ///
+    /// ## Returns
+    ///
+    /// * `Vec<(SaleListingDataKey, i128)>` - The listings with the current shares of their seller
+    fn list_all_sales_detailed(env: Env) -> Result<Vec<(SaleListingDataKey, i128)>, Error>;
+
+    /// Lists the active share sales priced in a token
+    ///
+    /// ## Arguments
+    ///
+    /// * `token` - The address of the payment token
+    ///
+    /// ## Returns
+    ///
+    /// * `Vec<SaleListingDataKey>` - The active listings priced in the token
+    fn list_sales_by_token(env: Env, token: Address) -> Result<Vec<SaleListingDataKey>, Error>;
+
+    /// Lists the sellers whose listings expired but were not pruned yet
+    ///
+    /// Lets keepers find the listings to remove with `prune_expired_listings`
+    /// without scanning the marketplace themselves.
+    ///
+    /// ## Arguments
+    ///
+    /// * `limit` - The maximum number of sellers to return
+    ///
+    /// ## Returns
+    ///
+    /// * `Vec<Address>` - The sellers of expired listings, in active listing order
+    fn list_expired_listings(env: Env, limit: u32) -> Result<Vec<Address>, Error>;
+
+    /// Gets the listing price history of a seller
+    ///
+    /// Only the most recent listings are kept.
+    ///
+    /// ## Arguments
+    ///
+    /// * `seller` - The address of the seller
+    ///
+    /// ## Returns
+    ///
+    /// * `Vec<(u32, i128)>` - The ledger and price per share of each listing, oldest first
+    fn get_price_history(env: Env, seller: Address) -> Result<Vec<(u32, i128)>, Error>;
+
+    /// Gets how much a buyer has spent on share purchases in a token
+    ///
+    /// ## Arguments
+    ///
+    /// * `buyer` - The address of the buyer
+    /// * `token` - The address of the payment token
+    ///
+    /// ## Returns
+    ///
+    /// * `i128` - The accumulated total price of the buyer's purchases
+    fn get_buyer_spent(env: Env, buyer: Address, token: Address) -> Result<i128, Error>;
+
+    /// Gets an escrowed purchase
+    ///
+    /// ## Arguments
+    ///
+    /// * `buyer` - The address of the buyer
+    /// * `seller` - The address of the seller
+    ///
+    /// ## Returns
+    ///
+    /// * `Option<EscrowDataKey>` - The escrow if it exists
+    fn get_escrow(env: Env, buyer: Address, seller: Address) -> Result<Option<EscrowDataKey>, Error>;
+
+    // ========== Commission Functions ==========
+
+    /// **COMMISSION RECIPIENT ONLY FUNCTION**
+    ///
+    /// Updates the commission recipient address.
+    ///
+    /// Only the current commission recipient can call this function.
+    /// The contract itself cannot be the recipient and fails with `InvalidRecipient`.
+    ///
+    /// ## Arguments
+    ///
+    /// * `new_recipient` - The new address to receive commissions
+    fn set_commission_recipient(env: Env, new_recipient: Address) -> Result<(), Error>;
+
+    /// **COMMISSION RECIPIENT ONLY FUNCTION**
+    ///
+    /// Updates the buy commission rate (applied on share purchases).
+    ///
+    /// Only the current commission recipient can call this function.
+    /// Rate is in basis points (e.g., 150 = 1.5%).
+    /// Maximum rate is 5000 (50%).
+    ///
+    /// ## Arguments
+    ///
+    /// * `new_rate_bps` - The new commission rate in basis points
+    fn set_buy_commission_rate(env: Env, new_rate_bps: i128) -> Result<(), Error>;
+
+    /// **COMMISSION RECIPIENT ONLY FUNCTION**
+    ///
+    /// Updates the distribution commission rate (applied on token distributions).
+    ///
+    /// Only the current commission recipient can call this function.
+    /// Rate is in basis points (e.g., 50 = 0.5%).
+    /// Maximum rate is 5000 (50%).
+    ///
+    /// ## Arguments
+    ///
+    /// * `new_rate_bps` - The new commission rate in basis points
+    fn set_distribution_commission_rate(env: Env, new_rate_bps: i128) -> Result<(), Error>;
+
+    /// **COMMISSION RECIPIENT ONLY FUNCTION**
+    ///
+    /// Enables or disables accruing buy commissions in the contract.
+    ///
+    /// When enabled, buy commissions stay in the contract until the recipient sweeps them
+    /// with `sweep_buy_commission`, instead of being transferred on every purchase.
+    /// Disabled by default.
+    ///
+    /// ## Arguments
+    ///
+    /// * `enabled` - Whether buy commissions are accrued
+    fn set_defer_buy_commission(env: Env, enabled: bool) -> Result<(), Error>;
+
+    /// **COMMISSION RECIPIENT ONLY FUNCTION**
+    ///
+    /// Transfers the buy commission accrued for the payment token to the recipient, along
+    /// with the listing fees that can no longer be refunded.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_address` - The address of the payment token
+    fn sweep_buy_commission(env: Env, token_address: Address) -> Result<(), Error>;
+
+    /// Gets the buy commission accrued for the payment token.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token_address` - The address of the payment token
+    ///
+    /// ## Returns
+    ///
+    /// * `i128` - The accrued buy commission
+    fn get_accrued_buy_commission(env: Env, token_address: Address) -> Result<i128, Error>;
+
+    /// Gets the current commission configuration.
+    ///
+    /// ## Returns
+    ///
+    /// * `CommissionConfig` - The current commission configuration
+    fn get_commission_config(env: Env) -> Result<CommissionConfig, Error>;
+
+    /// Gets the commission earned in a token, by source.
+    ///
+    /// Commission left in the contract because the recipient is the contract itself
+    /// is not counted.
+    ///
+    /// ## Arguments
+    ///
+    /// * `token` - The address of the token
+    ///
+    /// ## Returns
+    ///
+    /// * `(i128, i128, i128)` - The commission from buys, from distributions and the total
+    fn get_commission_summary(env: Env, token: Address) -> Result<(i128, i128, i128), Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Sets the minimum commission rates the commission recipient cannot go below.
+    ///
+    /// Rates are in basis points, maximum is 5000 (50%).
+    /// Current rates below a new floor are kept until the recipient updates them.
+    ///
+    /// ## Arguments
+    ///
+    /// * `buy_rate_bps` - The minimum buy commission rate in basis points
+    /// * `distribution_rate_bps` - The minimum distribution commission rate in basis points
+    fn set_commission_floor(
+        env: Env,
+        buy_rate_bps: i128,
+        distribution_rate_bps: i128,
+    ) -> Result<(), Error>;
+
+    /// Gets the minimum commission rates set by the admin.
+    ///
+    /// ## Returns
+    ///
+    /// * `CommissionFloor` - The current commission floor
+    fn get_commission_floor(env: Env) -> Result<CommissionFloor, Error>;
+
+    /// **ADMIN ONLY FUNCTION**
+    ///
+    /// Exempts the address from the buy commission, or stops exempting it.
+    ///
+    /// Sales where either the seller or the buyer is exempt don't pay the buy commission,
+    /// the seller receives the full price.
+    ///
+    /// ## Arguments
+    ///
+    /// * `address` - The address of the seller or buyer
+    /// * `exempt` - Whether the address is exempt
+    fn set_buy_commission_exempt(env: Env, address: Address, exempt: bool) -> Result<(), Error>;
+
+    /// Checks if the address is exempt from the buy commission.
+    ///
+    /// ## Arguments
+    ///
+    /// * `address` - The address to check
+    ///
+    /// ## Returns
+    ///
+    /// * `bool` - True if the address is exempt
+    fn is_buy_commission_exempt(env: Env, address: Address) -> Result<bool, Error>;
+}
+
+#[contract]
+pub struct Splitter;
+
+#[contractimpl]
+impl SplitterTrait for Splitter {
+    // ========== Execute Functions ==========
+
+    fn init(
+        env: Env,
+        admin: Address,
+        shares: Vec<ShareDataKey>,
+        mutable: bool,
+    ) -> Result<(), Error> {
+        execute::init(env, admin, shares, mutable, DEFAULT_SHARE_DENOMINATOR, None)
+    }
+
+    fn init_with_share_denominator(
+        env: Env,
+        admin: Address,
+        shares: Vec<ShareDataKey>,
+        mutable: bool,
+        share_denominator: i128,
+    ) -> Result<(), Error> {
+        execute::init(env, admin, shares, mutable, share_denominator, None)
+    }
+
+    fn init_with_commission(
+        env: Env,
+        admin: Address,
+        shares: Vec<ShareDataKey>,
+        mutable: bool,
+        commission: CommissionConfig,
+    ) -> Result<(), Error> {
+        execute::init(
+            env,
+            admin,
+            shares,
+            mutable,
+            DEFAULT_SHARE_DENOMINATOR,
+            Some(commission),
+        )
+    }
+
+    fn transfer_tokens(
+        env: Env,
+        token_address: Address,
+        recipient: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        execute::transfer_tokens(env, token_address, recipient, amount)
+    }
+
+    fn transfer_all_unused(
+        env: Env,
+        token_address: Address,
+        recipient: Address,
+    ) -> Result<(), Error> {
+        execute::transfer_all_unused(env, token_address, recipient)
+    }
+
+    fn distribute_tokens(env: Env, token_address: Address) -> Result<(), Error> {
+        execute::distribute_tokens(env, token_address, None)
+    }
+
+    fn distribute_as(
+        env: Env,
+        distributor: Address,
+        token_address: Address,
+    ) -> Result<(), Error> {
+        execute::distribute_tokens(env, token_address, Some(distributor))
+    }
+
+    fn distribute_amount(env: Env, token_address: Address, amount: i128) -> Result<(), Error> {
+        execute::distribute_amount(env, token_address, amount)
+    }
+
+    fn distribute_time_weighted(env: Env, token_address: Address) -> Result<(), Error> {
+        execute::distribute_time_weighted(env, token_address)
+    }
+
+    fn distribute_to_subset(
+        env: Env,
+        token_address: Address,
+        subset: Vec<Address>,
+        amount: i128,
+    ) -> Result<(), Error> {
+        execute::distribute_to_subset(env, token_address, subset, amount)
+    }
+
+    fn start_epoch(
+        env: Env,
+        token_address: Address,
+        amount: i128,
+        duration_ledgers: u32,
+    ) -> Result<(), Error> {
+        execute::start_epoch(env, token_address, amount, duration_ledgers)
+    }
+
+    fn claim_epoch(env: Env, shareholder: Address, token_address: Address) -> Result<i128, Error> {
+        execute::claim_epoch(env, shareholder, token_address)
+    }
+
+    fn update_shares(
+        env: Env,
+        shares: Vec<ShareDataKey>,
+        clear_removed_allocations: bool,
+        force: bool,
+    ) -> Result<(), Error> {
+        execute::update_shares(env, shares, clear_removed_allocations, force)
+    }
+
+    fn update_shares_with_remainder(
+        env: Env,
+        changes: Vec<ShareDataKey>,
+        remainder: Address,
+    ) -> Result<(), Error> {
+        execute::update_shares_with_remainder(env, changes, remainder)
+    }
 
     fn lock_contract(env: Env) -> Result<(), Error> {
         execute::lock_contract(env)
     }
 
+    fn lock_shares(
+        env: Env,
+        shareholder: Address,
+        amount: i128,
+        unlock_ledger: u32,
+    ) -> Result<(), Error> {
+        execute::lock_shares(env, shareholder, amount, unlock_ledger)
+    }
+
+    fn claim_initial_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        execute::claim_initial_admin(env, new_admin)
+    }
+
+    fn migrate(env: Env) -> Result<(), Error> {
+        execute::migrate(env)
+    }
+
+    fn migrate_shareholder(env: Env, old: Address, new: Address) -> Result<(), Error> {
+        execute::migrate_shareholder(env, old, new)
+    }
+
+    fn rebuild_shareholders(env: Env) -> Result<u32, Error> {
+        execute::rebuild_shareholders(env)
+    }
+
+    fn set_min_shareholders(env: Env, min_shareholders: u32) -> Result<(), Error> {
+        execute::set_min_shareholders(env, min_shareholders)
+    }
+
+    fn set_treasury(env: Env, treasury: Address) -> Result<(), Error> {
+        execute::set_treasury(env, treasury)
+    }
+
+    fn set_transfer_allowlist(env: Env, recipients: Vec<Address>) -> Result<(), Error> {
+        execute::set_transfer_allowlist(env, recipients)
+    }
+
+    fn set_max_active_listings(env: Env, max_active_listings: u32) -> Result<(), Error> {
+        execute::set_max_active_listings(env, max_active_listings)
+    }
+
+    fn set_max_allocation_per_dist(env: Env, max_allocation: i128) -> Result<(), Error> {
+        execute::set_max_allocation_per_dist(env, max_allocation)
+    }
+
+    fn set_reinvest_bps(env: Env, bps: i128) -> Result<(), Error> {
+        execute::set_reinvest_bps(env, bps)
+    }
+
+    fn withdraw_reinvest_pool(
+        env: Env,
+        token_address: Address,
+        recipient: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        execute::withdraw_reinvest_pool(env, token_address, recipient, amount)
+    }
+
+    fn set_emit_per_shareholder_events(env: Env, enabled: bool) -> Result<(), Error> {
+        execute::set_emit_per_shareholder_events(env, enabled)
+    }
+
+    fn set_cancel_penalty(env: Env, bps: i128, min_age_ledgers: u32) -> Result<(), Error> {
+        execute::set_cancel_penalty(env, bps, min_age_ledgers)
+    }
+
+    fn set_listing_fee(env: Env, bps: i128, refund_window_ledgers: u32) -> Result<(), Error> {
+        execute::set_listing_fee(env, bps, refund_window_ledgers)
+    }
+
+    fn set_seller_blocked(env: Env, seller: Address, blocked: bool) -> Result<(), Error> {
+        execute::set_seller_blocked(env, seller, blocked)
+    }
+
+    fn freeze_shareholder(env: Env, shareholder: Address, frozen: bool) -> Result<(), Error> {
+        execute::freeze_shareholder(env, shareholder, frozen)
+    }
+
+    fn set_distribution_optout(env: Env, shareholder: Address, opted_out: bool) -> Result<(), Error> {
+        execute::set_distribution_optout(env, shareholder, opted_out)
+    }
+
+    fn set_identity(env: Env, address: Address, identity: u64) -> Result<(), Error> {
+        execute::set_identity(env, address, identity)
+    }
+
+    fn set_distributor(env: Env, distributor: Address, enabled: bool) -> Result<(), Error> {
+        execute::set_distributor(env, distributor, enabled)
+    }
+
+    fn set_share_lot_size(env: Env, share_lot_size: i128) -> Result<(), Error> {
+        execute::set_share_lot_size(env, share_lot_size)
+    }
+
+    fn set_transfer_cooldown(env: Env, cooldown_ledgers: u32) -> Result<(), Error> {
+        execute::set_transfer_cooldown(env, cooldown_ledgers)
+    }
+
+    fn set_recipient_pays_commission(env: Env, enabled: bool) -> Result<(), Error> {
+        execute::set_recipient_pays_commission(env, enabled)
+    }
+
+    fn set_distribution_cooldown(
+        env: Env,
+        token_address: Address,
+        cooldown_ledgers: u32,
+    ) -> Result<(), Error> {
+        execute::set_distribution_cooldown(env, token_address, cooldown_ledgers)
+    }
+
+    fn set_fixed_payee(env: Env, payee: Address, bps: i128) -> Result<(), Error> {
+        execute::set_fixed_payee(env, payee, bps)
+    }
+
+    fn set_transfer_commission(
+        env: Env,
+        token: Address,
+        transfer_commission_bps: i128,
+    ) -> Result<(), Error> {
+        execute::set_transfer_commission(env, token, transfer_commission_bps)
+    }
+
+    fn set_min_claimable(
+        env: Env,
+        token_address: Address,
+        min_claimable: i128,
+    ) -> Result<(), Error> {
+        execute::set_min_claimable(env, token_address, min_claimable)
+    }
+
+    fn set_min_seller_proceeds(
+        env: Env,
+        token_address: Address,
+        min_proceeds: i128,
+    ) -> Result<(), Error> {
+        execute::set_min_seller_proceeds(env, token_address, min_proceeds)
+    }
+
     fn withdraw_allocation(
         env: Env,
         token_address: Address,
@@ -308,6 +1816,32 @@ impl SplitterTrait for Splitter {
         execute::withdraw_allocation(env, token_address, shareholder, amount)
     }
 
+    fn approve_withdraw_operator(
+        env: Env,
+        shareholder: Address,
+        operator: Address,
+        token_address: Address,
+        allowance: i128,
+    ) -> Result<(), Error> {
+        execute::approve_withdraw_operator(env, shareholder, operator, token_address, allowance)
+    }
+
+    fn withdraw_allocations_for(
+        env: Env,
+        operator: Address,
+        claims: Vec<(Address, Address, i128)>,
+    ) -> Result<(), Error> {
+        execute::withdraw_allocations_for(env, operator, claims)
+    }
+
+    fn push_allocation(
+        env: Env,
+        token_address: Address,
+        shareholder: Address,
+    ) -> Result<i128, Error> {
+        execute::push_allocation(env, token_address, shareholder)
+    }
+
     fn transfer_shares(
         env: Env,
         from: Address,
@@ -317,24 +1851,108 @@ impl SplitterTrait for Splitter {
         execute::transfer_shares(env, from, to, amount)
     }
 
+    fn consolidate_shares(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        execute::consolidate_shares(env, from, to, amount)
+    }
+
     // ========== Query Functions ==========
 
     fn get_share(env: Env, shareholder: Address) -> Result<Option<i128>, Error> {
         query::get_share(env, shareholder)
     }
 
+    fn get_share_at(env: Env, shareholder: Address, ledger: u32) -> Result<i128, Error> {
+        query::get_share_at(env, shareholder, ledger)
+    }
+
+    fn get_effective_share(env: Env, shareholder: Address) -> Result<(i128, i128, i128), Error> {
+        query::get_effective_share(env, shareholder)
+    }
+
+    fn get_share_or_zero(env: Env, shareholder: Address) -> Result<i128, Error> {
+        query::get_share_or_zero(env, shareholder)
+    }
+
+    fn is_shareholder(env: Env, address: Address) -> Result<bool, Error> {
+        query::is_shareholder(env, address)
+    }
+
     fn list_shares(env: Env) -> Result<Vec<ShareDataKey>, Error> {
         query::list_shares(env)
     }
 
+    fn list_shares_sorted(env: Env) -> Result<Vec<ShareDataKey>, Error> {
+        query::list_shares_sorted(env)
+    }
+
+    fn list_shares_with_allocation(
+        env: Env,
+        token: Address,
+    ) -> Result<Vec<(Address, i128, i128)>, Error> {
+        query::list_shares_with_allocation(env, token)
+    }
+
     fn get_config(env: Env) -> Result<ConfigDataKey, Error> {
         query::get_config(env)
     }
 
+    fn get_version(env: Env) -> Result<u32, Error> {
+        query::get_version(env)
+    }
+
+    fn get_fixed_payee(env: Env) -> Result<Option<FixedPayee>, Error> {
+        Ok(FixedPayee::get(&env))
+    }
+
+    fn get_transfer_commission(env: Env) -> Result<Option<TransferCommission>, Error> {
+        Ok(TransferCommission::get(&env))
+    }
+
+    fn get_cancel_penalty(env: Env) -> Result<Option<CancelPenalty>, Error> {
+        Ok(CancelPenalty::get(&env))
+    }
+
+    fn get_listing_fee(env: Env) -> Result<Option<ListingFee>, Error> {
+        Ok(ListingFee::get(&env))
+    }
+
+    fn get_transfer_allowlist(env: Env) -> Result<Vec<Address>, Error> {
+        Ok(SettingsDataKey::get_transfer_allowlist(&env))
+    }
+
+    fn get_reinvest_pool(env: Env, token_address: Address) -> Result<i128, Error> {
+        Ok(SettingsDataKey::get_reinvest_pool(&env, &token_address))
+    }
+
     fn get_allocation(env: Env, shareholder: Address, token: Address) -> Result<i128, Error> {
         query::get_allocation(env, shareholder, token)
     }
 
+    fn list_allocations(env: Env, token: Address) -> Result<Vec<(Address, i128)>, Error> {
+        query::list_allocations(env, token)
+    }
+
+    fn reconcile_allocations(env: Env, token: Address) -> Result<(i128, i128), Error> {
+        query::reconcile_allocations(env, token)
+    }
+
+    fn verify_integrity(env: Env) -> Result<bool, Error> {
+        query::verify_integrity(env)
+    }
+
+    fn get_unused_balance(env: Env, token: Address) -> Result<i128, Error> {
+        query::get_unused_balance(env, token)
+    }
+
+    fn get_contract_balance(env: Env, token: Address) -> Result<i128, Error> {
+        query::get_contract_balance(env, token)
+    }
+
     // ========== Share Marketplace Functions ==========
 
     fn list_shares_for_sale(
@@ -347,10 +1965,29 @@ impl SplitterTrait for Splitter {
         execute::list_shares_for_sale(env, seller, shares_amount, price_per_share, payment_token)
     }
 
+    fn create_listing(
+        env: Env,
+        seller: Address,
+        shares_amount: i128,
+        price_per_share: i128,
+        payment_token: Address,
+    ) -> Result<(), Error> {
+        execute::create_listing(env, seller, shares_amount, price_per_share, payment_token)
+    }
+
     fn cancel_listing(env: Env, seller: Address) -> Result<(), Error> {
         execute::cancel_listing(env, seller)
     }
 
+    fn cancel_all_my_orders(
+        env: Env,
+        seller: Address,
+        pools: Vec<(Address, i128)>,
+        buyers: Vec<Address>,
+    ) -> Result<(), Error> {
+        execute::cancel_all_my_orders(env, seller, pools, buyers)
+    }
+
     fn buy_shares(
         env: Env,
         buyer: Address,
@@ -360,14 +1997,164 @@ impl SplitterTrait for Splitter {
         execute::buy_shares(env, buyer, seller, shares_amount)
     }
 
+    fn buy_shares_by_id(
+        env: Env,
+        buyer: Address,
+        listing_id: u64,
+        shares_amount: i128,
+    ) -> Result<(), Error> {
+        execute::buy_shares_by_id(env, buyer, listing_id, shares_amount)
+    }
+
+    fn buy_shares_multi(
+        env: Env,
+        buyer: Address,
+        orders: Vec<(Address, i128)>,
+    ) -> Result<(), Error> {
+        execute::buy_shares_multi(env, buyer, orders)
+    }
+
+    fn contribute_to_pool(
+        env: Env,
+        seller: Address,
+        payment_token: Address,
+        price_per_share: i128,
+        shares_amount: i128,
+    ) -> Result<(), Error> {
+        execute::contribute_to_pool(env, seller, payment_token, price_per_share, shares_amount)
+    }
+
+    fn leave_pool(
+        env: Env,
+        seller: Address,
+        payment_token: Address,
+        price_per_share: i128,
+    ) -> Result<(), Error> {
+        execute::leave_pool(env, seller, payment_token, price_per_share)
+    }
+
+    fn buy_from_pool(
+        env: Env,
+        buyer: Address,
+        payment_token: Address,
+        price_per_share: i128,
+        shares_amount: i128,
+    ) -> Result<(), Error> {
+        execute::buy_from_pool(env, buyer, payment_token, price_per_share, shares_amount)
+    }
+
+    fn set_allowed_buyers(
+        env: Env,
+        seller: Address,
+        allowed_buyers: Vec<Address>,
+    ) -> Result<(), Error> {
+        execute::set_allowed_buyers(env, seller, allowed_buyers)
+    }
+
+    fn set_credit_proceeds(
+        env: Env,
+        seller: Address,
+        credit_proceeds: bool,
+    ) -> Result<(), Error> {
+        execute::set_credit_proceeds(env, seller, credit_proceeds)
+    }
+
+    fn set_listing_expiration(
+        env: Env,
+        seller: Address,
+        expiration_ledger: u32,
+    ) -> Result<(), Error> {
+        execute::set_listing_expiration(env, seller, expiration_ledger)
+    }
+
+    fn set_listing_activation(
+        env: Env,
+        seller: Address,
+        activation_ledger: u32,
+    ) -> Result<(), Error> {
+        execute::set_listing_activation(env, seller, activation_ledger)
+    }
+
+    fn activate_listing(env: Env, seller: Address) -> Result<(), Error> {
+        execute::activate_listing(env, seller)
+    }
+
+    fn set_max_per_buyer(env: Env, seller: Address, max_per_buyer: i128) -> Result<(), Error> {
+        execute::set_max_per_buyer(env, seller, max_per_buyer)
+    }
+
+    fn prune_expired_listings(env: Env, limit: u32) -> Result<u32, Error> {
+        execute::prune_expired_listings(env, limit)
+    }
+
+    fn deposit_for_purchase(
+        env: Env,
+        buyer: Address,
+        seller: Address,
+        shares_amount: i128,
+    ) -> Result<(), Error> {
+        execute::deposit_for_purchase(env, buyer, seller, shares_amount)
+    }
+
+    fn release_purchase(env: Env, seller: Address, buyer: Address) -> Result<(), Error> {
+        execute::release_purchase(env, seller, buyer)
+    }
+
+    fn refund_purchase(env: Env, buyer: Address, seller: Address) -> Result<(), Error> {
+        execute::refund_purchase(env, buyer, seller)
+    }
+
+    fn get_listings(
+        env: Env,
+        sellers: Vec<Address>,
+    ) -> Result<Vec<Option<SaleListingDataKey>>, Error> {
+        query::get_listings(env, sellers)
+    }
+
     fn get_listing(env: Env, seller: Address) -> Result<Option<SaleListingDataKey>, Error> {
         query::get_listing(env, seller)
     }
 
+    fn get_listing_fill(env: Env, seller: Address) -> Result<Option<(i128, i128)>, Error> {
+        query::get_listing_fill(env, seller)
+    }
+
+    fn get_listing_payment_token(env: Env, seller: Address) -> Result<Option<Address>, Error> {
+        query::get_listing_payment_token(env, seller)
+    }
+
+    fn get_listing_total(env: Env, seller: Address) -> Result<Option<i128>, Error> {
+        query::get_listing_total(env, seller)
+    }
+
     fn list_all_sales(env: Env) -> Result<Vec<SaleListingDataKey>, Error> {
         query::list_all_sales(env)
     }
 
+    fn list_all_sales_detailed(env: Env) -> Result<Vec<(SaleListingDataKey, i128)>, Error> {
+        query::list_all_sales_detailed(env)
+    }
+
+    fn list_sales_by_token(env: Env, token: Address) -> Result<Vec<SaleListingDataKey>, Error> {
+        query::list_sales_by_token(env, token)
+    }
+
+    fn list_expired_listings(env: Env, limit: u32) -> Result<Vec<Address>, Error> {
+        query::list_expired_listings(env, limit)
+    }
+
+    fn get_price_history(env: Env, seller: Address) -> Result<Vec<(u32, i128)>, Error> {
+        query::get_price_history(env, seller)
+    }
+
+    fn get_buyer_spent(env: Env, buyer: Address, token: Address) -> Result<i128, Error> {
+        query::get_buyer_spent(env, buyer, token)
+    }
+
+    fn get_escrow(env: Env, buyer: Address, seller: Address) -> Result<Option<EscrowDataKey>, Error> {
+        query::get_escrow(env, buyer, seller)
+    }
+
     // ========== Commission Functions ==========
 
     fn set_commission_recipient(env: Env, new_recipient: Address) -> Result<(), Error> {
@@ -382,7 +2169,43 @@ impl SplitterTrait for Splitter {
         CommissionConfig::set_distribution_rate(&env, new_rate_bps)
     }
 
+    fn set_defer_buy_commission(env: Env, enabled: bool) -> Result<(), Error> {
+        CommissionConfig::set_defer_buy_commission(&env, enabled)
+    }
+
+    fn sweep_buy_commission(env: Env, token_address: Address) -> Result<(), Error> {
+        execute::sweep_buy_commission(env, token_address)
+    }
+
+    fn get_accrued_buy_commission(env: Env, token_address: Address) -> Result<i128, Error> {
+        Ok(CommissionConfig::get_accrued_buy_commission(&env, &token_address))
+    }
+
     fn get_commission_config(env: Env) -> Result<CommissionConfig, Error> {
         Ok(CommissionConfig::get(&env))
     }
+
+    fn get_commission_summary(env: Env, token: Address) -> Result<(i128, i128, i128), Error> {
+        query::get_commission_summary(env, token)
+    }
+
+    fn set_commission_floor(
+        env: Env,
+        buy_rate_bps: i128,
+        distribution_rate_bps: i128,
+    ) -> Result<(), Error> {
+        execute::set_commission_floor(env, buy_rate_bps, distribution_rate_bps)
+    }
+
+    fn get_commission_floor(env: Env) -> Result<CommissionFloor, Error> {
+        Ok(CommissionFloor::get(&env))
+    }
+
+    fn set_buy_commission_exempt(env: Env, address: Address, exempt: bool) -> Result<(), Error> {
+        execute::set_buy_commission_exempt(env, address, exempt)
+    }
+
+    fn is_buy_commission_exempt(env: Env, address: Address) -> Result<bool, Error> {
+        query::is_buy_commission_exempt(env, address)
+    }
 }
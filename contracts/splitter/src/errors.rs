@@ -1,6 +1,11 @@
 use soroban_sdk::contracterror;
 
-#[contracterror]
+/// Errors of the contract.
+///
+/// Contract specs hold at most 50 error cases, so this enum isn't exported itself.
+/// Its cases are exported by `spec::Error` up to code 50 and by `spec::ExtError` from 51 on.
+/// New errors take the next free code, codes of removed errors are never reused.
+#[contracterror(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum Error {
@@ -35,4 +40,151 @@ pub enum Error {
     DuplicateShareholder = 23,
     // Commission errors
     InvalidCommissionRate = 24,
+    // Shareholder set errors
+    TooFewShareholders = 25,
+    // Share marketplace errors
+    ListingAlreadyExists = 26,
+    // Escrow errors
+    NoEscrow = 27,
+    EscrowActive = 28,
+    // Share marketplace errors
+    BuyerNotWhitelisted = 29,
+    // Input size errors
+    TooManyItems = 30,
+    // Shareholder set errors
+    ShareholderExists = 31,
+    NoShareholders = 32,
+    // Share marketplace errors
+    TooManyListings = 33,
+    // Share validation errors
+    InvalidShareGranularity = 34,
+    // Distribution errors
+    InvalidPayeeShare = 35,
+    // Share marketplace errors
+    InsufficientPaymentBalance = 36,
+    // Epoch errors
+    EpochActive = 37,
+    InvalidEpochDuration = 38,
+    // Shares update errors
+    ShareholderHasPendingAllocation = 39,
+    // Listing cancellation errors
+    InvalidCancelPenalty = 40,
+    // Distribution errors
+    InvalidAllocationCap = 41,
+    // Withdrawal errors
+    BelowMinimumWithdrawal = 42,
+    // Share validation errors
+    ZeroShareNotAllowed = 43,
+    // Token transfer and shareholder address errors
+    RecipientNotAllowed = 44,
+    // Listing id errors
+    ListingChanged = 45,
+    // Operator errors
+    InsufficientShareAllowance = 46,
+    // Reinvestment errors
+    InvalidReinvestShare = 47,
+    // Listing fee errors
+    InvalidListingFee = 48,
+    // Admin errors
+    InitialAdminClaimDisabled = 49,
+    // Share lock errors
+    SharesLocked = 50,
+    // Share transfer errors
+    TransferCooldownActive = 51,
+    // Distribution errors
+    DistributionCooldownActive = 52,
+    // Compliance errors
+    ShareholderFrozen = 53,
+    // Share marketplace errors
+    SellerBlocked = 54,
+    // Distribution errors
+    NotAShareholder = 55,
+    // Share marketplace errors
+    SellerProceedsTooLow = 56,
+    PerBuyerCapExceeded = 57,
+    SameIdentity = 58,
+    // Commission errors
+    InvalidRecipient = 59,
+}
+
+/// Exported error cases of the contract, they must match the cases of `Error`
+pub mod spec {
+    use soroban_sdk::contracterror;
+
+    /// Error cases with the codes up to 50
+    #[contracterror]
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    #[repr(u32)]
+    pub enum Error {
+        NotInitialized = 1,
+        AlreadyInitialized = 2,
+        Unauthorized = 3,
+        ContractLocked = 4,
+        LowShareCount = 5,
+        InvalidShareTotal = 6,
+        ZeroTransferAmount = 7,
+        TransferAmountAboveBalance = 8,
+        TransferAmountAboveUnusedBalance = 9,
+        ZeroWithdrawalAmount = 10,
+        WithdrawalAmountAboveAllocation = 11,
+        NoSharesToSell = 12,
+        NoActiveListing = 13,
+        InsufficientSharesInListing = 14,
+        InvalidPrice = 15,
+        InvalidShareAmount = 16,
+        CannotBuyOwnShares = 17,
+        NoSharesToTransfer = 18,
+        InsufficientSharesToTransfer = 19,
+        CannotTransferToSelf = 20,
+        Overflow = 21,
+        NegativeShareAmount = 22,
+        DuplicateShareholder = 23,
+        InvalidCommissionRate = 24,
+        TooFewShareholders = 25,
+        ListingAlreadyExists = 26,
+        NoEscrow = 27,
+        EscrowActive = 28,
+        BuyerNotWhitelisted = 29,
+        TooManyItems = 30,
+        ShareholderExists = 31,
+        NoShareholders = 32,
+        TooManyListings = 33,
+        InvalidShareGranularity = 34,
+        InvalidPayeeShare = 35,
+        InsufficientPaymentBalance = 36,
+        EpochActive = 37,
+        InvalidEpochDuration = 38,
+        ShareholderHasPendingAllocation = 39,
+        // Listing cancellation errors
+        InvalidCancelPenalty = 40,
+        // Distribution errors
+        InvalidAllocationCap = 41,
+        BelowMinimumWithdrawal = 42,
+        ZeroShareNotAllowed = 43,
+        RecipientNotAllowed = 44,
+        ListingChanged = 45,
+        InsufficientShareAllowance = 46,
+        // Reinvestment errors
+        InvalidReinvestShare = 47,
+        // Listing fee errors
+        InvalidListingFee = 48,
+        InitialAdminClaimDisabled = 49,
+        SharesLocked = 50,
+    }
+
+    /// Error cases with the codes from 51 on
+    #[contracterror]
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    #[repr(u32)]
+    pub enum ExtError {
+        TransferCooldownActive = 51,
+        DistributionCooldownActive = 52,
+        ShareholderFrozen = 53,
+        SellerBlocked = 54,
+        NotAShareholder = 55,
+        SellerProceedsTooLow = 56,
+        PerBuyerCapExceeded = 57,
+        SameIdentity = 58,
+        InvalidRecipient = 59,
+    }
 }
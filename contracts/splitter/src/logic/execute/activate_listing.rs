@@ -0,0 +1,26 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{
+    errors::Error,
+    logic::helpers::emit_listing_activation,
+    storage::SaleListingDataKey,
+};
+
+/// Announces a delayed listing once its activation ledger is reached.
+///
+/// Anyone can call it, a listing is only announced once.
+pub fn execute(env: Env, seller: Address) -> Result<(), Error> {
+    let mut listing =
+        SaleListingDataKey::get_listing(&env, &seller).ok_or(Error::NoActiveListing)?;
+
+    if !listing.is_active(&env) {
+        return Err(Error::NoActiveListing);
+    }
+
+    if listing.needs_activation_event(&env) {
+        emit_listing_activation(&env, &mut listing);
+        SaleListingDataKey::save_listing(&env, &listing);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{AllocationDataKey, ConfigDataKey},
+};
+
+pub fn execute(
+    env: Env,
+    shareholder: Address,
+    operator: Address,
+    token_address: Address,
+    allowance: i128,
+) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the shareholder
+    shareholder.require_auth();
+
+    // The allowance cannot be negative, 0 revokes it
+    if allowance < 0 {
+        return Err(Error::ZeroWithdrawalAmount);
+    }
+
+    AllocationDataKey::save_withdraw_allowance(
+        &env,
+        &shareholder,
+        &operator,
+        &token_address,
+        allowance,
+    );
+
+    // Emit approval event
+    env.events().publish(
+        (symbol_short!("approve"), shareholder, operator),
+        (token_address, allowance),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use soroban_sdk::{symbol_short, Address, Env, Vec};
+
+use crate::{
+    errors::Error,
+    logic::helpers::{
+        check_min_shareholders, check_not_frozen, check_share_granularity, get_buy_commission,
+        get_free_shares, get_token_client, move_shares, pay_buy_commission,
+    },
+    storage::{
+        BuyerSpentDataKey, ConfigDataKey, PoolContribution,
+        PooledListingDataKey, SettingsDataKey, ShareDataKey,
+    },
+};
+
+/// Buys shares from the pooled listing of the token and price.
+///
+/// Contributions are filled oldest first and every contributor is paid for the shares
+/// filled from them. Shares a contributor no longer owns are dropped from the pool.
+pub fn execute(
+    env: Env,
+    buyer: Address,
+    payment_token: Address,
+    price_per_share: i128,
+    shares_amount: i128,
+) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Require buyer authorization
+    buyer.require_auth();
+
+    // Validate inputs
+    if shares_amount <= 0 {
+        return Err(Error::InvalidShareAmount);
+    }
+
+    // Frozen addresses can't buy
+    check_not_frozen(&env, &buyer)?;
+
+    // Shares can only be bought in whole lots
+    check_share_granularity(&env, shares_amount)?;
+
+    let contributions =
+        PooledListingDataKey::get_contributions(&env, &payment_token, price_per_share);
+    if contributions.is_empty() {
+        return Err(Error::NoActiveListing);
+    }
+
+    // Plan the fills before moving anything, so a partially fillable order fails as a whole
+    let mut fills: Vec<(Address, i128)> = Vec::new(&env);
+    let mut kept: Vec<PoolContribution> = Vec::new(&env);
+    let mut remaining = shares_amount;
+    for contribution in contributions.iter() {
+        // The buyer's own contributions, also under the same identity, and those of
+        // frozen contributors are left in the pool
+        if remaining == 0
+            || contribution.seller == buyer
+            || SettingsDataKey::is_same_identity(&env, &buyer, &contribution.seller)
+            || SettingsDataKey::is_frozen(&env, &contribution.seller)
+        {
+            kept.push_back(contribution);
+            continue;
+        }
+
+        // Only the shares the contributor still owns and can move can be filled
+        let mut owned = get_free_shares(&env, &contribution.seller);
+        for (seller, filled) in fills.iter() {
+            if seller == contribution.seller {
+                owned -= filled;
+            }
+        }
+
+        let available = contribution.shares.min(owned);
+        if available <= 0 {
+            continue;
+        }
+
+        let fill = remaining.min(available);
+        fills.push_back((contribution.seller.clone(), fill));
+        remaining -= fill;
+
+        if available > fill {
+            kept.push_back(PoolContribution {
+                seller: contribution.seller,
+                shares: available - fill,
+            });
+        }
+    }
+
+    // Verify enough shares in the pool
+    if remaining > 0 {
+        return Err(Error::InsufficientSharesInListing);
+    }
+
+    // Make sure the total price fits
+    let total_price = shares_amount
+        .checked_mul(price_per_share)
+        .ok_or(Error::Overflow)?;
+
+    // Fail fast when the buyer can't pay, the transfers stay the final authority
+    let token_client = get_token_client(&env, &payment_token);
+    if token_client.balance(&buyer) < total_price {
+        return Err(Error::InsufficientPaymentBalance);
+    }
+
+    let min_seller_proceeds = SettingsDataKey::get_min_seller_proceeds(&env, &payment_token);
+
+    for (seller, fill) in fills.iter() {
+        // Make sure the sale doesn't shrink the shareholder set below the minimum
+        let seller_share = ShareDataKey::get_share(&env, &seller)
+            .ok_or(Error::NoSharesToSell)?
+            .share;
+        check_min_shareholders(
+            &env,
+            seller_share <= fill,
+            ShareDataKey::get_share(&env, &buyer).is_none(),
+        )?;
+
+        // Pay the contributor for the shares filled from them
+        let price = fill * price_per_share;
+        let commission = get_buy_commission(&env, &seller, &buyer, price);
+        let seller_receives = price - commission;
+
+        // Don't let the commission eat the contributor's payment
+        if seller_receives < min_seller_proceeds {
+            return Err(Error::SellerProceedsTooLow);
+        }
+
+        if seller_receives > 0 {
+            token_client.transfer(&buyer, &seller, &seller_receives);
+        }
+
+        // Pay commission to recipient, or keep it in the contract until the recipient sweeps it
+        pay_buy_commission(&env, &payment_token, &buyer, &buyer, commission)?;
+
+        // Move the shares from the contributor to the buyer
+        move_shares(&env, &seller, &buyer, fill)?;
+
+        // Emit pool fill event
+        env.events().publish(
+            (symbol_short!("pool_fill"), seller, buyer.clone()),
+            (fill, price, payment_token.clone()),
+        );
+    }
+
+    // Track the buyer's spending
+    BuyerSpentDataKey::add_spent(&env, &buyer, &payment_token, total_price)?;
+
+    PooledListingDataKey::save_contributions(&env, &payment_token, price_per_share, &kept);
+
+    Ok(())
+}
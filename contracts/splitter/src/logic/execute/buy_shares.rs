@@ -2,8 +2,14 @@ use soroban_sdk::{symbol_short, Address, Env};
 
 use crate::{
     errors::Error,
-    logic::helpers::get_token_client,
-    storage::{CommissionConfig, SaleListingDataKey, ShareDataKey},
+    logic::helpers::{
+        check_min_shareholders, check_not_frozen, check_share_granularity, emit_listing_activation,
+        get_buy_commission, get_token_client, get_total_shares, move_shares, pay_buy_commission,
+    },
+    storage::{
+        AllocationDataKey, BuyerSpentDataKey, CommissionConfig, SaleListingDataKey,
+        SettingsDataKey, ShareDataKey,
+    },
 };
 
 pub fn execute(
@@ -15,6 +21,18 @@ pub fn execute(
     // Require buyer authorization
     buyer.require_auth();
 
+    let listing = validate(&env, &buyer, &seller, shares_amount)?;
+    purchase(&env, &buyer, &seller, shares_amount, listing)
+}
+
+/// Checks that the buyer can buy `shares_amount` from the seller's listing
+/// and returns the listing
+pub(super) fn validate(
+    env: &Env,
+    buyer: &Address,
+    seller: &Address,
+    shares_amount: i128,
+) -> Result<SaleListingDataKey, Error> {
     // Validate inputs
     if shares_amount <= 0 {
         return Err(Error::InvalidShareAmount);
@@ -25,101 +43,162 @@ pub fn execute(
         return Err(Error::CannotBuyOwnShares);
     }
 
+    // Nor from another address of the same identity
+    if SettingsDataKey::is_same_identity(env, buyer, seller) {
+        return Err(Error::SameIdentity);
+    }
+
+    // Frozen addresses can neither buy nor sell
+    check_not_frozen(env, buyer)?;
+    check_not_frozen(env, seller)?;
+
+    // Shares can only be bought in whole lots
+    check_share_granularity(env, shares_amount)?;
+
     // Get listing
-    let listing =
-        SaleListingDataKey::get_listing(&env, &seller).ok_or(Error::NoActiveListing)?;
+    let listing = SaleListingDataKey::get_listing(env, seller).ok_or(Error::NoActiveListing)?;
+
+    // Expired listings can no longer be bought
+    if listing.is_expired(env) {
+        return Err(Error::NoActiveListing);
+    }
+
+    // Delayed listings can't be bought before their activation ledger
+    if !listing.is_active(env) {
+        return Err(Error::NoActiveListing);
+    }
+
+    // Private listings can only be bought by whitelisted buyers
+    if !listing.is_buyer_allowed(buyer) {
+        return Err(Error::BuyerNotWhitelisted);
+    }
 
     // Verify enough shares in listing
     if shares_amount > listing.shares_for_sale {
         return Err(Error::InsufficientSharesInListing);
     }
 
+    // A single buyer can't buy more than the per-buyer cap of the listing
+    listing.check_buyer_cap(env, buyer, shares_amount)?;
+
+    // Get current share data
+    let seller_share_data = ShareDataKey::get_share(env, seller).ok_or(Error::NoSharesToSell)?;
+
+    // Make sure the sale doesn't shrink the shareholder set below the minimum
+    check_min_shareholders(
+        env,
+        seller_share_data.share <= shares_amount,
+        ShareDataKey::get_share(env, buyer).is_none(),
+    )?;
+
+    // Make sure the total price fits
+    let total_price = shares_amount
+        .checked_mul(listing.price_per_share)
+        .ok_or(Error::Overflow)?;
+
+    // Fail fast when the buyer can't pay, the transfer stays the final authority
+    let token_client = get_token_client(env, &listing.payment_token);
+    if token_client.balance(buyer) < total_price {
+        return Err(Error::InsufficientPaymentBalance);
+    }
+
+    Ok(listing)
+}
+
+/// Pays the seller and moves the shares of a validated purchase to the buyer
+pub(super) fn purchase(
+    env: &Env,
+    buyer: &Address,
+    seller: &Address,
+    shares_amount: i128,
+    mut listing: SaleListingDataKey,
+) -> Result<(), Error> {
+    // The first buy after the activation ledger announces the listing if no keeper did
+    if listing.needs_activation_event(env) {
+        emit_listing_activation(env, &mut listing);
+    }
+
     // Calculate total price (with overflow protection)
     let total_price = shares_amount
         .checked_mul(listing.price_per_share)
         .ok_or(Error::Overflow)?;
 
-    // Get commission config and calculate commission (1.5% on buys)
-    let commission_config = CommissionConfig::get(&env);
-    let commission = CommissionConfig::calculate_commission(total_price, commission_config.buy_rate_bps);
+    // Get commission config and calculate commission (1.5% on buys), unless either side is exempt
+    let commission_config = CommissionConfig::get(env);
+    let commission = get_buy_commission(env, seller, buyer, total_price);
     let seller_receives = total_price - commission;
 
+    // Don't let the commission eat the seller's payment
+    if seller_receives < SettingsDataKey::get_min_seller_proceeds(env, &listing.payment_token) {
+        return Err(Error::SellerProceedsTooLow);
+    }
+
     // Transfer payment from buyer
-    let token_client = get_token_client(&env, &listing.payment_token);
+    let token_client = get_token_client(env, &listing.payment_token);
 
-    // Pay seller (total - commission)
+    // Pay seller (total - commission), either directly or as an allocation kept in the contract
     if seller_receives > 0 {
-        token_client.transfer(&buyer, &seller, &seller_receives);
-    }
-
-    // Pay commission to recipient
-    if commission > 0 {
-        token_client.transfer(&buyer, &commission_config.recipient, &commission);
+        if listing.credit_proceeds {
+            token_client.transfer(buyer, env.current_contract_address(), &seller_receives);
+
+            let allocation =
+                AllocationDataKey::get_allocation(env, seller, &listing.payment_token).unwrap_or(0);
+            AllocationDataKey::save_allocation(
+                env,
+                seller,
+                &listing.payment_token,
+                allocation.checked_add(seller_receives).ok_or(Error::Overflow)?,
+            );
+        } else {
+            token_client.transfer(buyer, seller, &seller_receives);
+        }
     }
 
-    // Get current share data
-    let mut seller_share_data =
-        ShareDataKey::get_share(&env, &seller).ok_or(Error::NoSharesToSell)?;
+    // Pay commission to recipient, or keep it in the contract until the recipient sweeps it
+    pay_buy_commission(env, &listing.payment_token, buyer, buyer, commission)?;
 
-    // Reduce seller's shares
-    seller_share_data.share -= shares_amount;
+    // Move the shares from the seller to the buyer
+    move_shares(env, seller, buyer, shares_amount)?;
 
-    if seller_share_data.share > 0 {
-        ShareDataKey::save_share(&env, seller.clone(), seller_share_data.share);
-    } else {
-        // Seller has no more shares, remove them
-        ShareDataKey::remove_share(&env, &seller);
-
-        // Remove from shareholders list
-        let mut shareholders = ShareDataKey::get_shareholders(&env);
-        let mut found_index: Option<u32> = None;
-        for (i, addr) in shareholders.iter().enumerate() {
-            if addr == seller {
-                found_index = Some(i as u32);
-                break;
-            }
-        }
-        if let Some(index) = found_index {
-            shareholders.remove(index);
-            ShareDataKey::save_shareholders(&env, shareholders);
-        }
-    }
+    // Shares only change hands, the total never drifts
+    debug_assert_eq!(get_total_shares(env), Ok(ShareDataKey::get_share_denominator(env)));
 
-    // Increase buyer's shares (or create new shareholder)
-    let buyer_share_data = ShareDataKey::get_share(&env, &buyer);
-    let new_buyer_shares = match buyer_share_data {
-        Some(data) => data.share + shares_amount,
-        None => {
-            // Add buyer to shareholders list
-            let mut shareholders = ShareDataKey::get_shareholders(&env);
-            shareholders.push_back(buyer.clone());
-            ShareDataKey::save_shareholders(&env, shareholders);
-            shares_amount
-        }
-    };
+    // Track the buyer's spending
+    BuyerSpentDataKey::add_spent(env, buyer, &listing.payment_token, total_price)?;
 
-    ShareDataKey::save_share(&env, buyer.clone(), new_buyer_shares);
+    // Track what the buyer bought from this listing for the per-buyer cap
+    if listing.max_per_buyer > 0 {
+        SaleListingDataKey::add_buyer_filled(env, buyer, seller, listing.listing_id, shares_amount)?;
+    }
 
     // Update listing
     let remaining_shares = listing.shares_for_sale - shares_amount;
     if remaining_shares > 0 {
         // Update listing with remaining shares
         SaleListingDataKey::save_listing(
-            &env,
-            seller.clone(),
-            remaining_shares,
-            listing.price_per_share,
-            listing.payment_token.clone(),
+            env,
+            &SaleListingDataKey {
+                shares_for_sale: remaining_shares,
+                ..listing.clone()
+            },
         );
     } else {
         // All shares sold, remove listing
-        SaleListingDataKey::remove_listing(&env, &seller);
+        SaleListingDataKey::remove_listing(env, seller);
     }
 
-    // Emit share sale event
+    // Emit share sale event, remaining shares are 0 when the listing was fully sold.
+    // The buy rate in effect lets indexers spot rate changes between quote and execution.
     env.events().publish(
-        (symbol_short!("sold"), seller, buyer),
-        (shares_amount, total_price, listing.payment_token),
+        (symbol_short!("sold"), seller.clone(), buyer.clone()),
+        (
+            shares_amount,
+            total_price,
+            listing.payment_token,
+            remaining_shares,
+            commission,
+            commission_config.buy_rate_bps,
+        ),
     );
 
     Ok(())
@@ -0,0 +1,35 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{errors::Error, storage::SaleListingDataKey};
+
+use super::buy_shares::{purchase, validate};
+
+/// Buys shares from the listing with the given id, failing if the seller relisted since
+pub fn execute(
+    env: Env,
+    buyer: Address,
+    listing_id: u64,
+    shares_amount: i128,
+) -> Result<(), Error> {
+    // Require buyer authorization
+    buyer.require_auth();
+
+    // An id that was assigned before but has no open listing anymore was relisted or removed
+    let seller = match SaleListingDataKey::get_listing_seller(&env, listing_id) {
+        Some(seller) => seller,
+        None if SaleListingDataKey::is_issued_listing_id(&env, listing_id) => {
+            return Err(Error::ListingChanged)
+        }
+        None => return Err(Error::NoActiveListing),
+    };
+
+    // The seller's current listing must still be the one the buyer refers to
+    let current =
+        SaleListingDataKey::get_listing(&env, &seller).ok_or(Error::NoActiveListing)?;
+    if current.listing_id != listing_id {
+        return Err(Error::ListingChanged);
+    }
+
+    let listing = validate(&env, &buyer, &seller, shares_amount)?;
+    purchase(&env, &buyer, &seller, shares_amount, listing)
+}
@@ -0,0 +1,28 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::{errors::Error, storage::MAX_BATCH_ORDERS};
+
+use super::buy_shares::{purchase, validate};
+
+pub fn execute(env: Env, buyer: Address, orders: Vec<(Address, i128)>) -> Result<(), Error> {
+    // Require buyer authorization once for the whole batch
+    buyer.require_auth();
+
+    if orders.len() > MAX_BATCH_ORDERS {
+        return Err(Error::TooManyItems);
+    }
+
+    // Validate every order before executing any of them
+    for (seller, shares_amount) in orders.iter() {
+        validate(&env, &buyer, &seller, shares_amount)?;
+    }
+
+    // Earlier orders change the listings and shares, so each order is checked
+    // again right before it executes
+    for (seller, shares_amount) in orders.iter() {
+        let listing = validate(&env, &buyer, &seller, shares_amount)?;
+        purchase(&env, &buyer, &seller, shares_amount, listing)?;
+    }
+
+    Ok(())
+}
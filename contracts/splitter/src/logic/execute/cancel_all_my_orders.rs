@@ -0,0 +1,62 @@
+use soroban_sdk::{symbol_short, Address, Env, Vec};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, EscrowDataKey, SaleListingDataKey, MAX_BATCH_ORDERS},
+};
+
+use super::{cancel_listing, leave_pool, refund_purchase};
+
+/// Clears every marketplace position of the seller in one call: the listing, the
+/// contributions to the given pools and the escrowed purchases of the given buyers.
+///
+/// Pools and escrows are not indexed by seller, so the seller names them.
+/// Slots without a position are skipped.
+pub fn execute(
+    env: Env,
+    seller: Address,
+    pools: Vec<(Address, i128)>,
+    buyers: Vec<Address>,
+) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    seller.require_auth();
+
+    if pools.len() > MAX_BATCH_ORDERS || buyers.len() > MAX_BATCH_ORDERS {
+        return Err(Error::TooManyItems);
+    }
+
+    // Cancel the listing like `cancel_listing`, penalty and fee refund included
+    let mut listed_shares: i128 = 0;
+    if let Some(listing) = SaleListingDataKey::get_listing(&env, &seller) {
+        listed_shares = listing.shares_for_sale;
+        cancel_listing::cancel(&env, &seller, listing)?;
+    }
+
+    // Leave the pools
+    let mut pooled_shares: i128 = 0;
+    for (payment_token, price_per_share) in pools.iter() {
+        if let Some(removed) = leave_pool::leave(&env, &seller, &payment_token, price_per_share) {
+            pooled_shares += removed;
+        }
+    }
+
+    // The seller rejects the escrowed purchases, so the buyers get their funds back
+    let mut refunded: u32 = 0;
+    for buyer in buyers.iter() {
+        if let Some(escrow) = EscrowDataKey::get_escrow(&env, &buyer, &seller) {
+            refund_purchase::refund(&env, escrow);
+            refunded += 1;
+        }
+    }
+
+    // Emit summary event
+    env.events().publish(
+        (symbol_short!("cncl_all"), seller),
+        (listed_shares, pooled_shares, refunded),
+    );
+
+    Ok(())
+}
@@ -1,20 +1,74 @@
 use soroban_sdk::{symbol_short, Address, Env};
 
-use crate::{errors::Error, storage::SaleListingDataKey};
+use crate::{
+    errors::Error,
+    logic::helpers::get_token_client,
+    storage::{CancelPenalty, CommissionConfig, ListingFee, SaleListingDataKey, ShareDataKey},
+};
 
 pub fn execute(env: Env, seller: Address) -> Result<(), Error> {
     seller.require_auth();
 
     // Verify listing exists
-    SaleListingDataKey::get_listing(&env, &seller).ok_or(Error::NoActiveListing)?;
+    let listing =
+        SaleListingDataKey::get_listing(&env, &seller).ok_or(Error::NoActiveListing)?;
+
+    cancel(&env, &seller, listing)
+}
+
+/// Removes the listing of an authorized seller, charging the cancel penalty
+/// and refunding the listing fee
+pub(super) fn cancel(env: &Env, seller: &Address, listing: SaleListingDataKey) -> Result<(), Error> {
+    // Charge the penalty for cancelling the listing too early
+    if let Some(cancel_penalty) = CancelPenalty::get(env) {
+        let penalty = cancel_penalty.calculate(env, &listing)?;
+
+        if penalty > 0 {
+            let commission_config = CommissionConfig::get(env);
+            let token_client = get_token_client(env, &listing.payment_token);
+            token_client.transfer(seller, &commission_config.recipient, &penalty);
+
+            // Emit penalty event
+            env.events().publish(
+                (symbol_short!("penalty"), seller.clone()),
+                (listing.payment_token.clone(), penalty),
+            );
+        }
+    }
+
+    // Refund part of the listing fee for cancelling early, in the token it was paid in.
+    // The refundable part of the fees is never swept, so it is only capped by the fees
+    // held in case the refund window was extended since.
+    if let Some(listing_fee) = ListingFee::get(env) {
+        let accrued = ListingFee::get_accrued(env, &listing.fee_token);
+        let refund = listing_fee.calculate_refund(env, &listing)?.min(accrued);
+
+        if refund > 0 {
+            ListingFee::add_accrued(env, &listing.fee_token, -refund)?;
+
+            let token_client = get_token_client(env, &listing.fee_token);
+            token_client.transfer(&env.current_contract_address(), seller, &refund);
+
+            // Emit refund event
+            env.events().publish(
+                (symbol_short!("fee_rfnd"), seller.clone()),
+                (listing.fee_token.clone(), refund),
+            );
+        }
+    }
 
     // Remove listing
-    SaleListingDataKey::remove_listing(&env, &seller);
+    SaleListingDataKey::remove_listing(env, seller);
+
+    // Get the shares the seller still owns
+    let seller_shares = ShareDataKey::get_share(env, seller)
+        .map(|share| share.share)
+        .unwrap_or(0);
 
-    // Emit canceled event
+    // Emit canceled event with the seller's shares and the cancelled listing's shares
     env.events().publish(
-        (symbol_short!("canceled"), seller),
-        true,
+        (symbol_short!("canceled"), seller.clone()),
+        (seller_shares, listing.shares_for_sale),
     );
 
     Ok(())
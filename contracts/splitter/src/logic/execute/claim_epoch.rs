@@ -0,0 +1,63 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    logic::helpers::{check_not_frozen, get_token_client},
+    storage::{ConfigDataKey, EpochDataKey},
+};
+
+use super::start_epoch::epoch_shares;
+
+/// Transfers the part of the epoch vested to the shareholder so far, returning the claimed amount
+pub fn execute(env: Env, shareholder: Address, token_address: Address) -> Result<i128, Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the shareholder
+    shareholder.require_auth();
+
+    // Frozen shareholders keep vesting, but can't claim until they are unfrozen
+    check_not_frozen(&env, &shareholder)?;
+
+    let mut epoch = match EpochDataKey::get_epoch(&env, &token_address) {
+        Some(epoch) => epoch,
+        None => return Ok(0),
+    };
+
+    // Only the shares held when the epoch started vest, and not to shareholders that opted out
+    let (shares, share_total) = epoch_shares(&env, &token_address);
+    let share = match shares.get(shareholder.clone()) {
+        Some(share) if share > 0 => share,
+        _ => return Ok(0),
+    };
+
+    // Vested part of the shareholder, minus what was already claimed from this epoch
+    let entitled = epoch
+        .vested(&env)?
+        .checked_mul(share)
+        .ok_or(Error::Overflow)?
+        / share_total;
+    let claimed = epoch.get_claimed(&env, &token_address, &shareholder);
+    let amount = (entitled - claimed).min(epoch.amount - epoch.claimed);
+
+    if amount <= 0 {
+        return Ok(0);
+    }
+
+    epoch.claimed += amount;
+    EpochDataKey::save_epoch(&env, &token_address, &epoch);
+    epoch.save_claimed(&env, &token_address, &shareholder, claimed + amount);
+
+    // Transfer the tokens to the shareholder
+    let token_client = get_token_client(&env, &token_address);
+    token_client.transfer(&env.current_contract_address(), &shareholder, &amount);
+
+    // Emit claim event
+    env.events().publish(
+        (symbol_short!("claim"), shareholder),
+        (token_address, amount),
+    );
+
+    Ok(amount)
+}
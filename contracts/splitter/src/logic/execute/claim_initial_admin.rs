@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{errors::Error, storage::ConfigDataKey};
+
+/// Replaces a mis-set admin once, before the contract was used
+pub fn execute(env: Env, new_admin: Address) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the current admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Only possible before any distribution or marketplace action, and only once
+    if ConfigDataKey::has_activity(&env) {
+        return Err(Error::InitialAdminClaimDisabled);
+    }
+
+    ConfigDataKey::save_admin(&env, new_admin.clone());
+    ConfigDataKey::mark_activity(&env);
+
+    // Emit admin event
+    env.events().publish((symbol_short!("admin"),), new_admin);
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    logic::helpers::{check_not_frozen, check_share_granularity, get_free_shares},
+    storage::{
+        ConfigDataKey, PoolContribution, PooledListingDataKey, SettingsDataKey, ShareDataKey,
+        MAX_POOL_CONTRIBUTIONS,
+    },
+};
+
+/// Adds shares of the seller to the pooled listing of the token and price.
+///
+/// The shares stay with the seller until a buyer fills them with `buy_from_pool`.
+pub fn execute(
+    env: Env,
+    seller: Address,
+    payment_token: Address,
+    price_per_share: i128,
+    shares_amount: i128,
+) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Validate inputs
+    if shares_amount <= 0 {
+        return Err(Error::InvalidShareAmount);
+    }
+    if price_per_share <= 0 {
+        return Err(Error::InvalidPrice);
+    }
+
+    // Require seller authorization
+    seller.require_auth();
+
+    // Blocked sellers cannot add shares to pooled listings either
+    if SettingsDataKey::is_seller_blocked(&env, &seller) {
+        return Err(Error::SellerBlocked);
+    }
+
+    // Frozen addresses can't sell through the pool
+    check_not_frozen(&env, &seller)?;
+
+    // Shares can only be pooled in whole lots
+    check_share_granularity(&env, shares_amount)?;
+
+    // Marketplace actions disable claiming the initial admin
+    ConfigDataKey::mark_activity(&env);
+
+    let mut contributions =
+        PooledListingDataKey::get_contributions(&env, &payment_token, price_per_share);
+
+    if contributions.len() >= MAX_POOL_CONTRIBUTIONS {
+        return Err(Error::TooManyListings);
+    }
+
+    // The seller cannot pool more shares than they own
+    let seller_share = ShareDataKey::get_share(&env, &seller)
+        .ok_or(Error::NoSharesToSell)?
+        .share;
+    let mut pooled = shares_amount;
+    for contribution in contributions.iter() {
+        if contribution.seller == seller {
+            pooled = pooled.checked_add(contribution.shares).ok_or(Error::Overflow)?;
+        }
+    }
+    if pooled > seller_share {
+        return Err(Error::NoSharesToSell);
+    }
+
+    // Locked and escrowed shares can never be filled, so don't pool them
+    if pooled > get_free_shares(&env, &seller) {
+        return Err(Error::SharesLocked);
+    }
+
+    // New contributions are filled after the existing ones
+    contributions.push_back(PoolContribution {
+        seller: seller.clone(),
+        shares: shares_amount,
+    });
+    PooledListingDataKey::save_contributions(&env, &payment_token, price_per_share, &contributions);
+
+    // Emit pooled event
+    env.events().publish(
+        (symbol_short!("pooled"), seller),
+        (payment_token, price_per_share, shares_amount),
+    );
+
+    Ok(())
+}
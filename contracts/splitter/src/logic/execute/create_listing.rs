@@ -0,0 +1,23 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{errors::Error, storage::SaleListingDataKey};
+
+use super::list_shares_for_sale;
+
+/// Creates a new sale listing, refusing to replace an existing one.
+///
+/// Use `list_shares_for_sale` to intentionally replace a listing.
+pub fn execute(
+    env: Env,
+    seller: Address,
+    shares_amount: i128,
+    price_per_share: i128,
+    payment_token: Address,
+) -> Result<(), Error> {
+    // Make sure the seller doesn't already have a listing
+    if SaleListingDataKey::get_listing(&env, &seller).is_some() {
+        return Err(Error::ListingAlreadyExists);
+    }
+
+    list_shares_for_sale::execute(env, seller, shares_amount, price_per_share, payment_token)
+}
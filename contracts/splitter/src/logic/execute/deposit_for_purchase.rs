@@ -0,0 +1,122 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    logic::helpers::{check_not_frozen, check_share_granularity, get_token_client},
+    storage::{
+        ConfigDataKey, EscrowDataKey, SaleListingDataKey, SettingsDataKey,
+        ESCROW_EXPIRATION_LEDGERS,
+    },
+};
+
+pub fn execute(
+    env: Env,
+    buyer: Address,
+    seller: Address,
+    shares_amount: i128,
+) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Require buyer authorization
+    buyer.require_auth();
+
+    // Validate inputs
+    if shares_amount <= 0 {
+        return Err(Error::InvalidShareAmount);
+    }
+
+    // Cannot buy from yourself
+    if buyer == seller {
+        return Err(Error::CannotBuyOwnShares);
+    }
+
+    // Nor from another address of the same identity
+    if SettingsDataKey::is_same_identity(&env, &buyer, &seller) {
+        return Err(Error::SameIdentity);
+    }
+
+    // Frozen addresses can neither buy nor sell
+    check_not_frozen(&env, &buyer)?;
+    check_not_frozen(&env, &seller)?;
+
+    // Shares can only be bought in whole lots
+    check_share_granularity(&env, shares_amount)?;
+
+    // Only one escrowed purchase per buyer and seller
+    if EscrowDataKey::get_escrow(&env, &buyer, &seller).is_some() {
+        return Err(Error::EscrowActive);
+    }
+
+    // Get listing
+    let listing =
+        SaleListingDataKey::get_listing(&env, &seller).ok_or(Error::NoActiveListing)?;
+
+    // Expired listings can no longer be bought
+    if listing.is_expired(&env) {
+        return Err(Error::NoActiveListing);
+    }
+
+    // Delayed listings can't be bought before their activation ledger
+    if !listing.is_active(&env) {
+        return Err(Error::NoActiveListing);
+    }
+
+    // Private listings can only be bought by whitelisted buyers
+    if !listing.is_buyer_allowed(&buyer) {
+        return Err(Error::BuyerNotWhitelisted);
+    }
+
+    // Verify enough shares in listing
+    if shares_amount > listing.shares_for_sale {
+        return Err(Error::InsufficientSharesInListing);
+    }
+
+    // A single buyer can't buy more than the per-buyer cap of the listing
+    listing.check_buyer_cap(&env, &buyer, shares_amount)?;
+
+    // Calculate total price (with overflow protection)
+    let total_price = shares_amount
+        .checked_mul(listing.price_per_share)
+        .ok_or(Error::Overflow)?;
+
+    // Move the payment from the buyer into the contract
+    let token_client = get_token_client(&env, &listing.payment_token);
+    token_client.transfer(&buyer, env.current_contract_address(), &total_price);
+
+    // Reserve the shares, so the listing can't be sold or escrowed beyond what it holds
+    let remaining_shares = listing.shares_for_sale - shares_amount;
+    if remaining_shares > 0 {
+        SaleListingDataKey::save_listing(
+            &env,
+            &SaleListingDataKey {
+                shares_for_sale: remaining_shares,
+                ..listing.clone()
+            },
+        );
+    } else {
+        SaleListingDataKey::remove_listing(&env, &seller);
+    }
+
+    // Store the escrow, locking the price of the listing at the time of the deposit
+    EscrowDataKey::save_escrow(
+        &env,
+        &EscrowDataKey {
+            buyer: buyer.clone(),
+            seller: seller.clone(),
+            shares_amount,
+            total_price,
+            payment_token: listing.payment_token.clone(),
+            expiration_ledger: env.ledger().sequence() + ESCROW_EXPIRATION_LEDGERS,
+        },
+    );
+
+    // Emit escrowed event
+    env.events().publish(
+        (symbol_short!("escrowed"), buyer, seller),
+        (shares_amount, total_price, listing.payment_token),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{errors::Error, logic::helpers::get_unused_balance, storage::ConfigDataKey};
+
+use super::distribute_tokens::distribute;
+
+pub fn execute(env: Env, token_address: Address, amount: i128) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Distribution amount cannot be equal and less than 0
+    if amount <= 0 {
+        return Err(Error::ZeroTransferAmount);
+    };
+
+    // Distribution amount cannot be greater than the unused balance
+    if amount > get_unused_balance(&env, &token_address) {
+        return Err(Error::TransferAmountAboveUnusedBalance);
+    };
+
+    // The rest of the unused balance stays in the contract
+    distribute(&env, &token_address, amount)
+}
@@ -0,0 +1,61 @@
+use soroban_sdk::{symbol_short, Address, Env, Symbol, Vec};
+
+use crate::{
+    errors::Error,
+    logic::helpers::get_unused_balance,
+    storage::{ConfigDataKey, ShareDataKey},
+};
+
+use super::distribute_tokens::distribute_to;
+
+/// Distributes the unused balance of the token pro-rata to the share * ledgers each
+/// shareholder accumulated since the last time-weighted distribution, then resets the weights
+pub fn execute(env: Env, token_address: Address) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Calculate the distributable amount (only NEW deposits, not already allocated or escrowed tokens)
+    let distributable = get_unused_balance(&env, &token_address);
+
+    // If there's nothing new to distribute, return early, but let callers tell the no-op apart
+    if distributable <= 0 {
+        env.events().publish(
+            (Symbol::new(&env, "no_distrib"), token_address),
+            distributable,
+        );
+        return Ok(());
+    }
+
+    // Only current shareholders take part, addresses that sold out during the period don't
+    let mut weights: Vec<(Address, i128)> = Vec::new(&env);
+    let mut weight_total: i128 = 0;
+    for shareholder in ShareDataKey::get_shareholders(&env).iter() {
+        let weight = ShareDataKey::get_time_weight(&env, &shareholder);
+        if weight > 0 {
+            weight_total = weight_total.checked_add(weight).ok_or(Error::Overflow)?;
+            weights.push_back((shareholder, weight));
+        }
+    }
+
+    // No ledger has passed since the period started, so there's nothing to weigh by
+    if weight_total == 0 {
+        return Err(Error::NoShareholders);
+    }
+
+    distribute_to(&env, &token_address, distributable, &weights, weight_total)?;
+
+    // The next time-weighted distribution only counts the ledgers from now on
+    ShareDataKey::reset_time_weights(&env);
+
+    // Emit time-weighted distribution event
+    env.events().publish(
+        (symbol_short!("dist_tw"), token_address),
+        (weight_total, ShareDataKey::get_time_weight_period(&env)),
+    );
+
+    Ok(())
+}
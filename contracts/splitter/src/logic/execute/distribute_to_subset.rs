@@ -0,0 +1,61 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::{
+    errors::Error,
+    logic::helpers::get_unused_balance,
+    storage::{ConfigDataKey, ShareDataKey},
+};
+
+use super::distribute_tokens::distribute_to;
+
+/// Distributes `amount` of the unused balance only among the given shareholders,
+/// pro-rata to their shares within the subset
+pub fn execute(
+    env: Env,
+    token_address: Address,
+    subset: Vec<Address>,
+    amount: i128,
+) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Distribution amount cannot be equal and less than 0
+    if amount <= 0 {
+        return Err(Error::ZeroTransferAmount);
+    };
+
+    // There must be someone to distribute to
+    if subset.is_empty() {
+        return Err(Error::NoShareholders);
+    }
+
+    // Distribution amount cannot be greater than the unused balance
+    if amount > get_unused_balance(&env, &token_address) {
+        return Err(Error::TransferAmountAboveUnusedBalance);
+    };
+
+    // Every address must be a distinct shareholder, the subset shares replace the denominator
+    let mut subset_shares: Vec<(Address, i128)> = Vec::new(&env);
+    let mut subset_total: i128 = 0;
+    for (i, shareholder) in subset.iter().enumerate() {
+        let share = ShareDataKey::get_share(&env, &shareholder)
+            .map(|share| share.share)
+            .unwrap_or(0);
+        if share <= 0 {
+            return Err(Error::NotAShareholder);
+        }
+
+        if subset.last_index_of(&shareholder) != Some(i as u32) {
+            return Err(Error::DuplicateShareholder);
+        }
+
+        subset_total = subset_total.checked_add(share).ok_or(Error::Overflow)?;
+        subset_shares.push_back((shareholder, share));
+    }
+
+    distribute_to(&env, &token_address, amount, &subset_shares, subset_total)
+}
@@ -1,43 +1,131 @@
-use soroban_sdk::{symbol_short, Address, Env};
+use soroban_sdk::{symbol_short, Address, Env, Symbol, Vec};
 
 use crate::{
     errors::Error,
-    logic::helpers::get_token_client,
-    storage::{AllocationDataKey, CommissionConfig, ConfigDataKey, ShareDataKey},
+    logic::helpers::{get_token_client, get_unused_balance},
+    storage::{
+        AllocationDataKey, CommissionConfig, ConfigDataKey, FixedPayee, SettingsDataKey,
+        ShareDataKey,
+    },
 };
 
-pub fn execute(env: Env, token_address: Address) -> Result<(), Error> {
+/// Distributes the unused balance of the token, triggered by the admin
+/// or by the `distributor` when one is given
+pub fn execute(
+    env: Env,
+    token_address: Address,
+    distributor: Option<Address>,
+) -> Result<(), Error> {
     if !ConfigDataKey::exists(&env) {
         return Err(Error::NotInitialized);
     };
 
-    // Make sure the caller is the admin
-    ConfigDataKey::require_admin(&env)?;
+    match distributor {
+        // Make sure the caller is an allowed distributor
+        Some(distributor) => {
+            if !SettingsDataKey::is_distributor(&env, &distributor) {
+                return Err(Error::Unauthorized);
+            }
+            distributor.require_auth();
+        }
+        // Make sure the caller is the admin
+        None => ConfigDataKey::require_admin(&env)?,
+    }
+
+    // Calculate the distributable amount (only NEW deposits, not already allocated or escrowed tokens)
+    let distributable = get_unused_balance(&env, &token_address);
+
+    // If there's nothing new to distribute, return early, but let callers tell the no-op apart
+    if distributable <= 0 {
+        env.events().publish(
+            (Symbol::new(&env, "no_distrib"), token_address),
+            distributable,
+        );
+        return Ok(());
+    }
 
-    let token_client = get_token_client(&env, &token_address);
+    distribute(&env, &token_address, distributable)
+}
 
-    // Get the total token balance held by the contract
-    let balance = token_client.balance(&env.current_contract_address());
+/// Takes the distribution commission from `distributable` and allocates the rest pro-rata
+pub(super) fn distribute(
+    env: &Env,
+    token_address: &Address,
+    distributable: i128,
+) -> Result<(), Error> {
+    let mut shares: Vec<(Address, i128)> = Vec::new(env);
+    for shareholder in ShareDataKey::get_shareholders(env).iter() {
+        if let Some(ShareDataKey { share, .. }) = ShareDataKey::get_share(env, &shareholder) {
+            shares.push_back((shareholder, share));
+        }
+    }
 
-    // Get how much has already been allocated (pending claims)
-    let total_allocated =
-        AllocationDataKey::get_total_allocation(&env, &token_address).unwrap_or(0);
+    distribute_to(
+        env,
+        token_address,
+        distributable,
+        &shares,
+        ShareDataKey::get_share_denominator(env),
+    )
+}
 
-    // Calculate the distributable amount (only NEW deposits, not already allocated tokens)
-    let distributable = balance - total_allocated;
+/// Takes the distribution commission from `distributable` and allocates the rest pro-rata
+/// among the given shareholders and their shares, which must sum up to `share_total`.
+/// The shares can be any weight, such as time-weighted shares.
+pub(super) fn distribute_to(
+    env: &Env,
+    token_address: &Address,
+    distributable: i128,
+    shares: &Vec<(Address, i128)>,
+    share_total: i128,
+) -> Result<(), Error> {
+    let token_client = get_token_client(env, token_address);
 
-    // If there's nothing new to distribute, return early
-    if distributable <= 0 {
-        return Ok(());
+    // Shareholders that opted out are left out, the others absorb their portion
+    let mut share_total = share_total;
+    let mut opted_in: Vec<(Address, i128)> = Vec::new(env);
+    for (shareholder, share) in shares.iter() {
+        if SettingsDataKey::is_opted_out(env, &shareholder) {
+            share_total -= share;
+        } else {
+            opted_in.push_back((shareholder, share));
+        }
+    }
+    let shares = &opted_in;
+
+    // Without shareholders nothing can be allocated, so don't take any commission either
+    if shares.is_empty() || share_total <= 0 {
+        return Err(Error::NoShareholders);
+    }
+
+    // Each distribution takes commission, so tokens with a cooldown can't be distributed too often
+    let cooldown_ledgers = SettingsDataKey::get_distribution_cooldown_ledgers(env, token_address);
+    if cooldown_ledgers > 0 {
+        if let Some(last_ledger) = SettingsDataKey::get_last_distribution_ledger(env, token_address) {
+            if env.ledger().sequence() < last_ledger.saturating_add(cooldown_ledgers) {
+                return Err(Error::DistributionCooldownActive);
+            }
+        }
+        SettingsDataKey::save_last_distribution_ledger(env, token_address, env.ledger().sequence());
     }
 
+    // Distributions disable claiming the initial admin
+    ConfigDataKey::mark_activity(env);
+
     // Calculate and transfer distribution commission (0.5%)
-    let commission_config = CommissionConfig::get(&env);
-    let commission = CommissionConfig::calculate_commission(distributable, commission_config.distribution_rate_bps);
+    let commission_config = CommissionConfig::get(env);
+    let commission = if commission_config.distribution_rate_bps == 0 {
+        // Fast path for commission-free distributions
+        0
+    } else {
+        CommissionConfig::calculate_commission(distributable, commission_config.distribution_rate_bps)
+    };
 
-    // Transfer commission to recipient
-    if commission > 0 {
+    // Transfer commission to recipient, a recipient set to the contract itself
+    // leaves the commission in the contract as unused balance
+    if commission > 0 && commission_config.recipient != env.current_contract_address() {
         token_client.transfer(&env.current_contract_address(), &commission_config.recipient, &commission);
+        CommissionConfig::add_commission_earned(env, token_address, 0, commission)?;
 
         // Emit commission event
         env.events().publish(
@@ -46,72 +134,145 @@ pub fn execute(env: Env, token_address: Address) -> Result<(), Error> {
         );
     }
 
+    // Emit the inputs of the split, so indexers can reconstruct it deterministically
+    env.events().publish(
+        (symbol_short!("dist_v2"), token_address.clone()),
+        (distributable, shares.len(), commission),
+    );
+
     // Amount available to distribute to shareholders (after commission)
-    let amount_for_shareholders = distributable - commission;
+    let mut amount_for_shareholders = distributable - commission;
+    debug_assert!(commission_config.distribution_rate_bps != 0 || amount_for_shareholders == distributable);
+
+    // The fixed payee gets its slice before the shareholders
+    let mut payee_amount: i128 = 0;
+    if let Some(payee) = FixedPayee::get(env) {
+        payee_amount = (amount_for_shareholders * payee.bps) / 10000;
+
+        if payee_amount > 0 {
+            token_client.transfer(&env.current_contract_address(), &payee.address, &payee_amount);
+            amount_for_shareholders -= payee_amount;
+
+            // Emit payee event
+            env.events().publish(
+                (symbol_short!("payee"), payee.address),
+                (token_address.clone(), payee_amount),
+            );
+        }
+    }
+
+    // The reinvestment slice is taken before the pro-rata split, so every shareholder
+    // is reduced by the same fraction
+    let mut reinvested: i128 = 0;
+    let reinvest_bps = SettingsDataKey::get_reinvest_bps(env);
+    if reinvest_bps > 0 {
+        reinvested = (amount_for_shareholders * reinvest_bps) / 10000;
 
-    // If nothing left for shareholders after commission, return
+        if reinvested > 0 {
+            let pool = SettingsDataKey::get_reinvest_pool(env, token_address);
+            SettingsDataKey::save_reinvest_pool(env, token_address, pool + reinvested);
+            amount_for_shareholders -= reinvested;
+
+            // Emit reinvest event
+            env.events().publish(
+                (symbol_short!("reinvest"), token_address.clone()),
+                reinvested,
+            );
+        }
+    }
+
+    // If nothing left for shareholders after commission, the fixed payee and reinvestment, return
     if amount_for_shareholders <= 0 {
         return Ok(());
     }
 
-    // Get the shareholders vector
-    let shareholders = ShareDataKey::get_shareholders(&env);
-
     let mut total_distributed: i128 = 0;
+    let mut treasury_dust: i128 = 0;
     let mut largest_shareholder: Option<Address> = None;
     let mut largest_share: i128 = 0;
+    let mut largest_amount: i128 = 0;
+
+    // Amounts above the per-shareholder cap are not redistributed, they stay as unused balance
+    let max_allocation = SettingsDataKey::get_max_allocation_per_dist(env);
+    let mut capped: i128 = 0;
+
+    // Large pools can skip the per-shareholder events, the summary is always emitted
+    let emit_per_shareholder_events = SettingsDataKey::get_emit_per_shareholder_events(env);
 
     // For each shareholder, calculate the amount of tokens to distribute
-    for shareholder in shareholders.iter() {
-        if let Some(ShareDataKey { share, .. }) = ShareDataKey::get_share(&env, &shareholder) {
-            // Track the largest shareholder for dust distribution
-            if share > largest_share {
-                largest_share = share;
-                largest_shareholder = Some(shareholder.clone());
-            }
+    for (shareholder, share) in shares.iter() {
+        // Shareholders without shares get nothing, not even the dust
+        if share <= 0 {
+            continue;
+        }
 
-            // Calculate the amount of tokens to distribute from the amount left after commission
-            // Equivalent to: amount_for_shareholders * share / 10000 (with floor division)
-            let amount = (amount_for_shareholders as i128 * share as i128) / 10000i128;
-
-            if amount > 0 {
-                // Get the current allocation for the user - default to 0
-                let allocation =
-                    AllocationDataKey::get_allocation(&env, &shareholder, &token_address)
-                        .unwrap_or(0);
-
-                // Update the allocation with the new amount
-                AllocationDataKey::save_allocation(
-                    &env,
-                    &shareholder,
-                    &token_address,
-                    allocation + amount,
-                );
+        // Calculate the amount of tokens to distribute from the amount left after commission
+        // Equivalent to: amount_for_shareholders * share / share_total (with floor division)
+        let mut amount = (amount_for_shareholders * share) / share_total;
 
-                total_distributed += amount;
+        // Keep the amount above the cap in the contract
+        if max_allocation > 0 && amount > max_allocation {
+            capped += amount - max_allocation;
+            amount = max_allocation;
+        }
 
-                // Emit per-shareholder distribution event
+        // Track the largest shareholder for dust distribution, on a tie the
+        // lexicographically smallest address wins regardless of the vector order
+        let is_smaller_address = match &largest_shareholder {
+            Some(largest) => shareholder < *largest,
+            None => true,
+        };
+        if share > largest_share || (share == largest_share && is_smaller_address) {
+            largest_share = share;
+            largest_shareholder = Some(shareholder.clone());
+            largest_amount = amount;
+        }
+
+        if amount > 0 {
+            // Get the current allocation for the user - default to 0
+            let allocation =
+                AllocationDataKey::get_allocation(env, &shareholder, token_address)
+                    .unwrap_or(0);
+
+            // Update the allocation with the new amount
+            AllocationDataKey::save_allocation(
+                env,
+                &shareholder,
+                token_address,
+                allocation + amount,
+            );
+
+            total_distributed += amount;
+
+            // Emit per-shareholder distribution event
+            if emit_per_shareholder_events {
                 env.events().publish(
                     (symbol_short!("distrib"), shareholder.clone()),
                     (token_address.clone(), amount),
                 );
             }
-        };
+        }
     }
 
-    // Handle rounding dust: give remainder to the largest shareholder
+    // Handle rounding dust: give remainder to the largest shareholder (smallest address on a tie)
     // This ensures all distributable tokens (after commission) are actually distributed
-    let dust = amount_for_shareholders - total_distributed;
+    let dust = amount_for_shareholders - total_distributed - capped;
     if dust > 0 {
-        if let Some(shareholder) = largest_shareholder {
+        if largest_shareholder.is_some()
+            && max_allocation > 0
+            && largest_amount + dust > max_allocation
+        {
+            // The dust would push the largest shareholder over the cap, so it stays unused too
+            capped += dust;
+        } else if let Some(shareholder) = largest_shareholder {
             let allocation =
-                AllocationDataKey::get_allocation(&env, &shareholder, &token_address)
+                AllocationDataKey::get_allocation(env, &shareholder, token_address)
                     .unwrap_or(0);
 
             AllocationDataKey::save_allocation(
-                &env,
+                env,
                 &shareholder,
-                &token_address,
+                token_address,
                 allocation + dust,
             );
 
@@ -122,12 +283,35 @@ pub fn execute(env: Env, token_address: Address) -> Result<(), Error> {
                 (symbol_short!("dust"), shareholder),
                 (token_address.clone(), dust),
             );
+        } else {
+            // No shareholder qualifies for the dust, send it to the treasury instead of stranding it
+            let treasury = SettingsDataKey::get_treasury(env)
+                .unwrap_or(commission_config.recipient.clone());
+            if treasury != env.current_contract_address() {
+                token_client.transfer(&env.current_contract_address(), &treasury, &dust);
+            }
+
+            treasury_dust = dust;
+
+            // Emit dust distribution event
+            env.events().publish(
+                (symbol_short!("dust"), treasury),
+                (token_address.clone(), dust),
+            );
         }
     }
 
+    // Every distributable token must end up as commission, with the payee, in the reinvestment
+    // pool, as an allocation, in the treasury or as unused balance above the cap
+    if commission + payee_amount + reinvested + total_distributed + treasury_dust + capped
+        != distributable
+    {
+        return Err(Error::Overflow);
+    }
+
     // Emit summary distribution event
     env.events().publish(
-        (symbol_short!("dist_all"), token_address),
+        (symbol_short!("dist_all"), token_address.clone()),
         total_distributed,
     );
 
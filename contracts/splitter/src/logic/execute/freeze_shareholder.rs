@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, shareholder: Address, frozen: bool) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Freeze or unfreeze the address
+    SettingsDataKey::save_frozen(&env, &shareholder, frozen);
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("frozen"), shareholder),
+        frozen,
+    );
+
+    Ok(())
+}
@@ -2,8 +2,8 @@ use soroban_sdk::{symbol_short, Address, Env, Vec};
 
 use crate::{
     errors::Error,
-    logic::helpers::{check_shares, update_shares},
-    storage::{ConfigDataKey, ShareDataKey},
+    logic::helpers::{check_shareholder_addresses, check_shares, update_shares},
+    storage::{CommissionConfig, ConfigDataKey, ShareDataKey, CONTRACT_VERSION},
 };
 
 pub fn execute(
@@ -11,16 +11,34 @@ pub fn execute(
     admin: Address,
     shares: Vec<ShareDataKey>,
     mutable: bool,
+    share_denominator: i128,
+    commission: Option<CommissionConfig>,
 ) -> Result<(), Error> {
     if ConfigDataKey::exists(&env) {
         return Err(Error::AlreadyInitialized);
     };
 
+    if share_denominator <= 0 {
+        return Err(Error::InvalidShareTotal);
+    }
+
     // Initialize the contract configuration
     ConfigDataKey::init(&env, admin.clone(), mutable);
+    ConfigDataKey::save_version(&env, CONTRACT_VERSION);
+
+    // A commission given upfront replaces the defaults before anything can be charged
+    if let Some(commission) = commission {
+        CommissionConfig::init(&env, &commission)?;
+    }
+
+    // The share denominator is fixed for the lifetime of the contract
+    ShareDataKey::save_share_denominator(&env, share_denominator);
 
-    // Check if the shares sum up to 10000
-    check_shares(&shares)?;
+    // Check if the shares sum up to the share denominator
+    check_shares(&shares, share_denominator)?;
+
+    // Make sure no allocation can end up in an address that cannot withdraw it
+    check_shareholder_addresses(&env, &shares)?;
 
     // Update the shares of the shareholders
     update_shares(&env, &shares);
@@ -31,5 +49,13 @@ pub fn execute(
         (shares.len() as u32, mutable),
     );
 
+    // Emit the initial share of each shareholder
+    for share in shares.iter() {
+        env.events().publish(
+            (symbol_short!("init_sh"), share.shareholder),
+            share.share,
+        );
+    }
+
     Ok(())
 }
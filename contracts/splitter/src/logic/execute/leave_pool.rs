@@ -0,0 +1,56 @@
+use soroban_sdk::{symbol_short, Address, Env, Vec};
+
+use crate::{errors::Error, storage::PooledListingDataKey};
+
+/// Removes every contribution of the seller from the pooled listing of the token and price
+pub fn execute(
+    env: Env,
+    seller: Address,
+    payment_token: Address,
+    price_per_share: i128,
+) -> Result<(), Error> {
+    seller.require_auth();
+
+    // Verify the seller contributed to the pool
+    if leave(&env, &seller, &payment_token, price_per_share).is_none() {
+        return Err(Error::NoActiveListing);
+    }
+
+    Ok(())
+}
+
+/// Removes every contribution of an authorized seller from the pooled listing
+/// and returns the shares removed, None when the seller didn't contribute
+pub(super) fn leave(
+    env: &Env,
+    seller: &Address,
+    payment_token: &Address,
+    price_per_share: i128,
+) -> Option<i128> {
+    let contributions =
+        PooledListingDataKey::get_contributions(env, payment_token, price_per_share);
+
+    let mut kept = Vec::new(env);
+    let mut removed: i128 = 0;
+    for contribution in contributions.iter() {
+        if contribution.seller == *seller {
+            removed += contribution.shares;
+        } else {
+            kept.push_back(contribution);
+        }
+    }
+
+    if kept.len() == contributions.len() {
+        return None;
+    }
+
+    PooledListingDataKey::save_contributions(env, payment_token, price_per_share, &kept);
+
+    // Emit unpooled event
+    env.events().publish(
+        (symbol_short!("unpooled"), seller.clone()),
+        (payment_token.clone(), price_per_share, removed),
+    );
+
+    Some(removed)
+}
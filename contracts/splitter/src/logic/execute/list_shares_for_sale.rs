@@ -1,8 +1,12 @@
-use soroban_sdk::{symbol_short, Address, Env};
+use soroban_sdk::{symbol_short, Address, Env, Vec};
 
 use crate::{
     errors::Error,
-    storage::{SaleListingDataKey, ShareDataKey},
+    logic::helpers::{check_not_frozen, check_share_granularity, get_free_shares, get_token_client},
+    storage::{
+        ConfigDataKey, ListingFee, PriceHistoryDataKey, SaleListingDataKey,
+        SettingsDataKey, ShareDataKey,
+    },
 };
 
 pub fn execute(
@@ -23,6 +27,17 @@ pub fn execute(
     // Require seller authorization
     seller.require_auth();
 
+    // Blocked sellers cannot open or replace listings, their current listing stays buyable
+    if SettingsDataKey::is_seller_blocked(&env, &seller) {
+        return Err(Error::SellerBlocked);
+    }
+
+    // Frozen addresses can't list shares
+    check_not_frozen(&env, &seller)?;
+
+    // Shares can only be listed in whole lots
+    check_share_granularity(&env, shares_amount)?;
+
     // Verify seller has enough shares
     let seller_share_data =
         ShareDataKey::get_share(&env, &seller).ok_or(Error::NoSharesToSell)?;
@@ -31,8 +46,82 @@ pub fn execute(
         return Err(Error::NoSharesToSell);
     }
 
+    // Locked and escrowed shares can never be filled, so don't list them
+    if shares_amount > get_free_shares(&env, &seller) {
+        return Err(Error::SharesLocked);
+    }
+
+    // Keep the settings of the listing (buyer whitelist, proceeds setting, expiration,
+    // activation delay and per-buyer cap), its age and the paid fee when the seller
+    // replaces their listing
+    let previous = match SaleListingDataKey::get_listing(&env, &seller) {
+        Some(listing) => listing,
+        None => {
+            // Only new listings count towards the marketplace limit
+            let max_active_listings = SettingsDataKey::get_max_active_listings(&env);
+            if max_active_listings > 0
+                && SaleListingDataKey::get_active_listings(&env).len() >= max_active_listings
+            {
+                return Err(Error::TooManyListings);
+            }
+
+            // Only new listings pay the listing fee
+            let fee = match ListingFee::get(&env) {
+                Some(listing_fee) => listing_fee.calculate(shares_amount, price_per_share)?,
+                None => 0,
+            };
+            if fee > 0 {
+                // The fee is held in the contract for the commission recipient, who can
+                // only sweep the part that can no longer be refunded
+                let token_client = get_token_client(&env, &payment_token);
+                token_client.transfer(&seller, env.current_contract_address(), &fee);
+                ListingFee::add_accrued(&env, &payment_token, fee)?;
+
+                // Emit listing fee event
+                env.events().publish(
+                    (symbol_short!("list_fee"), seller.clone()),
+                    (payment_token.clone(), fee),
+                );
+            }
+
+            SaleListingDataKey {
+                seller: seller.clone(),
+                shares_for_sale: shares_amount,
+                price_per_share,
+                payment_token: payment_token.clone(),
+                allowed_buyers: Vec::new(&env),
+                expiration_ledger: 0,
+                credit_proceeds: false,
+                created_ledger: env.ledger().sequence(),
+                listing_id: 0,
+                fee_paid: fee,
+                fee_token: payment_token.clone(),
+                original_shares: shares_amount,
+                activation_ledger: 0,
+                activation_emitted: false,
+                max_per_buyer: 0,
+            }
+        }
+    };
+
+    // Marketplace actions disable claiming the initial admin
+    ConfigDataKey::mark_activity(&env);
+
     // Create listing
-    SaleListingDataKey::save_listing(&env, seller.clone(), shares_amount, price_per_share, payment_token.clone());
+    SaleListingDataKey::save_listing(
+        &env,
+        &SaleListingDataKey {
+            shares_for_sale: shares_amount,
+            price_per_share,
+            payment_token: payment_token.clone(),
+            listing_id: SaleListingDataKey::next_listing_id(&env),
+            original_shares: shares_amount,
+            ..previous
+        },
+    );
+
+    // Record the listing price
+    PriceHistoryDataKey::record_price(&env, &seller, price_per_share);
 
     // Emit listing event
     env.events().publish(
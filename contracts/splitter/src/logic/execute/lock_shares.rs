@@ -0,0 +1,35 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, ShareLock},
+};
+
+pub fn execute(
+    env: Env,
+    shareholder: Address,
+    amount: i128,
+    unlock_ledger: u32,
+) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // The locked amount cannot be negative, 0 removes the lock
+    if amount < 0 {
+        return Err(Error::NegativeShareAmount);
+    }
+
+    ShareLock::save(&env, &shareholder, amount, unlock_ledger);
+
+    // Emit lock event
+    env.events().publish(
+        (symbol_short!("lock"), shareholder),
+        (amount, unlock_ledger),
+    );
+
+    Ok(())
+}
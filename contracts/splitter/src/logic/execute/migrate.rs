@@ -0,0 +1,35 @@
+use soroban_sdk::{symbol_short, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SaleListingDataKey, CONTRACT_VERSION},
+};
+
+pub fn execute(env: Env) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    let version = ConfigDataKey::get_version(&env);
+
+    // Storage layout changes are migrated here, one version at a time.
+    // Version 2 added fields to the sale listings, which older listings are rewritten with.
+    // Share locks, epochs and the extended keys are new keys that read as empty when missing,
+    // so they don't need a migration.
+    if version < 2 {
+        SaleListingDataKey::migrate_listings(&env);
+    }
+
+    ConfigDataKey::save_version(&env, CONTRACT_VERSION);
+
+    // Emit migration event
+    env.events().publish(
+        (symbol_short!("migrate"),),
+        (version, CONTRACT_VERSION),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{
+        AllocationDataKey, ConfigDataKey, EscrowDataKey, SaleListingDataKey, SettingsDataKey,
+        ShareDataKey, ShareLock,
+    },
+};
+
+pub fn execute(env: Env, old: Address, new: Address) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // The new address must not hold shares already
+    if ShareDataKey::get_share(&env, &new).is_some() {
+        return Err(Error::ShareholderExists);
+    }
+
+    let share = ShareDataKey::get_share(&env, &old).ok_or(Error::NoSharesToTransfer)?;
+
+    // Locked shares stay with the address they were locked for
+    if ShareLock::get_locked(&env, &old) > 0 {
+        return Err(Error::SharesLocked);
+    }
+    ShareLock::save(&env, &old, 0, 0);
+
+    // Escrowed purchases are settled with the addresses they were made by,
+    // so they must be released or refunded first, on either side
+    if EscrowDataKey::get_escrowed_shares(&env, &old) > 0 {
+        return Err(Error::EscrowActive);
+    }
+    for seller in ShareDataKey::get_shareholders(&env).iter() {
+        if EscrowDataKey::get_escrow(&env, &old, &seller).is_some() {
+            return Err(Error::EscrowActive);
+        }
+    }
+
+    // Move the share entry along with its time weight and checkpoints
+    ShareDataKey::move_share(&env, &old, &new);
+
+    // Replace the address in the shareholders list, keeping its position
+    let mut shareholders = ShareDataKey::get_shareholders(&env);
+    if let Some(index) = shareholders.first_index_of(&old) {
+        shareholders.set(index, new.clone());
+        ShareDataKey::save_shareholders(&env, shareholders);
+    }
+
+    // Move all of the pending allocations
+    for token in AllocationDataKey::get_shareholder_tokens(&env, &old).iter() {
+        let allocation = AllocationDataKey::get_allocation(&env, &old, &token).unwrap_or(0);
+        AllocationDataKey::remove_allocation(&env, &old, &token);
+
+        let new_allocation = AllocationDataKey::get_allocation(&env, &new, &token).unwrap_or(0);
+        AllocationDataKey::save_allocation(&env, &new, &token, new_allocation + allocation);
+    }
+
+    // A compliance hold follows the shareholder to the new address
+    if SettingsDataKey::is_frozen(&env, &old) {
+        SettingsDataKey::save_frozen(&env, &old, false);
+        SettingsDataKey::save_frozen(&env, &new, true);
+    }
+
+    // The old address can no longer sell, so drop its listing
+    if SaleListingDataKey::get_listing(&env, &old).is_some() {
+        SaleListingDataKey::remove_listing(&env, &old);
+    }
+
+    // Emit migrated event
+    env.events().publish(
+        (symbol_short!("migrated"), old, new),
+        share.share,
+    );
+
+    Ok(())
+}
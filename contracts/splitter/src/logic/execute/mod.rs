@@ -1,25 +1,143 @@
+mod approve_withdraw_operator;
+mod claim_epoch;
+mod claim_initial_admin;
+mod consolidate_shares;
+mod distribute_amount;
+mod distribute_time_weighted;
+mod distribute_to_subset;
 mod distribute_tokens;
+mod freeze_shareholder;
 mod init;
 mod lock_contract;
+mod lock_shares;
+mod migrate;
+mod migrate_shareholder;
+mod push_allocation;
+mod rebuild_shareholders;
+mod set_buy_commission_exempt;
+mod set_commission_floor;
+mod set_distribution_cooldown;
+mod set_distribution_optout;
+mod set_distributor;
+mod set_emit_per_shareholder_events;
+mod set_fixed_payee;
+mod set_max_active_listings;
+mod set_max_allocation_per_dist;
+mod set_min_claimable;
+mod set_min_seller_proceeds;
+mod set_min_shareholders;
+mod set_recipient_pays_commission;
+mod set_reinvest_bps;
+mod set_share_lot_size;
+mod set_transfer_allowlist;
+mod set_transfer_commission;
+mod set_transfer_cooldown;
+mod set_treasury;
+mod start_epoch;
+mod sweep_buy_commission;
+mod transfer_all_unused;
 mod transfer_tokens;
 mod transfer_shares;
 mod update_shares;
+mod update_shares_with_remainder;
 mod withdraw_allocation;
+mod withdraw_allocations_for;
+mod withdraw_reinvest_pool;
 
 // Marketplace execute functions
+mod activate_listing;
+mod buy_from_pool;
 mod buy_shares;
+mod buy_shares_by_id;
+mod buy_shares_multi;
+mod cancel_all_my_orders;
 mod cancel_listing;
+mod contribute_to_pool;
+mod create_listing;
+mod deposit_for_purchase;
+mod leave_pool;
 mod list_shares_for_sale;
+mod prune_expired_listings;
+mod refund_purchase;
+mod release_purchase;
+mod set_allowed_buyers;
+mod set_cancel_penalty;
+mod set_credit_proceeds;
+mod set_identity;
+mod set_listing_activation;
+mod set_listing_expiration;
+mod set_listing_fee;
+mod set_max_per_buyer;
+mod set_seller_blocked;
 
+pub use approve_withdraw_operator::execute as approve_withdraw_operator;
+pub use claim_epoch::execute as claim_epoch;
+pub use claim_initial_admin::execute as claim_initial_admin;
+pub use consolidate_shares::execute as consolidate_shares;
+pub use distribute_amount::execute as distribute_amount;
+pub use distribute_time_weighted::execute as distribute_time_weighted;
+pub use distribute_to_subset::execute as distribute_to_subset;
 pub use distribute_tokens::execute as distribute_tokens;
+pub use freeze_shareholder::execute as freeze_shareholder;
 pub use init::execute as init;
 pub use lock_contract::execute as lock_contract;
+pub use lock_shares::execute as lock_shares;
+pub use migrate::execute as migrate;
+pub use migrate_shareholder::execute as migrate_shareholder;
+pub use push_allocation::execute as push_allocation;
+pub use rebuild_shareholders::execute as rebuild_shareholders;
+pub use set_buy_commission_exempt::execute as set_buy_commission_exempt;
+pub use set_commission_floor::execute as set_commission_floor;
+pub use set_distribution_cooldown::execute as set_distribution_cooldown;
+pub use set_distribution_optout::execute as set_distribution_optout;
+pub use set_distributor::execute as set_distributor;
+pub use set_emit_per_shareholder_events::execute as set_emit_per_shareholder_events;
+pub use set_fixed_payee::execute as set_fixed_payee;
+pub use set_max_active_listings::execute as set_max_active_listings;
+pub use set_max_allocation_per_dist::execute as set_max_allocation_per_dist;
+pub use set_min_claimable::execute as set_min_claimable;
+pub use set_min_seller_proceeds::execute as set_min_seller_proceeds;
+pub use set_min_shareholders::execute as set_min_shareholders;
+pub use set_recipient_pays_commission::execute as set_recipient_pays_commission;
+pub use set_reinvest_bps::execute as set_reinvest_bps;
+pub use set_share_lot_size::execute as set_share_lot_size;
+pub use set_transfer_allowlist::execute as set_transfer_allowlist;
+pub use set_transfer_commission::execute as set_transfer_commission;
+pub use set_transfer_cooldown::execute as set_transfer_cooldown;
+pub use set_treasury::execute as set_treasury;
+pub use start_epoch::execute as start_epoch;
+pub use sweep_buy_commission::execute as sweep_buy_commission;
+pub use transfer_all_unused::execute as transfer_all_unused;
 pub use transfer_tokens::execute as transfer_tokens;
 pub use transfer_shares::execute as transfer_shares;
 pub use update_shares::execute as update_shares;
+pub use update_shares_with_remainder::execute as update_shares_with_remainder;
 pub use withdraw_allocation::execute as withdraw_allocation;
+pub use withdraw_allocations_for::execute as withdraw_allocations_for;
+pub use withdraw_reinvest_pool::execute as withdraw_reinvest_pool;
 
 // Marketplace exports
+pub use activate_listing::execute as activate_listing;
+pub use buy_from_pool::execute as buy_from_pool;
 pub use buy_shares::execute as buy_shares;
+pub use buy_shares_by_id::execute as buy_shares_by_id;
+pub use buy_shares_multi::execute as buy_shares_multi;
+pub use cancel_all_my_orders::execute as cancel_all_my_orders;
 pub use cancel_listing::execute as cancel_listing;
+pub use contribute_to_pool::execute as contribute_to_pool;
+pub use create_listing::execute as create_listing;
+pub use deposit_for_purchase::execute as deposit_for_purchase;
+pub use leave_pool::execute as leave_pool;
 pub use list_shares_for_sale::execute as list_shares_for_sale;
+pub use prune_expired_listings::execute as prune_expired_listings;
+pub use refund_purchase::execute as refund_purchase;
+pub use release_purchase::execute as release_purchase;
+pub use set_allowed_buyers::execute as set_allowed_buyers;
+pub use set_cancel_penalty::execute as set_cancel_penalty;
+pub use set_credit_proceeds::execute as set_credit_proceeds;
+pub use set_identity::execute as set_identity;
+pub use set_listing_activation::execute as set_listing_activation;
+pub use set_listing_expiration::execute as set_listing_expiration;
+pub use set_listing_fee::execute as set_listing_fee;
+pub use set_max_per_buyer::execute as set_max_per_buyer;
+pub use set_seller_blocked::execute as set_seller_blocked;
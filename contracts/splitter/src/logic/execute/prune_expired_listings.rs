@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Env, Vec};
+
+use crate::{errors::Error, storage::SaleListingDataKey};
+
+pub fn execute(env: Env, limit: u32) -> Result<u32, Error> {
+    let active_sellers = SaleListingDataKey::get_active_listings(&env);
+
+    // Collect the expired listings first, removing them changes the active listings
+    let mut expired_sellers = Vec::new(&env);
+    for seller in active_sellers.iter().take(limit as usize) {
+        if let Some(listing) = SaleListingDataKey::get_listing(&env, &seller) {
+            if listing.is_expired(&env) {
+                expired_sellers.push_back(seller);
+            }
+        }
+    }
+
+    for seller in expired_sellers.iter() {
+        SaleListingDataKey::remove_listing(&env, &seller);
+
+        // Emit listing pruned event
+        env.events().publish((symbol_short!("pruned"), seller), ());
+    }
+
+    Ok(expired_sellers.len())
+}
@@ -0,0 +1,40 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    logic::helpers::{check_not_frozen, get_token_client},
+    storage::{AllocationDataKey, ConfigDataKey},
+};
+
+/// Transfers the whole allocation of the shareholder to them, without their signature
+pub fn execute(env: Env, token_address: Address, shareholder: Address) -> Result<i128, Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // A frozen shareholder must be unfrozen before their allocation can leave the contract
+    check_not_frozen(&env, &shareholder)?;
+
+    let allocation =
+        AllocationDataKey::get_allocation(&env, &shareholder, &token_address).unwrap_or(0);
+    if allocation <= 0 {
+        return Err(Error::ZeroWithdrawalAmount);
+    }
+
+    AllocationDataKey::remove_allocation(&env, &shareholder, &token_address);
+
+    // The tokens can only ever go to the shareholder
+    let token_client = get_token_client(&env, &token_address);
+    token_client.transfer(&env.current_contract_address(), &shareholder, &allocation);
+
+    // Emit pushed event
+    env.events().publish(
+        (symbol_short!("pushed"), shareholder),
+        (token_address, allocation),
+    );
+
+    Ok(allocation)
+}
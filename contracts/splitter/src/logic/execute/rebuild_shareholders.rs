@@ -0,0 +1,41 @@
+use soroban_sdk::{symbol_short, Address, Env, Vec};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, ShareDataKey},
+};
+
+/// Prunes shareholders vector entries without a share entry, and duplicated entries.
+///
+/// Persistent keys cannot be enumerated, so share entries missing from the vector
+/// cannot be recovered, use `update_shares` to restore those.
+pub fn execute(env: Env) -> Result<u32, Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    let shareholders = ShareDataKey::get_shareholders(&env);
+    let mut rebuilt: Vec<Address> = Vec::new(&env);
+
+    for shareholder in shareholders.iter() {
+        if rebuilt.contains(&shareholder) {
+            continue;
+        }
+        if ShareDataKey::get_share(&env, &shareholder).is_some() {
+            rebuilt.push_back(shareholder);
+        }
+    }
+
+    let pruned = shareholders.len() - rebuilt.len();
+    if pruned > 0 {
+        ShareDataKey::save_shareholders(&env, rebuilt);
+    }
+
+    // Emit rebuilt event with the number of pruned entries
+    env.events().publish((symbol_short!("rebuilt"),), pruned);
+
+    Ok(pruned)
+}
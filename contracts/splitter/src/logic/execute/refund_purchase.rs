@@ -0,0 +1,53 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    logic::helpers::get_token_client,
+    storage::{ConfigDataKey, EscrowDataKey, SaleListingDataKey},
+};
+
+pub fn execute(env: Env, buyer: Address, seller: Address) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Require buyer authorization
+    buyer.require_auth();
+
+    let escrow = EscrowDataKey::get_escrow(&env, &buyer, &seller).ok_or(Error::NoEscrow)?;
+
+    // The seller has until the expiration to release the purchase
+    if env.ledger().sequence() < escrow.expiration_ledger {
+        return Err(Error::EscrowActive);
+    }
+
+    refund(&env, escrow);
+
+    Ok(())
+}
+
+/// Removes the escrowed purchase and returns its funds to the buyer, the reserved shares
+/// go back to the seller's listing if it is still open
+pub(super) fn refund(env: &Env, escrow: EscrowDataKey) {
+    EscrowDataKey::remove_escrow(env, &escrow);
+
+    if let Some(listing) = SaleListingDataKey::get_listing(env, &escrow.seller) {
+        SaleListingDataKey::save_listing(
+            env,
+            &SaleListingDataKey {
+                shares_for_sale: listing.shares_for_sale + escrow.shares_amount,
+                ..listing
+            },
+        );
+    }
+
+    // Return the escrowed funds to the buyer
+    let token_client = get_token_client(env, &escrow.payment_token);
+    token_client.transfer(&env.current_contract_address(), &escrow.buyer, &escrow.total_price);
+
+    // Emit refunded event
+    env.events().publish(
+        (symbol_short!("refunded"), escrow.buyer, escrow.seller),
+        (escrow.total_price, escrow.payment_token),
+    );
+}
@@ -0,0 +1,75 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    logic::helpers::{
+        check_min_shareholders, check_not_frozen, get_buy_commission, get_token_client, move_shares,
+        pay_buy_commission,
+    },
+    storage::{
+        BuyerSpentDataKey, ConfigDataKey, EscrowDataKey, ShareDataKey,
+    },
+};
+
+pub fn execute(env: Env, seller: Address, buyer: Address) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // The seller confirms the purchase
+    seller.require_auth();
+
+    let escrow = EscrowDataKey::get_escrow(&env, &buyer, &seller).ok_or(Error::NoEscrow)?;
+
+    // Shares can't move while either side is frozen, the buyer can still be refunded
+    check_not_frozen(&env, &seller)?;
+    check_not_frozen(&env, &buyer)?;
+
+    // Get current share data
+    let seller_share_data =
+        ShareDataKey::get_share(&env, &seller).ok_or(Error::NoSharesToSell)?;
+
+    // Make sure the sale doesn't shrink the shareholder set below the minimum
+    check_min_shareholders(
+        &env,
+        seller_share_data.share <= escrow.shares_amount,
+        ShareDataKey::get_share(&env, &buyer).is_none(),
+    )?;
+
+    // Release the escrowed funds and shares, so the shares reserved for the buyer can move
+    EscrowDataKey::remove_escrow(&env, &escrow);
+
+    // Move the shares from the seller to the buyer
+    move_shares(&env, &seller, &buyer, escrow.shares_amount)?;
+
+    // Track the buyer's spending
+    BuyerSpentDataKey::add_spent(&env, &buyer, &escrow.payment_token, escrow.total_price)?;
+
+    // No commission when either side is exempt
+    let commission = get_buy_commission(&env, &seller, &buyer, escrow.total_price);
+    let seller_receives = escrow.total_price - commission;
+
+    let token_client = get_token_client(&env, &escrow.payment_token);
+
+    // Pay seller (total - commission)
+    if seller_receives > 0 {
+        token_client.transfer(&env.current_contract_address(), &seller, &seller_receives);
+    }
+
+    // Pay commission to recipient, or keep it in the contract until the recipient sweeps it
+    pay_buy_commission(
+        &env,
+        &escrow.payment_token,
+        &env.current_contract_address(),
+        &buyer,
+        commission,
+    )?;
+
+    // Emit released event
+    env.events().publish(
+        (symbol_short!("released"), seller, buyer),
+        (escrow.shares_amount, escrow.total_price, escrow.payment_token),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use soroban_sdk::{symbol_short, Address, Env, Vec};
+
+use crate::{
+    errors::Error,
+    storage::{SaleListingDataKey, MAX_ALLOWED_BUYERS},
+};
+
+pub fn execute(env: Env, seller: Address, allowed_buyers: Vec<Address>) -> Result<(), Error> {
+    // Require seller authorization
+    seller.require_auth();
+
+    // Keep the whitelist bounded
+    if allowed_buyers.len() > MAX_ALLOWED_BUYERS {
+        return Err(Error::TooManyItems);
+    }
+
+    let mut listing =
+        SaleListingDataKey::get_listing(&env, &seller).ok_or(Error::NoActiveListing)?;
+
+    listing.allowed_buyers = allowed_buyers.clone();
+    SaleListingDataKey::save_listing(&env, &listing);
+
+    // Emit whitelist event
+    env.events().publish(
+        (symbol_short!("buyers"), seller),
+        allowed_buyers,
+    );
+
+    Ok(())
+}
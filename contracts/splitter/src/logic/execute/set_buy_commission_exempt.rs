@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, address: Address, exempt: bool) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Exempt or stop exempting the address
+    SettingsDataKey::save_buy_commission_exempt(&env, &address, exempt);
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("com_exmpt"), address),
+        exempt,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Env};
+
+use crate::{
+    errors::Error,
+    storage::{CancelPenalty, ConfigDataKey},
+};
+
+pub fn execute(env: Env, bps: i128, min_age_ledgers: u32) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Update the early cancellation penalty
+    CancelPenalty::set(&env, bps, min_age_ledgers)?;
+
+    // Emit cancel penalty event
+    env.events().publish(
+        (symbol_short!("cxl_pen"),),
+        (bps, min_age_ledgers),
+    );
+
+    Ok(())
+}
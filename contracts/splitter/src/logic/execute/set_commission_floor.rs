@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Env};
+
+use crate::{
+    errors::Error,
+    storage::{CommissionFloor, ConfigDataKey},
+};
+
+pub fn execute(env: Env, buy_rate_bps: i128, distribution_rate_bps: i128) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Update the commission floor
+    CommissionFloor::set(&env, buy_rate_bps, distribution_rate_bps)?;
+
+    // Emit commission floor event
+    env.events().publish(
+        (symbol_short!("com_floor"),),
+        (buy_rate_bps, distribution_rate_bps),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{errors::Error, storage::SaleListingDataKey};
+
+pub fn execute(env: Env, seller: Address, credit_proceeds: bool) -> Result<(), Error> {
+    // Require seller authorization
+    seller.require_auth();
+
+    let mut listing =
+        SaleListingDataKey::get_listing(&env, &seller).ok_or(Error::NoActiveListing)?;
+
+    listing.credit_proceeds = credit_proceeds;
+    SaleListingDataKey::save_listing(&env, &listing);
+
+    // Emit proceeds setting event
+    env.events().publish(
+        (symbol_short!("proceeds"), seller),
+        credit_proceeds,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, token_address: Address, cooldown_ledgers: u32) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Update the distribution cooldown of the token, 0 disables it
+    SettingsDataKey::save_distribution_cooldown_ledgers(&env, &token_address, cooldown_ledgers);
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("dist_cool"), token_address),
+        cooldown_ledgers,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, shareholder: Address, opted_out: bool) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Only the shareholder can decline their own allocations
+    shareholder.require_auth();
+
+    SettingsDataKey::save_opted_out(&env, &shareholder, opted_out);
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("opt_out"), shareholder),
+        opted_out,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, distributor: Address, enabled: bool) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Allow or disallow the distributor
+    SettingsDataKey::save_distributor(&env, &distributor, enabled);
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("distribtr"), distributor),
+        enabled,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, FixedPayee},
+};
+
+pub fn execute(env: Env, payee: Address, bps: i128) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Update the fixed payee
+    FixedPayee::set(&env, payee.clone(), bps)?;
+
+    // Emit fixed payee event
+    env.events().publish(
+        (symbol_short!("fix_payee"), payee),
+        bps,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, address: Address, identity: u64) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Link or unlink the address
+    SettingsDataKey::save_identity(&env, &address, identity);
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("identity"), address),
+        identity,
+    );
+
+    Ok(())
+}
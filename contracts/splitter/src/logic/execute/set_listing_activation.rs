@@ -0,0 +1,24 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{errors::Error, storage::SaleListingDataKey};
+
+pub fn execute(env: Env, seller: Address, activation_ledger: u32) -> Result<(), Error> {
+    // Require seller authorization
+    seller.require_auth();
+
+    let mut listing =
+        SaleListingDataKey::get_listing(&env, &seller).ok_or(Error::NoActiveListing)?;
+
+    // A new delay is announced again once it is reached
+    listing.activation_ledger = activation_ledger;
+    listing.activation_emitted = false;
+    SaleListingDataKey::save_listing(&env, &listing);
+
+    // Emit activation delay event
+    env.events().publish(
+        (symbol_short!("list_dly"), seller),
+        activation_ledger,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{errors::Error, storage::SaleListingDataKey};
+
+pub fn execute(env: Env, seller: Address, expiration_ledger: u32) -> Result<(), Error> {
+    // Require seller authorization
+    seller.require_auth();
+
+    let mut listing =
+        SaleListingDataKey::get_listing(&env, &seller).ok_or(Error::NoActiveListing)?;
+
+    listing.expiration_ledger = expiration_ledger;
+    SaleListingDataKey::save_listing(&env, &listing);
+
+    // Emit expiration event
+    env.events().publish(
+        (symbol_short!("list_exp"), seller),
+        expiration_ledger,
+    );
+
+    Ok(())
+}
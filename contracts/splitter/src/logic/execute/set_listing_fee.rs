@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, ListingFee},
+};
+
+pub fn execute(env: Env, bps: i128, refund_window_ledgers: u32) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Update the listing fee
+    ListingFee::set(&env, bps, refund_window_ledgers)?;
+
+    // Emit listing fee event
+    env.events().publish(
+        (symbol_short!("list_fee"),),
+        (bps, refund_window_ledgers),
+    );
+
+    Ok(())
+}
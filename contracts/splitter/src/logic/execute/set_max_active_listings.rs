@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, max_active_listings: u32) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Update the maximum number of active listings
+    SettingsDataKey::save_max_active_listings(&env, max_active_listings);
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("max_list"),),
+        max_active_listings,
+    );
+
+    Ok(())
+}
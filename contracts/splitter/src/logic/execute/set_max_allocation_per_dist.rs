@@ -0,0 +1,31 @@
+use soroban_sdk::{symbol_short, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, max_allocation: i128) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // The cap cannot be negative, 0 disables it
+    if max_allocation < 0 {
+        return Err(Error::InvalidAllocationCap);
+    }
+
+    // Update the per-distribution allocation cap
+    SettingsDataKey::save_max_allocation_per_dist(&env, max_allocation);
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("max_alloc"),),
+        max_allocation,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{errors::Error, storage::SaleListingDataKey};
+
+pub fn execute(env: Env, seller: Address, max_per_buyer: i128) -> Result<(), Error> {
+    // Require seller authorization
+    seller.require_auth();
+
+    if max_per_buyer < 0 {
+        return Err(Error::InvalidShareAmount);
+    }
+
+    let mut listing =
+        SaleListingDataKey::get_listing(&env, &seller).ok_or(Error::NoActiveListing)?;
+
+    listing.max_per_buyer = max_per_buyer;
+    SaleListingDataKey::save_listing(&env, &listing);
+
+    // Emit per-buyer cap event
+    env.events().publish(
+        (symbol_short!("buyer_cap"), seller),
+        max_per_buyer,
+    );
+
+    Ok(())
+}
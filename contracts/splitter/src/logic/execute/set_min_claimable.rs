@@ -0,0 +1,31 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, token_address: Address, min_claimable: i128) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // The minimum cannot be negative, 0 removes it
+    if min_claimable < 0 {
+        return Err(Error::ZeroWithdrawalAmount);
+    }
+
+    // Update the minimum withdrawal of the token
+    SettingsDataKey::save_min_claimable(&env, &token_address, min_claimable);
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("min_claim"), token_address),
+        min_claimable,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, token_address: Address, min_proceeds: i128) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Sellers must always receive something
+    if min_proceeds < 1 {
+        return Err(Error::InvalidPrice);
+    }
+
+    // Update the minimum seller proceeds of the token
+    SettingsDataKey::save_min_seller_proceeds(&env, &token_address, min_proceeds);
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("min_proc"), token_address),
+        min_proceeds,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, min_shareholders: u32) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Update the minimum number of shareholders
+    SettingsDataKey::save_min_shareholders(&env, min_shareholders);
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("min_sh"),),
+        min_shareholders,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use soroban_sdk::{symbol_short, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, enabled: bool) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin, the recipient cannot waive its own commission
+    ConfigDataKey::require_admin(&env)?;
+
+    SettingsDataKey::save_recipient_pays_commission(&env, enabled);
+
+    // Emit settings event
+    env.events().publish((symbol_short!("rcpt_pays"),), enabled);
+
+    Ok(())
+}
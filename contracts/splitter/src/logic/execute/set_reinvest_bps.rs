@@ -0,0 +1,28 @@
+use soroban_sdk::{symbol_short, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, bps: i128) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // The reinvestment share must be between 0 and 100%
+    if !(0..=10000).contains(&bps) {
+        return Err(Error::InvalidReinvestShare);
+    }
+
+    // Update the reinvestment share
+    SettingsDataKey::save_reinvest_bps(&env, bps);
+
+    // Emit settings event
+    env.events().publish((symbol_short!("reinv_bps"),), bps);
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, seller: Address, blocked: bool) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Block or unblock the seller
+    SettingsDataKey::save_seller_blocked(&env, &seller, blocked);
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("blocked"), seller),
+        blocked,
+    );
+
+    Ok(())
+}
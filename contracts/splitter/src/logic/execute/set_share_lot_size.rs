@@ -0,0 +1,31 @@
+use soroban_sdk::{symbol_short, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey, ShareDataKey},
+};
+
+pub fn execute(env: Env, share_lot_size: i128) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // The lot size must fit in the total shares
+    if share_lot_size <= 0 || share_lot_size > ShareDataKey::get_share_denominator(&env) {
+        return Err(Error::InvalidShareAmount);
+    }
+
+    // Update the share lot size
+    SettingsDataKey::save_share_lot_size(&env, share_lot_size);
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("lot_size"),),
+        share_lot_size,
+    );
+
+    Ok(())
+}
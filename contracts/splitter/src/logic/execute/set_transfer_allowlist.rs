@@ -0,0 +1,31 @@
+use soroban_sdk::{symbol_short, Address, Env, Vec};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey, MAX_TRANSFER_ALLOWLIST},
+};
+
+pub fn execute(env: Env, recipients: Vec<Address>) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Keep the allowlist bounded
+    if recipients.len() > MAX_TRANSFER_ALLOWLIST {
+        return Err(Error::TooManyItems);
+    }
+
+    // Replace the allowlist, an empty list lifts the restriction
+    SettingsDataKey::save_transfer_allowlist(&env, &recipients);
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("allowlist"),),
+        recipients,
+    );
+
+    Ok(())
+}
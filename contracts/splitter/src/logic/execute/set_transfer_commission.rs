@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, TransferCommission},
+};
+
+pub fn execute(env: Env, token: Address, transfer_commission_bps: i128) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Update the share transfer commission
+    TransferCommission::set(&env, token.clone(), transfer_commission_bps)?;
+
+    // Emit transfer commission event
+    env.events().publish(
+        (symbol_short!("xfer_com"), token),
+        transfer_commission_bps,
+    );
+
+    Ok(())
+}
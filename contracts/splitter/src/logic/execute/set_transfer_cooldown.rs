@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, cooldown_ledgers: u32) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Update the share transfer cooldown, 0 disables it
+    SettingsDataKey::save_transfer_cooldown_ledgers(&env, cooldown_ledgers);
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("cooldown"),),
+        cooldown_ledgers,
+    );
+
+    Ok(())
+}
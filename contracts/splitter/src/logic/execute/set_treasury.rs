@@ -0,0 +1,26 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, treasury: Address) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Update the treasury
+    SettingsDataKey::save_treasury(&env, treasury.clone());
+
+    // Emit settings event
+    env.events().publish(
+        (symbol_short!("treasury"),),
+        treasury,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use soroban_sdk::{symbol_short, Address, Env, Map};
+
+use crate::{
+    errors::Error,
+    logic::helpers::{get_token_client, get_unused_balance},
+    storage::{
+        AllocationDataKey, CommissionConfig, ConfigDataKey, EpochDataKey, SettingsDataKey,
+        ShareDataKey,
+    },
+};
+
+pub fn execute(
+    env: Env,
+    token_address: Address,
+    amount: i128,
+    duration_ledgers: u32,
+) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // Epoch amount cannot be equal and less than 0
+    if amount <= 0 {
+        return Err(Error::ZeroTransferAmount);
+    };
+
+    // Vesting needs at least one ledger
+    if duration_ledgers == 0 {
+        return Err(Error::InvalidEpochDuration);
+    }
+
+    // Epochs disable claiming the initial admin like any other distribution
+    ConfigDataKey::mark_activity(&env);
+
+    // Only one epoch per token can vest at a time
+    let previous_id = match EpochDataKey::get_epoch(&env, &token_address) {
+        Some(epoch) => {
+            if !epoch.is_ended(&env) {
+                return Err(Error::EpochActive);
+            }
+            settle(&env, &token_address, &epoch)?;
+            epoch.id
+        }
+        None => 0,
+    };
+
+    // Epoch amount cannot be greater than the unused balance
+    if amount > get_unused_balance(&env, &token_address) {
+        return Err(Error::TransferAmountAboveUnusedBalance);
+    };
+
+    // The epoch vests to the shares at its start, later share movements don't change who claims.
+    // Shareholders that opted out are left out, the others absorb their portion
+    let mut shares: Map<Address, i128> = Map::new(&env);
+    for shareholder in ShareDataKey::get_shareholders(&env).iter() {
+        if SettingsDataKey::is_opted_out(&env, &shareholder) {
+            continue;
+        }
+        if let Some(ShareDataKey { share, .. }) = ShareDataKey::get_share(&env, &shareholder) {
+            if share > 0 {
+                shares.set(shareholder, share);
+            }
+        }
+    }
+
+    // Without shareholders nothing can vest, so don't take any commission either
+    if shares.is_empty() {
+        return Err(Error::NoShareholders);
+    }
+
+    // Take the distribution commission upfront, the rest vests to the shareholders
+    let commission_config = CommissionConfig::get(&env);
+    let commission =
+        CommissionConfig::calculate_commission(amount, commission_config.distribution_rate_bps);
+
+    // A recipient set to the contract itself leaves the commission as unused balance
+    if commission > 0 && commission_config.recipient != env.current_contract_address() {
+        let token_client = get_token_client(&env, &token_address);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &commission_config.recipient,
+            &commission,
+        );
+        CommissionConfig::add_commission_earned(&env, &token_address, 0, commission)?;
+
+        // Emit commission event
+        env.events().publish(
+            (symbol_short!("dist_com"), token_address.clone()),
+            (commission_config.recipient, commission),
+        );
+    }
+
+    let epoch = EpochDataKey {
+        id: previous_id + 1,
+        amount: amount - commission,
+        start_ledger: env.ledger().sequence(),
+        duration_ledgers,
+        claimed: 0,
+    };
+    EpochDataKey::save_epoch(&env, &token_address, &epoch);
+    EpochDataKey::save_epoch_shares(&env, &token_address, &shares);
+
+    // Emit epoch event
+    env.events().publish(
+        (symbol_short!("epoch"), token_address),
+        (epoch.id, epoch.amount, epoch.start_ledger, duration_ledgers),
+    );
+
+    Ok(())
+}
+
+/// Moves whatever the shareholders left unclaimed in the ended epoch into their allocations
+fn settle(env: &Env, token_address: &Address, epoch: &EpochDataKey) -> Result<(), Error> {
+    let mut remaining = epoch.amount - epoch.claimed;
+    let (shares, share_total) = epoch_shares(env, token_address);
+
+    for (shareholder, share) in shares.iter() {
+        if remaining <= 0 {
+            break;
+        }
+
+        if share <= 0 {
+            continue;
+        }
+
+        let entitled = epoch.amount.checked_mul(share).ok_or(Error::Overflow)? / share_total;
+        let owed = (entitled - epoch.get_claimed(env, token_address, &shareholder)).min(remaining);
+
+        if owed > 0 {
+            let allocation =
+                AllocationDataKey::get_allocation(env, &shareholder, token_address).unwrap_or(0);
+            AllocationDataKey::save_allocation(env, &shareholder, token_address, allocation + owed);
+            remaining -= owed;
+        }
+    }
+
+    // Any rounding dust left in the epoch becomes unused balance again
+    EpochDataKey::remove_epoch(env, token_address);
+
+    Ok(())
+}
+
+/// Returns the shares the epoch of the token vests to and their total, opted out shareholders
+/// excluded. Epochs started before the shares were snapshotted fall back to the current shares.
+pub(super) fn epoch_shares(env: &Env, token_address: &Address) -> (Map<Address, i128>, i128) {
+    let shares = match EpochDataKey::get_epoch_shares(env, token_address) {
+        Some(shares) => shares,
+        None => {
+            let mut shares: Map<Address, i128> = Map::new(env);
+            for shareholder in ShareDataKey::get_shareholders(env).iter() {
+                if SettingsDataKey::is_opted_out(env, &shareholder) {
+                    continue;
+                }
+                if let Some(ShareDataKey { share, .. }) = ShareDataKey::get_share(env, &shareholder) {
+                    shares.set(shareholder, share);
+                }
+            }
+            shares
+        }
+    };
+
+    let share_total = shares.values().iter().filter(|share| *share > 0).sum();
+    (shares, share_total)
+}
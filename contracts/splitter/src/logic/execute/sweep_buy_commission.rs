@@ -0,0 +1,46 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    logic::helpers::get_token_client,
+    storage::{CommissionConfig, ListingFee},
+};
+
+pub fn execute(env: Env, token_address: Address) -> Result<(), Error> {
+    // Only the commission recipient can sweep the accrued commission
+    let commission_config = CommissionConfig::get(&env);
+    commission_config.recipient.require_auth();
+
+    // Listing fees are swept along, except the part active listings could still get refunded
+    let accrued_commission = CommissionConfig::get_accrued_buy_commission(&env, &token_address);
+    let listing_fees = (ListingFee::get_accrued(&env, &token_address)
+        - ListingFee::get_refundable(&env, &token_address)?)
+    .max(0);
+    let accrued = accrued_commission
+        .checked_add(listing_fees)
+        .ok_or(Error::Overflow)?;
+
+    // If nothing was accrued, return early
+    if accrued <= 0 {
+        return Ok(());
+    }
+
+    CommissionConfig::remove_accrued_buy_commission(&env, &token_address);
+    ListingFee::add_accrued(&env, &token_address, -listing_fees)?;
+
+    // Transfer the accrued commission to the recipient in one go
+    let token_client = get_token_client(&env, &token_address);
+    token_client.transfer(
+        &env.current_contract_address(),
+        &commission_config.recipient,
+        &accrued,
+    );
+
+    // Emit sweep event
+    env.events().publish(
+        (symbol_short!("com_sweep"), token_address),
+        (commission_config.recipient, accrued),
+    );
+
+    Ok(())
+}
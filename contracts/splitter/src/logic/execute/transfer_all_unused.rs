@@ -0,0 +1,41 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    logic::helpers::{get_token_client, get_unused_balance},
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn execute(env: Env, token_address: Address, recipient: Address) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // When an allowlist is set, tokens can only go to the listed recipients
+    if !SettingsDataKey::is_transfer_recipient_allowed(&env, &recipient) {
+        return Err(Error::RecipientNotAllowed);
+    }
+
+    // Calculate the unused balance, allocations and escrowed funds are never swept
+    let unused_balance = get_unused_balance(&env, &token_address);
+
+    // If there's nothing to sweep, return early
+    if unused_balance <= 0 {
+        return Ok(());
+    }
+
+    // Transfer the unused tokens to the recipient
+    let token_client = get_token_client(&env, &token_address);
+    token_client.transfer(&env.current_contract_address(), &recipient, &unused_balance);
+
+    // Emit transfer event
+    env.events().publish(
+        (symbol_short!("transfer"), recipient),
+        (token_address, unused_balance),
+    );
+
+    Ok(())
+}
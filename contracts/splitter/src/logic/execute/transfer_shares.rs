@@ -2,13 +2,19 @@ use soroban_sdk::{symbol_short, Address, Env};
 
 use crate::{
     errors::Error,
-    storage::{ConfigDataKey, ShareDataKey},
+    logic::helpers::{
+        check_min_shareholders, check_not_frozen, check_share_granularity, get_free_shares,
+        get_token_client,
+    },
+    storage::{
+        CommissionConfig, ConfigDataKey, SettingsDataKey, ShareDataKey, TransferCommission,
+    },
 };
 
 /// Transfers shares from one shareholder to another.
 ///
 /// Any shareholder can transfer part or all of their shares to another address.
-/// The sender must authorize the transaction.
+/// The sender must authorize the transaction, and pays the transfer commission if one is set.
 ///
 /// ## Arguments
 ///
@@ -25,6 +31,9 @@ pub fn execute(env: Env, from: Address, to: Address, amount: i128) -> Result<(),
     // Sender must authorize
     from.require_auth();
 
+    // Frozen addresses can't send shares, they can still receive them
+    check_not_frozen(&env, &from)?;
+
     // Cannot transfer to self
     if from == to {
         return Err(Error::CannotTransferToSelf);
@@ -35,6 +44,20 @@ pub fn execute(env: Env, from: Address, to: Address, amount: i128) -> Result<(),
         return Err(Error::InvalidShareAmount);
     }
 
+    // Shares can only be transferred in whole lots
+    check_share_granularity(&env, amount)?;
+
+    // Throttle rapid churn, the sender must wait out the cooldown since their last transfer
+    let cooldown_ledgers = SettingsDataKey::get_transfer_cooldown_ledgers(&env);
+    if cooldown_ledgers > 0 {
+        if let Some(last_ledger) = SettingsDataKey::get_last_transfer_ledger(&env, &from) {
+            if env.ledger().sequence() < last_ledger.saturating_add(cooldown_ledgers) {
+                return Err(Error::TransferCooldownActive);
+            }
+        }
+        SettingsDataKey::save_last_transfer_ledger(&env, &from, env.ledger().sequence());
+    }
+
     // Get sender's current shares
     let sender_share = ShareDataKey::get_share(&env, &from);
     match sender_share {
@@ -45,6 +68,11 @@ pub fn execute(env: Env, from: Address, to: Address, amount: i128) -> Result<(),
                 return Err(Error::InsufficientSharesToTransfer);
             }
 
+            // Locked and escrowed shares stay with the sender
+            if amount > get_free_shares(&env, &from) {
+                return Err(Error::SharesLocked);
+            }
+
             // Calculate new shares
             let new_sender_share = share_data.share - amount;
 
@@ -56,6 +84,25 @@ pub fn execute(env: Env, from: Address, to: Address, amount: i128) -> Result<(),
                 None => amount,
             };
 
+            // Make sure the transfer doesn't shrink the shareholder set below the minimum
+            check_min_shareholders(&env, new_sender_share == 0, is_new_shareholder)?;
+
+            // Charge the sender the transfer commission, covered by their auth
+            if let Some(transfer_commission) = TransferCommission::get(&env) {
+                let fee = transfer_commission.calculate(amount)?;
+                if fee > 0 {
+                    let recipient = CommissionConfig::get(&env).recipient;
+                    let token_client = get_token_client(&env, &transfer_commission.token);
+                    token_client.transfer(&from, &recipient, &fee);
+
+                    // Emit transfer commission event
+                    env.events().publish(
+                        (symbol_short!("xfer_fee"), from.clone()),
+                        (transfer_commission.token, fee),
+                    );
+                }
+            }
+
             // Update sender's shares
             if new_sender_share == 0 {
                 // Remove sender from shareholders if they have no shares left
@@ -88,10 +135,10 @@ pub fn execute(env: Env, from: Address, to: Address, amount: i128) -> Result<(),
                 ShareDataKey::save_shareholders(&env, shareholders);
             }
 
-            // Emit transfer event
+            // Emit transfer event with the resulting shares, the sender's are 0 when fully removed
             env.events().publish(
                 (symbol_short!("transfer"), from.clone(), to.clone()),
-                amount,
+                (amount, new_sender_share, new_recipient_share),
             );
 
             Ok(())
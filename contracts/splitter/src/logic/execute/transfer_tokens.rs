@@ -2,8 +2,8 @@ use soroban_sdk::{symbol_short, Address, Env};
 
 use crate::{
     errors::Error,
-    logic::helpers::get_token_client,
-    storage::{AllocationDataKey, ConfigDataKey},
+    logic::helpers::{get_token_client, get_unused_balance},
+    storage::{ConfigDataKey, SettingsDataKey},
 };
 
 pub fn execute(
@@ -19,17 +19,18 @@ pub fn execute(
     // Make sure the caller is the admin
     ConfigDataKey::require_admin(&env)?;
 
+    // When an allowlist is set, tokens can only go to the listed recipients
+    if !SettingsDataKey::is_transfer_recipient_allowed(&env, &recipient) {
+        return Err(Error::RecipientNotAllowed);
+    }
+
     let token_client = get_token_client(&env, &token_address);
 
     // Get the available token balance
     let balance = token_client.balance(&env.current_contract_address());
 
-    // Get the total allocation for the token
-    let total_allocation =
-        AllocationDataKey::get_total_allocation(&env, &token_address).unwrap_or(0);
-
     // Calculate the unused balance that can be transferred
-    let unused_balance = balance - total_allocation;
+    let unused_balance = get_unused_balance(&env, &token_address);
 
     // Transfer amount cannot be equal and less than 0
     if amount <= 0 {
@@ -49,8 +50,15 @@ pub fn execute(
 
     // Emit transfer event
     env.events().publish(
-        (symbol_short!("transfer"), recipient),
-        (token_address, amount),
+        (symbol_short!("transfer"), recipient.clone()),
+        (token_address.clone(), amount),
+    );
+
+    // Emit swept event with what is left to sweep
+    let remaining_unused = get_unused_balance(&env, &token_address);
+    env.events().publish(
+        (symbol_short!("swept"), token_address),
+        (recipient, amount, remaining_unused),
     );
 
     Ok(())
@@ -1,12 +1,23 @@
-use soroban_sdk::{symbol_short, Env, Vec};
+use soroban_sdk::{symbol_short, Address, Env, Vec};
 
 use crate::{
     errors::Error,
-    logic::helpers::{check_shares, reset_shares, update_shares as update_shares_helper},
-    storage::{ConfigDataKey, ShareDataKey},
+    logic::helpers::{
+        check_shareholder_addresses, check_shares, pay_out_allocations, reset_shares,
+        update_shares as update_shares_helper,
+    },
+    storage::{
+        AllocationDataKey, ConfigDataKey, EscrowDataKey, SaleListingDataKey, SettingsDataKey,
+        ShareDataKey,
+    },
 };
 
-pub fn execute(env: Env, shares: Vec<ShareDataKey>) -> Result<(), Error> {
+pub fn execute(
+    env: Env,
+    shares: Vec<ShareDataKey>,
+    clear_removed_allocations: bool,
+    force: bool,
+) -> Result<(), Error> {
     if !ConfigDataKey::exists(&env) {
         return Err(Error::NotInitialized);
     };
@@ -19,8 +30,42 @@ pub fn execute(env: Env, shares: Vec<ShareDataKey>) -> Result<(), Error> {
         return Err(Error::ContractLocked);
     }
 
-    // Check if the shares sum up to 10000
-    check_shares(&shares)?;
+    // Check if the shares sum up to the share denominator
+    check_shares(&shares, ShareDataKey::get_share_denominator(&env))?;
+
+    // Make sure no allocation can end up in an address that cannot withdraw it
+    check_shareholder_addresses(&env, &shares)?;
+
+    for shareholder in ShareDataKey::get_shareholders(&env).iter() {
+        // Shares reserved by escrowed purchases must stay with the seller until released
+        let escrowed = EscrowDataKey::get_escrowed_shares(&env, &shareholder);
+        if escrowed > 0 {
+            let new_share = shares
+                .iter()
+                .find(|share| share.shareholder == shareholder)
+                .map(|share| share.share)
+                .unwrap_or(0);
+            if new_share < escrowed {
+                return Err(Error::EscrowActive);
+            }
+        }
+
+        if shares.iter().any(|share| share.shareholder == shareholder) {
+            continue;
+        }
+
+        if clear_removed_allocations {
+            // Pay out the pending allocations of the shareholders being removed
+            pay_out_allocations(&env, &shareholder);
+        } else if !force {
+            // Don't leave pending allocations behind unless explicitly asked to
+            for token in AllocationDataKey::get_shareholder_tokens(&env, &shareholder).iter() {
+                if AllocationDataKey::get_allocation(&env, &shareholder, &token).unwrap_or(0) > 0 {
+                    return Err(Error::ShareholderHasPendingAllocation);
+                }
+            }
+        }
+    }
 
     // Remove all of the shareholders and their shares
     reset_shares(&env);
@@ -28,6 +73,9 @@ pub fn execute(env: Env, shares: Vec<ShareDataKey>) -> Result<(), Error> {
     // Update the shares of the shareholders
     update_shares_helper(&env, &shares);
 
+    // Open listings cannot offer more shares than their seller holds after the update
+    fit_listings_to_shares(&env);
+
     // Emit shares updated event
     env.events().publish(
         (symbol_short!("shares"),),
@@ -36,3 +84,45 @@ pub fn execute(env: Env, shares: Vec<ShareDataKey>) -> Result<(), Error> {
 
     Ok(())
 }
+
+/// Reduces the listings of sellers that hold fewer shares than they listed,
+/// removing the listings of sellers that can no longer fill a single lot
+fn fit_listings_to_shares(env: &Env) {
+    let lot_size = SettingsDataKey::get_share_lot_size(env);
+
+    // Collect the listings first, removing them changes the active listings
+    let mut oversized: Vec<(Address, i128)> = Vec::new(env);
+    for seller in SaleListingDataKey::get_active_listings(env).iter() {
+        if let Some(listing) = SaleListingDataKey::get_listing(env, &seller) {
+            let seller_shares = ShareDataKey::get_share(env, &seller)
+                .map(|share| share.share)
+                .unwrap_or(0);
+            if listing.shares_for_sale > seller_shares {
+                // Listings are only filled in whole lots
+                oversized.push_back((seller, seller_shares - seller_shares % lot_size));
+            }
+        }
+    }
+
+    for (seller, shares_for_sale) in oversized.iter() {
+        if shares_for_sale > 0 {
+            if let Some(listing) = SaleListingDataKey::get_listing(env, &seller) {
+                SaleListingDataKey::save_listing(
+                    env,
+                    &SaleListingDataKey {
+                        shares_for_sale,
+                        ..listing
+                    },
+                );
+            }
+        } else {
+            SaleListingDataKey::remove_listing(env, &seller);
+        }
+
+        // Emit listing adjusted event, 0 shares when the listing was removed
+        env.events().publish(
+            (symbol_short!("list_adj"), seller),
+            shares_for_sale,
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, ShareDataKey},
+};
+
+/// Applies the share changes on top of the current shares and assigns the rest of the
+/// share denominator to the remainder shareholder.
+///
+/// A change to 0 removes the shareholder, as does a remainder of 0.
+pub fn execute(env: Env, changes: Vec<ShareDataKey>, remainder: Address) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // The remainder is computed, so it cannot be changed explicitly
+    if changes.iter().any(|change| change.shareholder == remainder) {
+        return Err(Error::DuplicateShareholder);
+    }
+
+    // Start from the current shareholders, without the remainder
+    let mut shares: Vec<ShareDataKey> = Vec::new(&env);
+    for shareholder in ShareDataKey::get_shareholders(&env).iter() {
+        if shareholder == remainder || changes.iter().any(|change| change.shareholder == shareholder)
+        {
+            continue;
+        }
+        if let Some(share) = ShareDataKey::get_share(&env, &shareholder) {
+            shares.push_back(share);
+        }
+    }
+
+    // Apply the changes, zero shares remove the shareholder
+    for change in changes.iter() {
+        if change.share < 0 {
+            return Err(Error::NegativeShareAmount);
+        }
+        if change.share > 0 {
+            shares.push_back(change);
+        }
+    }
+
+    let mut total: i128 = 0;
+    for share in shares.iter() {
+        total = total.checked_add(share.share).ok_or(Error::Overflow)?;
+    }
+
+    // The changes alone cannot exceed the share denominator
+    let remainder_share = ShareDataKey::get_share_denominator(&env) - total;
+    if remainder_share < 0 {
+        return Err(Error::InvalidShareTotal);
+    }
+    if remainder_share > 0 {
+        shares.push_back(ShareDataKey {
+            shareholder: remainder,
+            share: remainder_share,
+        });
+    }
+
+    // The admin, lock and allocation checks are the same as for a full update
+    super::update_shares::execute(env, shares, false, false)
+}
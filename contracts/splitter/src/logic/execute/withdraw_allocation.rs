@@ -2,10 +2,13 @@ use soroban_sdk::{symbol_short, Address, Env};
 
 use crate::{
     errors::Error,
-    logic::helpers::get_token_client,
-    storage::{AllocationDataKey, ConfigDataKey},
+    logic::helpers::{check_not_frozen, get_token_client},
+    storage::{AllocationDataKey, ConfigDataKey, SettingsDataKey},
 };
 
+/// Withdraw amount meaning "withdraw the whole allocation"
+const WITHDRAW_ALL: i128 = -1;
+
 pub fn execute(
     env: Env,
     token_address: Address,
@@ -19,20 +22,37 @@ pub fn execute(
     // Make sure the caller is the shareholder
     shareholder.require_auth();
 
+    // Frozen shareholders keep accruing allocations, but can't withdraw them
+    check_not_frozen(&env, &shareholder)?;
+
     let token_client = get_token_client(&env, &token_address);
 
     // Get the current allocation for the user - default to 0
     let allocation =
         AllocationDataKey::get_allocation(&env, &shareholder, &token_address).unwrap_or(0);
 
+    // Resolve the withdraw-all sentinel, which is a no-op without an allocation
+    let amount = if amount == WITHDRAW_ALL {
+        if allocation == 0 {
+            return Ok(());
+        }
+        allocation
+    } else {
+        amount
+    };
+
     // Withdraw amount cannot be equal and less than 0
-    if amount == 0 {
+    if amount <= 0 {
         return Err(Error::ZeroWithdrawalAmount);
     };
     // Withdraw amount cannot be greater than the allocation
     if amount > allocation {
         return Err(Error::WithdrawalAmountAboveAllocation);
     };
+    // Partial withdrawals cannot be smaller than the token minimum, the full allocation always can
+    if amount < allocation && amount < SettingsDataKey::get_min_claimable(&env, &token_address) {
+        return Err(Error::BelowMinimumWithdrawal);
+    };
 
     if amount == allocation {
         AllocationDataKey::remove_allocation(&env, &shareholder, &token_address);
@@ -0,0 +1,102 @@
+use soroban_sdk::{symbol_short, Address, Env, Vec};
+
+use crate::{
+    errors::Error,
+    logic::helpers::{check_not_frozen, get_token_client},
+    storage::{AllocationDataKey, ConfigDataKey, SettingsDataKey, MAX_BATCH_ORDERS},
+};
+
+/// Withdraws the allocations of several shareholders to them, triggered by an approved operator
+pub fn execute(
+    env: Env,
+    operator: Address,
+    claims: Vec<(Address, Address, i128)>,
+) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Require operator authorization once for the whole batch
+    operator.require_auth();
+
+    if claims.len() > MAX_BATCH_ORDERS {
+        return Err(Error::TooManyItems);
+    }
+
+    // Validate every claim before executing any of them, earlier claims of the same
+    // shareholder and token count against the allowance and the allocation
+    for (i, (shareholder, token_address, amount)) in claims.iter().enumerate() {
+        if amount <= 0 {
+            return Err(Error::ZeroWithdrawalAmount);
+        }
+
+        // Operators can't withdraw for frozen shareholders either
+        check_not_frozen(&env, &shareholder)?;
+
+        let mut claimed: i128 = 0;
+        for (other_shareholder, other_token, other_amount) in claims.iter().take(i) {
+            if other_shareholder == shareholder && other_token == token_address {
+                claimed += other_amount;
+            }
+        }
+
+        let allowance = AllocationDataKey::get_withdraw_allowance(
+            &env,
+            &shareholder,
+            &operator,
+            &token_address,
+        );
+        if claimed + amount > allowance {
+            return Err(Error::InsufficientShareAllowance);
+        }
+
+        let allocation = AllocationDataKey::get_allocation(&env, &shareholder, &token_address)
+            .unwrap_or(0)
+            - claimed;
+        if amount > allocation {
+            return Err(Error::WithdrawalAmountAboveAllocation);
+        }
+
+        // Partial withdrawals cannot be smaller than the token minimum
+        if amount < allocation && amount < SettingsDataKey::get_min_claimable(&env, &token_address)
+        {
+            return Err(Error::BelowMinimumWithdrawal);
+        }
+    }
+
+    for (shareholder, token_address, amount) in claims.iter() {
+        let allowance = AllocationDataKey::get_withdraw_allowance(
+            &env,
+            &shareholder,
+            &operator,
+            &token_address,
+        );
+        AllocationDataKey::save_withdraw_allowance(
+            &env,
+            &shareholder,
+            &operator,
+            &token_address,
+            allowance - amount,
+        );
+
+        let allocation =
+            AllocationDataKey::get_allocation(&env, &shareholder, &token_address).unwrap_or(0);
+        if amount == allocation {
+            AllocationDataKey::remove_allocation(&env, &shareholder, &token_address);
+        } else {
+            AllocationDataKey::save_allocation(&env, &shareholder, &token_address, allocation - amount);
+        }
+
+        // Transfer the tokens to the shareholder, never to the operator
+        let token_client = get_token_client(&env, &token_address);
+        token_client.transfer(&env.current_contract_address(), &shareholder, &amount);
+
+        // Emit withdraw event
+        env.events().publish(
+            (symbol_short!("withdraw"), shareholder),
+            (token_address, amount),
+        );
+    }
+
+    Ok(())
+}
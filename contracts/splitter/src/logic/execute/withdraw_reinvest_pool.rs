@@ -0,0 +1,52 @@
+use soroban_sdk::{symbol_short, Address, Env};
+
+use crate::{
+    errors::Error,
+    logic::helpers::get_token_client,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+/// Transfers tokens earmarked for reinvestment out of the contract, lowering the pool
+pub fn execute(
+    env: Env,
+    token_address: Address,
+    recipient: Address,
+    amount: i128,
+) -> Result<(), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Make sure the caller is the admin
+    ConfigDataKey::require_admin(&env)?;
+
+    // When an allowlist is set, tokens can only go to the listed recipients
+    if !SettingsDataKey::is_transfer_recipient_allowed(&env, &recipient) {
+        return Err(Error::RecipientNotAllowed);
+    }
+
+    // Transfer amount cannot be equal and less than 0
+    if amount <= 0 {
+        return Err(Error::ZeroTransferAmount);
+    };
+
+    // Only what was kept for reinvestment can be withdrawn this way
+    let pool = SettingsDataKey::get_reinvest_pool(&env, &token_address);
+    if amount > pool {
+        return Err(Error::TransferAmountAboveBalance);
+    };
+
+    SettingsDataKey::save_reinvest_pool(&env, &token_address, pool - amount);
+
+    // Transfer the tokens to the recipient
+    let token_client = get_token_client(&env, &token_address);
+    token_client.transfer(&env.current_contract_address(), &recipient, &amount);
+
+    // Emit reinvestment event with what is left in the pool
+    env.events().publish(
+        (symbol_short!("reinvest"), token_address),
+        (recipient, amount, pool - amount),
+    );
+
+    Ok(())
+}
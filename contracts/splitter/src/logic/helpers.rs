@@ -1,12 +1,34 @@
 use soroban_sdk::{
+    symbol_short,
     token::{self, TokenClient},
-    Address, Env, Vec,
+    Address, Env, Symbol, Vec,
 };
 
-use crate::{errors::Error, storage::ShareDataKey};
+use crate::{
+    errors::Error,
+    storage::{
+        AllocationDataKey, CommissionConfig, EpochDataKey, EscrowDataKey, ListingFee,
+        SaleListingDataKey, SettingsDataKey, ShareDataKey, ShareLock,
+    },
+};
+
+/// Checks that no shareholder is the default commission address or the contract itself,
+/// which would lock their allocations forever
+pub fn check_shareholder_addresses(env: &Env, shares: &Vec<ShareDataKey>) -> Result<(), Error> {
+    let default_recipient = CommissionConfig::get_default_recipient(env);
+    let contract_address = env.current_contract_address();
+
+    for share in shares.iter() {
+        if share.shareholder == default_recipient || share.shareholder == contract_address {
+            return Err(Error::RecipientNotAllowed);
+        }
+    }
 
-/// Checks if the shares sum up to 10000, all shares are non-negative, and no duplicates
-pub fn check_shares(shares: &Vec<ShareDataKey>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Checks if the shares sum up to the share denominator, all shares are positive, and no duplicates
+pub fn check_shares(shares: &Vec<ShareDataKey>, share_denominator: i128) -> Result<(), Error> {
     // Allow single shareholder pools (e.g., for airdrops or simple revenue collection)
     if shares.len() < 1 {
         return Err(Error::LowShareCount);
@@ -21,6 +43,16 @@ pub fn check_shares(shares: &Vec<ShareDataKey>) -> Result<(), Error> {
             return Err(Error::NegativeShareAmount);
         }
 
+        // Zero-share entries would only bloat the distribution loop
+        if share.share == 0 {
+            return Err(Error::ZeroShareNotAllowed);
+        }
+
+        // A single share can never exceed the total
+        if share.share > share_denominator {
+            return Err(Error::Overflow);
+        }
+
         // Check for duplicate shareholders
         for j in (i + 1)..shares.len() as usize {
             if let Some(other_share) = shares.get(j as u32) {
@@ -30,10 +62,10 @@ pub fn check_shares(shares: &Vec<ShareDataKey>) -> Result<(), Error> {
             }
         }
 
-        total += share.share;
+        total = total.checked_add(share.share).ok_or(Error::Overflow)?;
     }
 
-    if total != 10000 {
+    if total != share_denominator {
         return Err(Error::InvalidShareTotal);
     };
 
@@ -57,6 +89,55 @@ pub fn update_shares(env: &Env, shares: &Vec<ShareDataKey>) {
     ShareDataKey::save_shareholders(&env, shareholders);
 }
 
+/// Moves shares from a seller to a buyer, keeping the shareholders vector in sync.
+///
+/// The seller is removed from the shareholders when they have no shares left,
+/// and the buyer is added when they're a new shareholder.
+pub fn move_shares(env: &Env, seller: &Address, buyer: &Address, amount: i128) -> Result<(), Error> {
+    let seller_share = ShareDataKey::get_share(env, seller)
+        .ok_or(Error::NoSharesToSell)?
+        .share;
+    if seller_share < amount {
+        return Err(Error::NoSharesToSell);
+    }
+
+    // Locked and escrowed shares never move, whichever path sells them
+    if amount > get_free_shares(env, seller) {
+        return Err(Error::SharesLocked);
+    }
+
+    // Reduce seller's shares
+    let new_seller_share = seller_share - amount;
+    if new_seller_share > 0 {
+        ShareDataKey::save_share(env, seller.clone(), new_seller_share);
+    } else {
+        // Seller has no more shares, remove them
+        ShareDataKey::remove_share(env, seller);
+
+        // Remove from shareholders list
+        let mut shareholders = ShareDataKey::get_shareholders(env);
+        if let Some(index) = shareholders.first_index_of(seller) {
+            shareholders.remove(index);
+            ShareDataKey::save_shareholders(env, shareholders);
+        }
+    }
+
+    // Increase buyer's shares (or create new shareholder)
+    let new_buyer_share = match ShareDataKey::get_share(env, buyer) {
+        Some(data) => data.share.checked_add(amount).ok_or(Error::Overflow)?,
+        None => {
+            // Add buyer to shareholders list
+            let mut shareholders = ShareDataKey::get_shareholders(env);
+            shareholders.push_back(buyer.clone());
+            ShareDataKey::save_shareholders(env, shareholders);
+            amount
+        }
+    };
+    ShareDataKey::save_share(env, buyer.clone(), new_buyer_share);
+
+    Ok(())
+}
+
 /// Removes all of the shareholders and their shares
 pub fn reset_shares(env: &Env) {
     for shareholder in ShareDataKey::get_shareholders(env).iter() {
@@ -65,6 +146,203 @@ pub fn reset_shares(env: &Env) {
     ShareDataKey::remove_shareholders(env);
 }
 
+/// Checks that moving shares does not drop the shareholder count below the configured minimum.
+///
+/// The count only shrinks when the sender gives away all of their shares to an existing shareholder.
+pub fn check_min_shareholders(
+    env: &Env,
+    removes_sender: bool,
+    adds_recipient: bool,
+) -> Result<(), Error> {
+    if !removes_sender || adds_recipient {
+        return Ok(());
+    }
+
+    let min_shareholders = SettingsDataKey::get_min_shareholders(env);
+    if min_shareholders == 0 {
+        return Ok(());
+    }
+
+    let remaining = ShareDataKey::get_shareholders(env).len().saturating_sub(1);
+    if remaining < min_shareholders {
+        return Err(Error::TooFewShareholders);
+    }
+
+    Ok(())
+}
+
+/// Returns the sum of the shares of all the shareholders, which must always be the share denominator
+pub fn get_total_shares(env: &Env) -> Result<i128, Error> {
+    let mut total: i128 = 0;
+    for shareholder in ShareDataKey::get_shareholders(env).iter() {
+        let share = ShareDataKey::get_share(env, &shareholder).map(|share| share.share).unwrap_or(0);
+        total = total.checked_add(share).ok_or(Error::Overflow)?;
+    }
+    Ok(total)
+}
+
+/// Returns the shares of the shareholder that are neither locked nor reserved by escrowed purchases
+pub fn get_free_shares(env: &Env, shareholder: &Address) -> i128 {
+    let share = ShareDataKey::get_share(env, shareholder).map(|share| share.share).unwrap_or(0);
+    (share
+        - ShareLock::get_locked(env, shareholder)
+        - EscrowDataKey::get_escrowed_shares(env, shareholder))
+    .max(0)
+}
+
+/// Returns the buy commission of a sale, 0 when the seller or the buyer is exempt,
+/// or when the buyer is the commission recipient and the recipient doesn't pay commission
+pub fn get_buy_commission(env: &Env, seller: &Address, buyer: &Address, total_price: i128) -> i128 {
+    if SettingsDataKey::is_buy_commission_exempt(env, seller)
+        || SettingsDataKey::is_buy_commission_exempt(env, buyer)
+    {
+        return 0;
+    }
+
+    let commission_config = CommissionConfig::get(env);
+    if *buyer == commission_config.recipient
+        && !SettingsDataKey::get_recipient_pays_commission(env)
+    {
+        return 0;
+    }
+
+    CommissionConfig::calculate_commission(total_price, commission_config.buy_rate_bps)
+}
+
+/// Pays the buy commission of a purchase by the buyer from the payer, which is the buyer or
+/// the contract holding the escrowed payment.
+///
+/// Commission the recipient would pay to itself goes to the treasury, or stays in the contract
+/// as unused balance without one, as does commission of a recipient set to the contract itself.
+/// Other commission is sent to the recipient, or kept in the contract until the recipient
+/// sweeps it when deferred.
+pub fn pay_buy_commission(
+    env: &Env,
+    token_address: &Address,
+    payer: &Address,
+    buyer: &Address,
+    commission: i128,
+) -> Result<(), Error> {
+    if commission <= 0 {
+        return Ok(());
+    }
+
+    let contract = env.current_contract_address();
+    let recipient = CommissionConfig::get(env).recipient;
+    let destination = if recipient == contract {
+        contract
+    } else if *buyer == recipient {
+        SettingsDataKey::get_treasury(env).unwrap_or(contract)
+    } else {
+        CommissionConfig::add_commission_earned(env, token_address, commission, 0)?;
+        if CommissionConfig::is_buy_commission_deferred(env) {
+            CommissionConfig::add_accrued_buy_commission(env, token_address, commission)?;
+            contract
+        } else {
+            recipient
+        }
+    };
+
+    // Nothing moves when the commission already is where it belongs
+    if *payer != destination {
+        get_token_client(env, token_address).transfer(payer, &destination, &commission);
+    }
+
+    Ok(())
+}
+
+/// Checks that the traded share amount is a multiple of the configured lot size
+pub fn check_share_granularity(env: &Env, amount: i128) -> Result<(), Error> {
+    if amount % SettingsDataKey::get_share_lot_size(env) != 0 {
+        return Err(Error::InvalidShareGranularity);
+    }
+
+    Ok(())
+}
+
+/// Checks that the address is not frozen by a compliance hold
+pub fn check_not_frozen(env: &Env, address: &Address) -> Result<(), Error> {
+    if SettingsDataKey::is_frozen(env, address) {
+        return Err(Error::ShareholderFrozen);
+    }
+
+    Ok(())
+}
+
+/// Announces the activation of a delayed listing, so indexers can show it as buyable.
+///
+/// The caller is responsible for saving the listing.
+pub fn emit_listing_activation(env: &Env, listing: &mut SaleListingDataKey) {
+    listing.activation_emitted = true;
+
+    env.events().publish(
+        (Symbol::new(env, "listing_active"), listing.seller.clone()),
+        (listing.listing_id, listing.activation_ledger),
+    );
+}
+
+/// Transfers all of the pending allocations of the shareholder to them.
+/// Frozen shareholders aren't paid out, their allocations stay withdrawable once unfrozen.
+pub fn pay_out_allocations(env: &Env, shareholder: &Address) {
+    if SettingsDataKey::is_frozen(env, shareholder) {
+        return;
+    }
+
+    for token in AllocationDataKey::get_shareholder_tokens(env, shareholder).iter() {
+        let allocation = AllocationDataKey::get_allocation(env, shareholder, &token).unwrap_or(0);
+        if allocation <= 0 {
+            continue;
+        }
+
+        AllocationDataKey::remove_allocation(env, shareholder, &token);
+        get_token_client(env, &token).transfer(
+            &env.current_contract_address(),
+            shareholder,
+            &allocation,
+        );
+
+        // Emit payout event
+        env.events().publish(
+            (symbol_short!("payout"), shareholder.clone()),
+            (token, allocation),
+        );
+    }
+}
+
 pub fn get_token_client<'a>(env: &'a Env, token_address: &Address) -> TokenClient<'a> {
     token::Client::new(env, token_address)
 }
+
+/// Returns the token balance that is not allocated to the shareholders, held in escrow,
+/// accrued as buy commission or listing fees or vesting in an epoch
+pub fn get_unused_balance(env: &Env, token_address: &Address) -> i128 {
+    let token_client = get_token_client(env, token_address);
+
+    // Get the total token balance held by the contract
+    let balance = token_client.balance(&env.current_contract_address());
+
+    // Get how much has already been allocated (pending claims)
+    let total_allocated =
+        AllocationDataKey::get_total_allocation(env, token_address).unwrap_or(0);
+
+    // Get how much is held in escrow for pending purchases
+    let total_escrow = EscrowDataKey::get_total_escrow(env, token_address);
+
+    // Get how much buy commission and listing fees are waiting to be swept by the recipient
+    let accrued_commission = CommissionConfig::get_accrued_buy_commission(env, token_address);
+    let accrued_listing_fees = ListingFee::get_accrued(env, token_address);
+
+    // Get how much is still vesting or unclaimed in the current epoch
+    let epoch_locked = EpochDataKey::get_locked(env, token_address);
+
+    // Get how much is earmarked for reinvestment
+    let reinvest_pool = SettingsDataKey::get_reinvest_pool(env, token_address);
+
+    balance
+        - total_allocated
+        - total_escrow
+        - accrued_commission
+        - accrued_listing_fees
+        - epoch_locked
+        - reinvest_pool
+}
@@ -0,0 +1,7 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{errors::Error, storage::BuyerSpentDataKey};
+
+pub fn query(env: Env, buyer: Address, token: Address) -> Result<i128, Error> {
+    Ok(BuyerSpentDataKey::get_spent(&env, &buyer, &token))
+}
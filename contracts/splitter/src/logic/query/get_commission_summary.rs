@@ -0,0 +1,12 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{errors::Error, storage::CommissionConfig};
+
+pub fn query(env: Env, token: Address) -> Result<(i128, i128, i128), Error> {
+    let (from_buys, from_distributions) = CommissionConfig::get_commission_earned(&env, &token);
+    let total = from_buys
+        .checked_add(from_distributions)
+        .ok_or(Error::Overflow)?;
+
+    Ok((from_buys, from_distributions, total))
+}
@@ -0,0 +1,8 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{errors::Error, logic::helpers::get_token_client};
+
+pub fn query(env: Env, token: Address) -> Result<i128, Error> {
+    let token_client = get_token_client(&env, &token);
+    Ok(token_client.balance(&env.current_contract_address()))
+}
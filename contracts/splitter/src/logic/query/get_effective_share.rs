@@ -0,0 +1,22 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{
+    errors::Error,
+    logic::helpers::get_free_shares,
+    storage::{ConfigDataKey, ShareDataKey},
+};
+
+pub fn query(env: Env, shareholder: Address) -> Result<(i128, i128, i128), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    let total = ShareDataKey::get_share(&env, &shareholder)
+        .map(|share| share.share)
+        .unwrap_or(0);
+
+    // A lock can outgrow the shares once the shareholder transferred some away
+    let free = get_free_shares(&env, &shareholder);
+
+    Ok((total, total - free, free))
+}
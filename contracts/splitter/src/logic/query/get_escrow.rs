@@ -0,0 +1,7 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{errors::Error, storage::EscrowDataKey};
+
+pub fn query(env: Env, buyer: Address, seller: Address) -> Result<Option<EscrowDataKey>, Error> {
+    Ok(EscrowDataKey::get_escrow(&env, &buyer, &seller))
+}
@@ -0,0 +1,8 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{errors::Error, storage::SaleListingDataKey};
+
+pub fn query(env: Env, seller: Address) -> Result<Option<(i128, i128)>, Error> {
+    Ok(SaleListingDataKey::get_listing(&env, &seller)
+        .map(|listing| (listing.original_shares, listing.shares_for_sale)))
+}
@@ -0,0 +1,7 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{errors::Error, storage::SaleListingDataKey};
+
+pub fn query(env: Env, seller: Address) -> Result<Option<Address>, Error> {
+    Ok(SaleListingDataKey::get_listing(&env, &seller).map(|listing| listing.payment_token))
+}
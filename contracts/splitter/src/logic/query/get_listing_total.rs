@@ -0,0 +1,16 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{errors::Error, storage::SaleListingDataKey};
+
+pub fn query(env: Env, seller: Address) -> Result<Option<i128>, Error> {
+    match SaleListingDataKey::get_listing(&env, &seller) {
+        Some(listing) => {
+            let total = listing
+                .shares_for_sale
+                .checked_mul(listing.price_per_share)
+                .ok_or(Error::Overflow)?;
+            Ok(Some(total))
+        }
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,20 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::{
+    errors::Error,
+    storage::{SaleListingDataKey, MAX_BATCH_LISTINGS},
+};
+
+pub fn query(env: Env, sellers: Vec<Address>) -> Result<Vec<Option<SaleListingDataKey>>, Error> {
+    // Keep the lookup bounded
+    if sellers.len() > MAX_BATCH_LISTINGS {
+        return Err(Error::TooManyItems);
+    }
+
+    let mut listings: Vec<Option<SaleListingDataKey>> = Vec::new(&env);
+    for seller in sellers.iter() {
+        listings.push_back(SaleListingDataKey::get_listing(&env, &seller));
+    }
+
+    Ok(listings)
+}
@@ -0,0 +1,7 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::{errors::Error, storage::PriceHistoryDataKey};
+
+pub fn query(env: Env, seller: Address) -> Result<Vec<(u32, i128)>, Error> {
+    Ok(PriceHistoryDataKey::get_price_history(&env, &seller))
+}
@@ -0,0 +1,7 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{errors::Error, storage::ShareDataKey};
+
+pub fn query(env: Env, shareholder: Address, ledger: u32) -> Result<i128, Error> {
+    Ok(ShareDataKey::get_share_at(&env, &shareholder, ledger))
+}
@@ -0,0 +1,16 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, ShareDataKey},
+};
+
+pub fn query(env: Env, shareholder: Address) -> Result<i128, Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+    match ShareDataKey::get_share(&env, &shareholder) {
+        Some(share) => Ok(share.share),
+        None => Ok(0),
+    }
+}
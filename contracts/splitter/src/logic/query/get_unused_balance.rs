@@ -0,0 +1,12 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{errors::Error, logic::helpers::get_unused_balance, storage::ConfigDataKey};
+
+pub fn query(env: Env, token: Address) -> Result<i128, Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Same figure `transfer_tokens` checks against, never negative
+    Ok(get_unused_balance(&env, &token).max(0))
+}
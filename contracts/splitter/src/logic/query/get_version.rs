@@ -0,0 +1,10 @@
+use soroban_sdk::Env;
+
+use crate::{errors::Error, storage::ConfigDataKey};
+
+pub fn query(env: Env) -> Result<u32, Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+    Ok(ConfigDataKey::get_version(&env))
+}
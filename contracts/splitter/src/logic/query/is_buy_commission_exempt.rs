@@ -0,0 +1,13 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, SettingsDataKey},
+};
+
+pub fn query(env: Env, address: Address) -> Result<bool, Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+    Ok(SettingsDataKey::is_buy_commission_exempt(&env, &address))
+}
@@ -0,0 +1,13 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, ShareDataKey},
+};
+
+pub fn query(env: Env, address: Address) -> Result<bool, Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+    Ok(ShareDataKey::get_share(&env, &address).is_some())
+}
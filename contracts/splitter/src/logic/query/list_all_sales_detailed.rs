@@ -0,0 +1,25 @@
+use soroban_sdk::{Env, Vec};
+
+use crate::{
+    errors::Error,
+    storage::{SaleListingDataKey, ShareDataKey, MAX_DETAILED_LISTINGS},
+};
+
+pub fn query(env: Env) -> Result<Vec<(SaleListingDataKey, i128)>, Error> {
+    let mut listings: Vec<(SaleListingDataKey, i128)> = Vec::new(&env);
+
+    // Only the first listings are returned for large marketplaces
+    for seller in SaleListingDataKey::get_active_listings(&env)
+        .iter()
+        .take(MAX_DETAILED_LISTINGS as usize)
+    {
+        if let Some(listing) = SaleListingDataKey::get_listing(&env, &seller) {
+            let seller_shares = ShareDataKey::get_share(&env, &seller)
+                .map(|share| share.share)
+                .unwrap_or(0);
+            listings.push_back((listing, seller_shares));
+        }
+    }
+
+    Ok(listings)
+}
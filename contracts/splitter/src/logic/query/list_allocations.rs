@@ -0,0 +1,28 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::{
+    errors::Error,
+    storage::{AllocationDataKey, ConfigDataKey, ShareDataKey, MAX_LISTED_ALLOCATIONS},
+};
+
+pub fn query(env: Env, token: Address) -> Result<Vec<(Address, i128)>, Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    let mut allocations: Vec<(Address, i128)> = Vec::new(&env);
+
+    for shareholder in ShareDataKey::get_shareholders(&env).iter() {
+        // Only the first allocations are returned for large pools
+        if allocations.len() >= MAX_LISTED_ALLOCATIONS {
+            break;
+        }
+
+        let allocation = AllocationDataKey::get_allocation(&env, &shareholder, &token).unwrap_or(0);
+        if allocation > 0 {
+            allocations.push_back((shareholder, allocation));
+        }
+    }
+
+    Ok(allocations)
+}
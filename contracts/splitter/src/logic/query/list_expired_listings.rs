@@ -0,0 +1,21 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::{errors::Error, storage::SaleListingDataKey};
+
+pub fn query(env: Env, limit: u32) -> Result<Vec<Address>, Error> {
+    let mut sellers = Vec::new(&env);
+
+    for seller in SaleListingDataKey::get_active_listings(&env).iter() {
+        if sellers.len() >= limit {
+            break;
+        }
+
+        if let Some(listing) = SaleListingDataKey::get_listing(&env, &seller) {
+            if listing.is_expired(&env) {
+                sellers.push_back(seller);
+            }
+        }
+    }
+
+    Ok(sellers)
+}
@@ -0,0 +1,16 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::{errors::Error, storage::SaleListingDataKey};
+
+pub fn query(env: Env, token: Address) -> Result<Vec<SaleListingDataKey>, Error> {
+    let sellers = SaleListingDataKey::get_token_listings(&env, &token);
+    let mut listings = Vec::new(&env);
+
+    for seller in sellers.iter() {
+        if let Some(listing) = SaleListingDataKey::get_listing(&env, &seller) {
+            listings.push_back(listing);
+        }
+    }
+
+    Ok(listings)
+}
@@ -0,0 +1,27 @@
+use soroban_sdk::{Env, Vec};
+
+use crate::{errors::Error, storage::ShareDataKey};
+
+use super::list_shares;
+
+/// Lists the shares ordered by share descending, shareholders with equal shares keep their order.
+///
+/// Uses an insertion sort, which is O(n²) and only meant for small pools.
+pub fn query(env: Env) -> Result<Vec<ShareDataKey>, Error> {
+    let shares = list_shares::query(env.clone())?;
+    let mut sorted: Vec<ShareDataKey> = Vec::new(&env);
+
+    for share in shares.iter() {
+        // Insert after every share that is greater or equal
+        let mut index = sorted.len();
+        for (i, other) in sorted.iter().enumerate() {
+            if other.share < share.share {
+                index = i as u32;
+                break;
+            }
+        }
+        sorted.insert(index, share);
+    }
+
+    Ok(sorted)
+}
@@ -0,0 +1,26 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::{
+    errors::Error,
+    storage::{AllocationDataKey, ConfigDataKey, ShareDataKey, MAX_JOINED_SHAREHOLDERS},
+};
+
+pub fn query(env: Env, token: Address) -> Result<Vec<(Address, i128, i128)>, Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    let mut shares: Vec<(Address, i128, i128)> = Vec::new(&env);
+
+    // Only the first shareholders are returned for large pools
+    for shareholder in ShareDataKey::get_shareholders(&env)
+        .iter()
+        .take(MAX_JOINED_SHAREHOLDERS as usize)
+    {
+        let share = ShareDataKey::get_share(&env, &shareholder).unwrap();
+        let allocation = AllocationDataKey::get_allocation(&env, &shareholder, &token).unwrap_or(0);
+        shares.push_back((shareholder, share.share, allocation));
+    }
+
+    Ok(shares)
+}
@@ -1,17 +1,65 @@
 mod get_allocation;
+mod get_commission_summary;
 mod get_config;
+mod get_contract_balance;
+mod get_effective_share;
 mod get_share;
+mod get_share_at;
+mod get_share_or_zero;
+mod get_unused_balance;
+mod get_version;
+mod is_buy_commission_exempt;
+mod is_shareholder;
+mod list_allocations;
 mod list_shares;
+mod list_shares_sorted;
+mod list_shares_with_allocation;
+mod reconcile_allocations;
+mod verify_integrity;
 
 // Marketplace query functions
+mod get_buyer_spent;
+mod get_escrow;
 mod get_listing;
+mod get_listing_fill;
+mod get_listing_payment_token;
+mod get_listing_total;
+mod get_listings;
+mod get_price_history;
 mod list_all_sales;
+mod list_all_sales_detailed;
+mod list_expired_listings;
+mod list_sales_by_token;
 
 pub use get_allocation::query as get_allocation;
+pub use get_commission_summary::query as get_commission_summary;
 pub use get_config::query as get_config;
+pub use get_contract_balance::query as get_contract_balance;
+pub use get_effective_share::query as get_effective_share;
 pub use get_share::query as get_share;
+pub use get_share_at::query as get_share_at;
+pub use get_share_or_zero::query as get_share_or_zero;
+pub use get_unused_balance::query as get_unused_balance;
+pub use get_version::query as get_version;
+pub use is_buy_commission_exempt::query as is_buy_commission_exempt;
+pub use is_shareholder::query as is_shareholder;
+pub use list_allocations::query as list_allocations;
 pub use list_shares::query as list_shares;
+pub use list_shares_sorted::query as list_shares_sorted;
+pub use list_shares_with_allocation::query as list_shares_with_allocation;
+pub use reconcile_allocations::query as reconcile_allocations;
+pub use verify_integrity::query as verify_integrity;
 
 // Marketplace exports
+pub use get_buyer_spent::query as get_buyer_spent;
+pub use get_escrow::query as get_escrow;
 pub use get_listing::query as get_listing;
+pub use get_listing_fill::query as get_listing_fill;
+pub use get_listing_payment_token::query as get_listing_payment_token;
+pub use get_listing_total::query as get_listing_total;
+pub use get_listings::query as get_listings;
+pub use get_price_history::query as get_price_history;
 pub use list_all_sales::query as list_all_sales;
+pub use list_all_sales_detailed::query as list_all_sales_detailed;
+pub use list_expired_listings::query as list_expired_listings;
+pub use list_sales_by_token::query as list_sales_by_token;
@@ -0,0 +1,23 @@
+use soroban_sdk::{Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{AllocationDataKey, ConfigDataKey, ShareDataKey},
+};
+
+pub fn query(env: Env, token: Address) -> Result<(i128, i128), Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // Sum the allocations of the current shareholders
+    let mut sum: i128 = 0;
+    for shareholder in ShareDataKey::get_shareholders(&env).iter() {
+        let allocation = AllocationDataKey::get_allocation(&env, &shareholder, &token).unwrap_or(0);
+        sum = sum.checked_add(allocation).ok_or(Error::Overflow)?;
+    }
+
+    let tracked = AllocationDataKey::get_total_allocation(&env, &token).unwrap_or(0);
+
+    Ok((sum, tracked))
+}
@@ -0,0 +1,16 @@
+use soroban_sdk::Env;
+
+use crate::{
+    errors::Error,
+    logic::helpers::get_total_shares,
+    storage::{ConfigDataKey, ShareDataKey},
+};
+
+pub fn query(env: Env) -> Result<bool, Error> {
+    if !ConfigDataKey::exists(&env) {
+        return Err(Error::NotInitialized);
+    };
+
+    // The shares of the shareholders must always sum up to the share denominator
+    Ok(get_total_shares(&env)? == ShareDataKey::get_share_denominator(&env))
+}
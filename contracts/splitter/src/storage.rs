@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, Env, IntoVal, String, Val, Vec};
+use soroban_sdk::{contracttype, Address, Env, IntoVal, Map, String, Symbol, TryFromVal, Val, Vec};
 
 use crate::errors::Error;
 
@@ -11,6 +11,45 @@ const DISTRIBUTION_COMMISSION_BPS: i128 = 50;
 
 const DAY_IN_LEDGERS: u32 = 17280;
 
+/// Total of all shares unless a different denominator is set at initialization
+pub const DEFAULT_SHARE_DENOMINATOR: i128 = 10000;
+
+/// Version of the storage layout, bumped by `migrate` when it changes
+pub const CONTRACT_VERSION: u32 = 2;
+
+/// Maximum number of price history entries kept per seller
+pub const MAX_PRICE_HISTORY: u32 = 10;
+
+/// Maximum number of share checkpoints kept per shareholder
+pub const MAX_SHARE_CHECKPOINTS: u32 = 20;
+
+/// Maximum number of buyers that can be whitelisted on a listing
+pub const MAX_ALLOWED_BUYERS: u32 = 20;
+
+/// Maximum number of orders in a single `buy_shares_multi` call
+pub const MAX_BATCH_ORDERS: u32 = 10;
+
+/// Maximum number of shareholders returned by `list_shares_with_allocation`
+pub const MAX_JOINED_SHAREHOLDERS: u32 = 100;
+
+/// Maximum number of allocations returned by `list_allocations`
+pub const MAX_LISTED_ALLOCATIONS: u32 = 100;
+
+/// Maximum number of listings returned by `list_all_sales_detailed`
+pub const MAX_DETAILED_LISTINGS: u32 = 50;
+
+/// Maximum number of sellers looked up at once by `get_listings`
+pub const MAX_BATCH_LISTINGS: u32 = 50;
+
+/// Maximum number of contributions kept in a single pooled listing
+pub const MAX_POOL_CONTRIBUTIONS: u32 = 50;
+
+/// Maximum number of recipients on the `transfer_tokens` allowlist
+pub const MAX_TRANSFER_ALLOWLIST: u32 = 20;
+
+/// Number of ledgers after which an escrowed purchase can be refunded to the buyer
+pub const ESCROW_EXPIRATION_LEDGERS: u32 = DAY_IN_LEDGERS;
+
 const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
 const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
 
@@ -32,6 +71,17 @@ where
         .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
 }
 
+/// Decodes a field of a value stored in an earlier layout, the default if it's missing
+fn decode_field<T>(e: &Env, fields: &Map<Symbol, Val>, name: &str, default: T) -> T
+where
+    T: TryFromVal<Env, Val>,
+{
+    fields
+        .get(Symbol::new(e, name))
+        .and_then(|val| T::try_from_val(e, &val).ok())
+        .unwrap_or(default)
+}
+
 #[derive(Clone, Debug, PartialEq)]
 #[contracttype]
 pub struct ShareDataKey {
@@ -41,6 +91,10 @@ pub struct ShareDataKey {
 impl ShareDataKey {
     /// Initializes the share for the shareholder
     pub fn save_share(e: &Env, shareholder: Address, share: i128) {
+        // The weight of the old share must be accrued before it changes
+        Self::accrue_time_weight(e, &shareholder);
+        Self::record_checkpoint(e, &shareholder, share);
+
         let key = DataKey::Share(shareholder.clone());
         e.storage()
             .persistent()
@@ -61,12 +115,235 @@ impl ShareDataKey {
         }
     }
 
+    /// Returns the total all shares sum up to, 10000 unless set at initialization
+    pub fn get_share_denominator(e: &Env) -> i128 {
+        bump_instance(e);
+        let key = DataKey::ShareDenominator;
+        e.storage().instance().get(&key).unwrap_or(DEFAULT_SHARE_DENOMINATOR)
+    }
+
+    /// Saves the total all shares sum up to
+    pub fn save_share_denominator(e: &Env, share_denominator: i128) {
+        bump_instance(e);
+        let key = DataKey::ShareDenominator;
+        e.storage().instance().set(&key, &share_denominator);
+    }
+
     /// Removes the share for the shareholder
     pub fn remove_share(e: &Env, shareholder: &Address) {
+        Self::accrue_time_weight(e, shareholder);
+        Self::record_checkpoint(e, shareholder, 0);
+
         let key = DataKey::Share(shareholder.clone());
         e.storage().persistent().remove(&key);
     }
 
+    /// Moves the share of the shareholder to an address without a share, along with the
+    /// time weight and the checkpoints, so the new address continues the shareholder's history.
+    /// History the new address has of its own is merged in.
+    pub fn move_share(e: &Env, old: &Address, new: &Address) {
+        let share = match Self::get_share(e, old) {
+            Some(share) => share.share,
+            None => return,
+        };
+
+        // Both weights are accrued up to now, so they simply add up
+        Self::accrue_time_weight(e, old);
+        Self::accrue_time_weight(e, new);
+        let (weight, since) = Self::get_stored_time_weight(e, old);
+        let (new_weight, _) = Self::get_stored_time_weight(e, new);
+        e.storage()
+            .persistent()
+            .remove(&ExtDataKey::ShareTimeWeight(old.clone()));
+        let key = ExtDataKey::ShareTimeWeight(new.clone());
+        e.storage().persistent().set(
+            &key,
+            &(Self::get_time_weight_period(e), weight + new_weight, since),
+        );
+        bump_persistent(e, &key);
+
+        let checkpoints = Self::merge_checkpoints(
+            e,
+            &Self::get_checkpoints(e, old),
+            &Self::get_checkpoints(e, new),
+        );
+        e.storage()
+            .persistent()
+            .remove(&ExtDataKey::ShareCheckpoints(old.clone()));
+        let key = ExtDataKey::ShareCheckpoints(new.clone());
+        e.storage().persistent().set(&key, &checkpoints);
+        bump_persistent(e, &key);
+
+        e.storage().persistent().remove(&DataKey::Share(old.clone()));
+        let key = DataKey::Share(new.clone());
+        e.storage().persistent().set(
+            &key,
+            &ShareDataKey {
+                shareholder: new.clone(),
+                share,
+            },
+        );
+        bump_persistent(e, &key);
+    }
+
+    /// Merges two checkpoint lists into the checkpoints of the summed shares,
+    /// keeping the most recent `MAX_SHARE_CHECKPOINTS` entries
+    fn merge_checkpoints(
+        e: &Env,
+        first: &Vec<(u32, i128)>,
+        second: &Vec<(u32, i128)>,
+    ) -> Vec<(u32, i128)> {
+        let mut merged = Vec::new(e);
+        let (mut i, mut j) = (0, 0);
+        let (mut first_share, mut second_share) = (0, 0);
+        loop {
+            let first_next = first.get(i);
+            let second_next = second.get(j);
+            let ledger = match (first_next, second_next) {
+                (Some((a, _)), Some((b, _))) => a.min(b),
+                (Some((a, _)), None) => a,
+                (None, Some((b, _))) => b,
+                (None, None) => break,
+            };
+
+            if let Some((checkpoint_ledger, share)) = first_next {
+                if checkpoint_ledger == ledger {
+                    first_share = share;
+                    i += 1;
+                }
+            }
+            if let Some((checkpoint_ledger, share)) = second_next {
+                if checkpoint_ledger == ledger {
+                    second_share = share;
+                    j += 1;
+                }
+            }
+            merged.push_back((ledger, first_share + second_share));
+        }
+
+        while merged.len() > MAX_SHARE_CHECKPOINTS {
+            merged.pop_front();
+        }
+        merged
+    }
+
+    /// Records the share of the shareholder from the current ledger on.
+    /// Only the most recent `MAX_SHARE_CHECKPOINTS` entries are kept.
+    fn record_checkpoint(e: &Env, shareholder: &Address, share: i128) {
+        let ledger = e.ledger().sequence();
+        let mut checkpoints = Self::get_checkpoints(e, shareholder);
+
+        // Several changes in one ledger only keep the last share
+        if let Some((last_ledger, _)) = checkpoints.last() {
+            if last_ledger == ledger {
+                checkpoints.pop_back();
+            }
+        }
+
+        checkpoints.push_back((ledger, share));
+        while checkpoints.len() > MAX_SHARE_CHECKPOINTS {
+            checkpoints.pop_front();
+        }
+
+        let key = ExtDataKey::ShareCheckpoints(shareholder.clone());
+        e.storage().persistent().set(&key, &checkpoints);
+        bump_persistent(e, &key);
+    }
+
+    /// Returns the share checkpoints of the shareholder, oldest entry first
+    pub fn get_checkpoints(e: &Env, shareholder: &Address) -> Vec<(u32, i128)> {
+        let key = ExtDataKey::ShareCheckpoints(shareholder.clone());
+        let res = e.storage().persistent().get::<ExtDataKey, Vec<(u32, i128)>>(&key);
+        match res {
+            Some(checkpoints) => {
+                bump_persistent(e, &key);
+                checkpoints
+            }
+            None => Vec::new(e),
+        }
+    }
+
+    /// Returns the share of the shareholder as of the ledger, from the latest checkpoint
+    /// at or before it. 0 when there is none, including ledgers older than the kept checkpoints.
+    pub fn get_share_at(e: &Env, shareholder: &Address, ledger: u32) -> i128 {
+        let mut share = 0;
+        for (checkpoint_ledger, checkpoint_share) in Self::get_checkpoints(e, shareholder).iter() {
+            if checkpoint_ledger > ledger {
+                break;
+            }
+            share = checkpoint_share;
+        }
+
+        share
+    }
+
+    /// Returns the time weight the shareholder accumulated in the current period, as the sum
+    /// of share * ledgers held, including the ledgers since the share last changed
+    pub fn get_time_weight(e: &Env, shareholder: &Address) -> i128 {
+        let (weight, since) = Self::get_stored_time_weight(e, shareholder);
+        let share = Self::get_share(e, shareholder).map(|share| share.share).unwrap_or(0);
+
+        weight + share * (e.ledger().sequence().saturating_sub(since) as i128)
+    }
+
+    /// Saves the time weight accumulated so far, so the share can change from the current ledger
+    fn accrue_time_weight(e: &Env, shareholder: &Address) {
+        let weight = Self::get_time_weight(e, shareholder);
+
+        let key = ExtDataKey::ShareTimeWeight(shareholder.clone());
+        e.storage().persistent().set(
+            &key,
+            &(Self::get_time_weight_period(e), weight, e.ledger().sequence()),
+        );
+        bump_persistent(e, &key);
+    }
+
+    /// Returns the stored weight of the shareholder and the ledger it was accrued until,
+    /// weights of earlier periods are discarded
+    fn get_stored_time_weight(e: &Env, shareholder: &Address) -> (i128, u32) {
+        let period = Self::get_time_weight_period(e);
+
+        let key = ExtDataKey::ShareTimeWeight(shareholder.clone());
+        let res = e.storage().persistent().get::<ExtDataKey, (u32, i128, u32)>(&key);
+        match res {
+            Some((stored_period, weight, since)) if stored_period == period => {
+                bump_persistent(e, &key);
+                (weight, since)
+            }
+            _ => (0, Self::get_time_weight_start(e)),
+        }
+    }
+
+    /// Returns the number of the current time weight period, 0 before the first reset
+    pub fn get_time_weight_period(e: &Env) -> u32 {
+        bump_instance(e);
+        e.storage()
+            .instance()
+            .get(&ExtDataKey::TimeWeightPeriod)
+            .unwrap_or(0)
+    }
+
+    /// Returns the ledger the current time weight period started at
+    pub fn get_time_weight_start(e: &Env) -> u32 {
+        bump_instance(e);
+        e.storage()
+            .instance()
+            .get(&ExtDataKey::TimeWeightStart)
+            .unwrap_or(0)
+    }
+
+    /// Starts a new time weight period at the current ledger, dropping all accumulated weights
+    pub fn reset_time_weights(e: &Env) {
+        bump_instance(e);
+        let period = Self::get_time_weight_period(e);
+        e.storage()
+            .instance()
+            .set(&ExtDataKey::TimeWeightPeriod, &(period + 1));
+        e.storage()
+            .instance()
+            .set(&ExtDataKey::TimeWeightStart, &e.ledger().sequence());
+    }
+
     /// Saves the list of shareholders
     pub fn save_shareholders(e: &Env, shareholders: Vec<Address>) {
         let key = DataKey::Shareholders;
@@ -146,6 +423,49 @@ impl ConfigDataKey {
         Ok(())
     }
 
+    /// Returns the storage layout version.
+    /// Contracts initialized before versioning was introduced have version 0.
+    pub fn get_version(e: &Env) -> u32 {
+        bump_instance(e);
+        let key = DataKey::Version;
+        e.storage().instance().get(&key).unwrap_or(0)
+    }
+
+    /// Saves the storage layout version
+    pub fn save_version(e: &Env, version: u32) {
+        bump_instance(e);
+        let key = DataKey::Version;
+        e.storage().instance().set(&key, &version);
+    }
+
+    /// Replaces the admin address
+    pub fn save_admin(e: &Env, admin: Address) {
+        bump_instance(e);
+        let key = DataKey::Config;
+        let config: Option<ConfigDataKey> = e.storage().instance().get(&key);
+        if let Some(mut config) = config {
+            config.admin = admin;
+            e.storage().instance().set(&key, &config);
+        }
+    }
+
+    /// Returns true once a distribution or marketplace action happened,
+    /// or the initial admin was already claimed
+    pub fn has_activity(e: &Env) -> bool {
+        bump_instance(e);
+        let key = DataKey::ActivityFlag;
+        e.storage().instance().get(&key).unwrap_or(false)
+    }
+
+    /// Records that the contract was used, which permanently disables `claim_initial_admin`
+    pub fn mark_activity(e: &Env) {
+        bump_instance(e);
+        let key = DataKey::ActivityFlag;
+        if !e.storage().instance().has(&key) {
+            e.storage().instance().set(&key, &true);
+        }
+    }
+
     /// Returns true if the contract is mutable
     // TODO: Maybe return an error if ConfigDataKey doesn't exist
     pub fn is_contract_locked(e: &Env) -> bool {
@@ -167,6 +487,7 @@ impl AllocationDataKey {
     /// Saves the allocation for a shareholder and updates total allocation tracking.
     /// This function correctly tracks the DELTA (difference) between old and new allocation
     /// to maintain accurate total allocation accounting.
+    /// A zero allocation removes the key, so no dead entries keep paying rent.
     pub fn save_allocation(e: &Env, shareholder: &Address, token: &Address, new_allocation: i128) {
         // Get the old allocation to calculate the delta
         let old_allocation = Self::get_allocation(e, shareholder, token).unwrap_or(0);
@@ -192,14 +513,24 @@ impl AllocationDataKey {
         }
 
         let key = DataKey::Allocation(shareholder.clone(), token.clone());
+        if new_allocation == 0 {
+            e.storage().persistent().remove(&key);
+            Self::remove_shareholder_token(e, shareholder, token);
+            return;
+        }
+
         e.storage().persistent().set(&key, &new_allocation);
         bump_persistent(e, &key);
+
+        if new_allocation > 0 {
+            Self::add_shareholder_token(e, shareholder, token);
+        }
     }
 
     pub fn remove_allocation(e: &Env, shareholder: &Address, token: &Address) {
         match Self::get_total_allocation(e, token) {
             Some(total_allocation) => {
-                let allocation = Self::get_allocation(e, shareholder, token).unwrap();
+                let allocation = Self::get_allocation(e, shareholder, token).unwrap_or(0);
                 let new_total_allocation = total_allocation - allocation;
 
                 if new_total_allocation == 0 {
@@ -213,6 +544,8 @@ impl AllocationDataKey {
 
         let key = DataKey::Allocation(shareholder.clone(), token.clone());
         e.storage().persistent().remove(&key);
+
+        Self::remove_shareholder_token(e, shareholder, token);
     }
 
     pub fn get_allocation(e: &Env, shareholder: &Address, token: &Address) -> Option<i128> {
@@ -227,6 +560,82 @@ impl AllocationDataKey {
         }
     }
 
+    // ========== Withdraw Allowance ==========
+
+    /// Returns how much of the shareholder's token allocation the operator can withdraw for them
+    pub fn get_withdraw_allowance(
+        e: &Env,
+        shareholder: &Address,
+        operator: &Address,
+        token: &Address,
+    ) -> i128 {
+        let key = DataKey::WithdrawAllowance(shareholder.clone(), operator.clone(), token.clone());
+        let res = e.storage().persistent().get::<DataKey, i128>(&key);
+        match res {
+            Some(allowance) => {
+                bump_persistent(e, &key);
+                allowance
+            }
+            None => 0,
+        }
+    }
+
+    /// Saves the operator's withdraw allowance, 0 removes it
+    pub fn save_withdraw_allowance(
+        e: &Env,
+        shareholder: &Address,
+        operator: &Address,
+        token: &Address,
+        allowance: i128,
+    ) {
+        let key = DataKey::WithdrawAllowance(shareholder.clone(), operator.clone(), token.clone());
+        if allowance > 0 {
+            e.storage().persistent().set(&key, &allowance);
+            bump_persistent(e, &key);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+    }
+
+    // ========== Shareholder Tokens ==========
+
+    /// Returns the tokens the shareholder has an allocation in
+    pub fn get_shareholder_tokens(e: &Env, shareholder: &Address) -> Vec<Address> {
+        let key = DataKey::ShareholderTokens(shareholder.clone());
+        let res = e.storage().persistent().get::<DataKey, Vec<Address>>(&key);
+        match res {
+            Some(tokens) => {
+                bump_persistent(e, &key);
+                tokens
+            }
+            None => Vec::new(e),
+        }
+    }
+
+    fn add_shareholder_token(e: &Env, shareholder: &Address, token: &Address) {
+        let mut tokens = Self::get_shareholder_tokens(e, shareholder);
+        if !tokens.contains(token) {
+            tokens.push_back(token.clone());
+            let key = DataKey::ShareholderTokens(shareholder.clone());
+            e.storage().persistent().set(&key, &tokens);
+            bump_persistent(e, &key);
+        }
+    }
+
+    fn remove_shareholder_token(e: &Env, shareholder: &Address, token: &Address) {
+        let mut tokens = Self::get_shareholder_tokens(e, shareholder);
+        if let Some(index) = tokens.first_index_of(token) {
+            tokens.remove(index);
+            let key = DataKey::ShareholderTokens(shareholder.clone());
+            if tokens.is_empty() {
+                e.storage().persistent().remove(&key);
+            } else {
+                e.storage().persistent().set(&key, &tokens);
+                bump_persistent(e, &key);
+            }
+        }
+    }
+
     // ========== Total Allocation ==========
 
     pub fn save_total_allocation(e: &Env, token: &Address, total_allocation: i128) {
@@ -260,29 +669,155 @@ pub struct SaleListingDataKey {
     pub shares_for_sale: i128,
     pub price_per_share: i128,
     pub payment_token: Address,
+    /// Buyers allowed to buy from the listing, empty for a public listing
+    pub allowed_buyers: Vec<Address>,
+    /// Ledger after which the listing can no longer be bought, 0 if it never expires
+    pub expiration_ledger: u32,
+    /// Credit the proceeds of a sale to the seller's allocation instead of transferring them
+    pub credit_proceeds: bool,
+    /// Ledger the listing was first created at
+    pub created_ledger: u32,
+    /// Unique id of the listing, a new one is assigned every time the seller lists
+    pub listing_id: u64,
+    /// Listing fee the seller paid when the listing was created
+    pub fee_paid: i128,
+    /// Token the listing fee was paid in, which refunds are paid in too
+    pub fee_token: Address,
+    /// Shares for sale when the seller listed, kept while the listing is partially filled
+    pub original_shares: i128,
+    /// Ledger from which the listing can be bought, 0 if it is active right away
+    pub activation_ledger: u32,
+    /// Whether the activation of a delayed listing was already announced
+    pub activation_emitted: bool,
+    /// Shares a single buyer can buy from the listing across all of their buys, 0 for no cap
+    pub max_per_buyer: i128,
 }
 
 impl SaleListingDataKey {
-    /// Creates a new sale listing
-    pub fn save_listing(
-        e: &Env,
-        seller: Address,
-        shares_for_sale: i128,
-        price_per_share: i128,
-        payment_token: Address,
-    ) {
-        let key = DataKey::SaleListing(seller.clone());
-        let listing = SaleListingDataKey {
-            seller: seller.clone(),
-            shares_for_sale,
-            price_per_share,
-            payment_token,
-        };
-        e.storage().persistent().set(&key, &listing);
+    /// Creates or updates a sale listing
+    pub fn save_listing(e: &Env, listing: &SaleListingDataKey) {
+        let key = DataKey::SaleListing(listing.seller.clone());
+
+        // A replaced listing can be priced in another token, and its id no longer refers to it
+        if let Some(previous) = e.storage().persistent().get::<DataKey, SaleListingDataKey>(&key) {
+            if previous.payment_token != listing.payment_token {
+                Self::remove_from_token_listings(e, &previous.payment_token, &listing.seller);
+            }
+            if previous.listing_id != listing.listing_id {
+                e.storage()
+                    .persistent()
+                    .remove(&DataKey::ListingSeller(previous.listing_id));
+            }
+        }
+
+        e.storage().persistent().set(&key, listing);
         bump_persistent(e, &key);
 
+        // Keep track of who listed under the id while the listing is open
+        let seller_key = DataKey::ListingSeller(listing.listing_id);
+        e.storage().persistent().set(&seller_key, &listing.seller);
+        bump_persistent(e, &seller_key);
+
         // Add to active listings
-        Self::add_to_active_listings(e, &seller);
+        Self::add_to_active_listings(e, &listing.seller);
+        Self::add_to_token_listings(e, &listing.payment_token, &listing.seller);
+    }
+
+    /// Returns the id for a new listing, ids start at 1
+    pub fn next_listing_id(e: &Env) -> u64 {
+        bump_instance(e);
+        let key = DataKey::NextListingId;
+        let listing_id: u64 = e.storage().instance().get(&key).unwrap_or(1);
+        e.storage().instance().set(&key, &(listing_id + 1));
+        listing_id
+    }
+
+    /// Returns true if the id was ever assigned to a listing
+    pub fn is_issued_listing_id(e: &Env, listing_id: u64) -> bool {
+        bump_instance(e);
+        let next_listing_id: u64 = e.storage().instance().get(&DataKey::NextListingId).unwrap_or(1);
+        listing_id > 0 && listing_id < next_listing_id
+    }
+
+    /// Gets the seller of the open listing with the id
+    pub fn get_listing_seller(e: &Env, listing_id: u64) -> Option<Address> {
+        let key = DataKey::ListingSeller(listing_id);
+        let res = e.storage().persistent().get(&key);
+        match res {
+            Some(seller) => {
+                bump_persistent(e, &key);
+                Some(seller)
+            }
+            None => None,
+        }
+    }
+
+    /// Returns true if the listing expired and can no longer be bought
+    pub fn is_expired(&self, e: &Env) -> bool {
+        self.expiration_ledger > 0 && e.ledger().sequence() > self.expiration_ledger
+    }
+
+    /// Returns true if the listing reached its activation ledger and can be bought
+    pub fn is_active(&self, e: &Env) -> bool {
+        e.ledger().sequence() >= self.activation_ledger
+    }
+
+    /// Returns true if the listing was delayed and its activation wasn't announced yet
+    pub fn needs_activation_event(&self, e: &Env) -> bool {
+        self.activation_ledger > 0 && !self.activation_emitted && self.is_active(e)
+    }
+
+    /// Returns how many shares the buyer bought from the seller's listing with the id
+    pub fn get_buyer_filled(e: &Env, buyer: &Address, seller: &Address, listing_id: u64) -> i128 {
+        let key = DataKey::BuyerListingFilled(buyer.clone(), seller.clone());
+        let res: Option<(u64, i128)> = e.storage().persistent().get(&key);
+        match res {
+            // A filled amount of an earlier listing doesn't count
+            Some((id, filled)) if id == listing_id => {
+                bump_persistent(e, &key);
+                filled
+            }
+            _ => 0,
+        }
+    }
+
+    /// Adds the shares to what the buyer bought from the seller's listing with the id
+    pub fn add_buyer_filled(
+        e: &Env,
+        buyer: &Address,
+        seller: &Address,
+        listing_id: u64,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let filled = Self::get_buyer_filled(e, buyer, seller, listing_id)
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+
+        let key = DataKey::BuyerListingFilled(buyer.clone(), seller.clone());
+        e.storage().persistent().set(&key, &(listing_id, filled));
+        bump_persistent(e, &key);
+        Ok(())
+    }
+
+    /// Checks that buying the shares keeps the buyer within the per-buyer cap of the listing
+    pub fn check_buyer_cap(&self, e: &Env, buyer: &Address, amount: i128) -> Result<(), Error> {
+        if self.max_per_buyer == 0 {
+            return Ok(());
+        }
+
+        let filled = Self::get_buyer_filled(e, buyer, &self.seller, self.listing_id)
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+        if filled > self.max_per_buyer {
+            return Err(Error::PerBuyerCapExceeded);
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the buyer is allowed to buy from the listing
+    pub fn is_buyer_allowed(&self, buyer: &Address) -> bool {
+        self.allowed_buyers.is_empty() || self.allowed_buyers.contains(buyer)
     }
 
     /// Gets a sale listing
@@ -298,9 +833,57 @@ impl SaleListingDataKey {
         }
     }
 
+    /// Rewrites the active listings stored in an earlier layout to the current one.
+    /// Fields added since then get their defaults, listings without an id get a new one.
+    pub fn migrate_listings(e: &Env) {
+        for seller in Self::get_active_listings(e).iter() {
+            let key = DataKey::SaleListing(seller.clone());
+            let fields: Map<Symbol, Val> = match e.storage().persistent().get(&key) {
+                Some(fields) => fields,
+                None => continue,
+            };
+
+            let shares_for_sale: i128 = decode_field(e, &fields, "shares_for_sale", 0);
+            let payment_token: Address =
+                decode_field(e, &fields, "payment_token", seller.clone());
+            let listing_id = if fields.contains_key(Symbol::new(e, "listing_id")) {
+                decode_field(e, &fields, "listing_id", 0)
+            } else {
+                Self::next_listing_id(e)
+            };
+            let listing = SaleListingDataKey {
+                seller: seller.clone(),
+                shares_for_sale,
+                price_per_share: decode_field(e, &fields, "price_per_share", 0),
+                payment_token: payment_token.clone(),
+                allowed_buyers: decode_field(e, &fields, "allowed_buyers", Vec::new(e)),
+                expiration_ledger: decode_field(e, &fields, "expiration_ledger", 0),
+                credit_proceeds: decode_field(e, &fields, "credit_proceeds", false),
+                created_ledger: decode_field(e, &fields, "created_ledger", e.ledger().sequence()),
+                listing_id,
+                fee_paid: decode_field(e, &fields, "fee_paid", 0),
+                fee_token: decode_field(e, &fields, "fee_token", payment_token.clone()),
+                original_shares: decode_field(e, &fields, "original_shares", shares_for_sale),
+                activation_ledger: decode_field(e, &fields, "activation_ledger", 0),
+                activation_emitted: decode_field(e, &fields, "activation_emitted", false),
+                max_per_buyer: decode_field(e, &fields, "max_per_buyer", 0),
+            };
+
+            // The stored listing can't be read in the current layout, so it's replaced as a whole
+            e.storage().persistent().remove(&key);
+            Self::save_listing(e, &listing);
+        }
+    }
+
     /// Removes a sale listing
     pub fn remove_listing(e: &Env, seller: &Address) {
         let key = DataKey::SaleListing(seller.clone());
+        if let Some(listing) = e.storage().persistent().get::<DataKey, SaleListingDataKey>(&key) {
+            Self::remove_from_token_listings(e, &listing.payment_token, seller);
+            e.storage()
+                .persistent()
+                .remove(&DataKey::ListingSeller(listing.listing_id));
+        }
         e.storage().persistent().remove(&key);
 
         // Remove from active listings
@@ -346,42 +929,471 @@ impl SaleListingDataKey {
             bump_persistent(e, &key);
         }
     }
+
+    /// Gets the sellers with an active listing priced in the token
+    pub fn get_token_listings(e: &Env, token: &Address) -> Vec<Address> {
+        let key = DataKey::ListingsByToken(token.clone());
+        let res = e.storage().persistent().get::<DataKey, Vec<Address>>(&key);
+        match res {
+            Some(listings) => {
+                bump_persistent(e, &key);
+                listings
+            }
+            None => Vec::new(e),
+        }
+    }
+
+    fn add_to_token_listings(e: &Env, token: &Address, seller: &Address) {
+        let mut listings = Self::get_token_listings(e, token);
+        if !listings.contains(seller) {
+            listings.push_back(seller.clone());
+            let key = DataKey::ListingsByToken(token.clone());
+            e.storage().persistent().set(&key, &listings);
+            bump_persistent(e, &key);
+        }
+    }
+
+    fn remove_from_token_listings(e: &Env, token: &Address, seller: &Address) {
+        let mut listings = Self::get_token_listings(e, token);
+        if let Some(index) = listings.first_index_of(seller) {
+            listings.remove(index);
+            let key = DataKey::ListingsByToken(token.clone());
+            if listings.is_empty() {
+                e.storage().persistent().remove(&key);
+            } else {
+                e.storage().persistent().set(&key, &listings);
+                bump_persistent(e, &key);
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
-#[contracttype]
-pub struct CommissionConfig {
-    pub recipient: Address,
-    pub buy_rate_bps: i128,          // Basis points for share purchases (150 = 1.5%)
-    pub distribution_rate_bps: i128, // Basis points for distributions (50 = 0.5%)
-}
+pub struct PriceHistoryDataKey {}
+impl PriceHistoryDataKey {
+    /// Records a listing price for the seller at the current ledger.
+    /// Only the most recent `MAX_PRICE_HISTORY` entries are kept.
+    pub fn record_price(e: &Env, seller: &Address, price_per_share: i128) {
+        let mut history = Self::get_price_history(e, seller);
+        history.push_back((e.ledger().sequence(), price_per_share));
+        while history.len() > MAX_PRICE_HISTORY {
+            history.pop_front();
+        }
 
-impl CommissionConfig {
-    /// Gets the commission config, initializing with defaults if not set
-    pub fn get(e: &Env) -> CommissionConfig {
-        bump_instance(e);
-        let key = DataKey::Commission;
-        match e.storage().instance().get::<DataKey, CommissionConfig>(&key) {
-            Some(config) => config,
-            None => {
-                // Initialize with default values
-                let default_address = Address::from_string(&String::from_str(e, DEFAULT_COMMISSION_ADDRESS));
-                let default_config = CommissionConfig {
-                    recipient: default_address,
-                    buy_rate_bps: BUY_COMMISSION_BPS,
-                    distribution_rate_bps: DISTRIBUTION_COMMISSION_BPS,
-                };
-                e.storage().instance().set(&key, &default_config);
-                default_config
+        let key = DataKey::PriceHistory(seller.clone());
+        e.storage().persistent().set(&key, &history);
+        bump_persistent(e, &key);
+    }
+
+    /// Returns the price history of the seller, oldest entry first
+    pub fn get_price_history(e: &Env, seller: &Address) -> Vec<(u32, i128)> {
+        let key = DataKey::PriceHistory(seller.clone());
+        let res = e.storage().persistent().get::<DataKey, Vec<(u32, i128)>>(&key);
+        match res {
+            Some(history) => {
+                bump_persistent(e, &key);
+                history
             }
+            None => Vec::new(e),
         }
     }
+}
 
-    /// Updates the commission recipient - only current recipient can call
-    pub fn set_recipient(e: &Env, new_recipient: Address) -> Result<(), Error> {
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuyerSpentDataKey {}
+impl BuyerSpentDataKey {
+    /// Adds the amount to what the buyer has spent on share purchases in the token
+    pub fn add_spent(e: &Env, buyer: &Address, token: &Address, amount: i128) -> Result<(), Error> {
+        let spent = Self::get_spent(e, buyer, token)
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+
+        let key = DataKey::BuyerSpent(buyer.clone(), token.clone());
+        e.storage().persistent().set(&key, &spent);
+        bump_persistent(e, &key);
+        Ok(())
+    }
+
+    /// Returns what the buyer has spent on share purchases in the token, defaults to 0
+    pub fn get_spent(e: &Env, buyer: &Address, token: &Address) -> i128 {
+        let key = DataKey::BuyerSpent(buyer.clone(), token.clone());
+        let res = e.storage().persistent().get(&key);
+        match res {
+            Some(spent) => {
+                bump_persistent(e, &key);
+                spent
+            }
+            None => 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct EscrowDataKey {
+    pub buyer: Address,
+    pub seller: Address,
+    pub shares_amount: i128,
+    pub total_price: i128,
+    pub payment_token: Address,
+    pub expiration_ledger: u32,
+}
+
+impl EscrowDataKey {
+    /// Saves an escrowed purchase, adds its funds to the total escrow of the payment token
+    /// and reserves its shares of the seller
+    pub fn save_escrow(e: &Env, escrow: &EscrowDataKey) {
+        let total_escrow = Self::get_total_escrow(e, &escrow.payment_token);
+        Self::save_total_escrow(e, &escrow.payment_token, total_escrow + escrow.total_price);
+
+        let escrowed_shares = Self::get_escrowed_shares(e, &escrow.seller);
+        Self::save_escrowed_shares(e, &escrow.seller, escrowed_shares + escrow.shares_amount);
+
+        let key = DataKey::Escrow(escrow.buyer.clone(), escrow.seller.clone());
+        e.storage().persistent().set(&key, escrow);
+        bump_persistent(e, &key);
+    }
+
+    /// Gets an escrowed purchase
+    pub fn get_escrow(e: &Env, buyer: &Address, seller: &Address) -> Option<EscrowDataKey> {
+        let key = DataKey::Escrow(buyer.clone(), seller.clone());
+        let res = e.storage().persistent().get(&key);
+        match res {
+            Some(escrow) => {
+                bump_persistent(e, &key);
+                Some(escrow)
+            }
+            None => None,
+        }
+    }
+
+    /// Removes an escrowed purchase, subtracts its funds from the total escrow of the payment token
+    /// and releases the reserved shares of the seller
+    pub fn remove_escrow(e: &Env, escrow: &EscrowDataKey) {
+        let total_escrow = Self::get_total_escrow(e, &escrow.payment_token);
+        Self::save_total_escrow(e, &escrow.payment_token, total_escrow - escrow.total_price);
+
+        let escrowed_shares = Self::get_escrowed_shares(e, &escrow.seller);
+        Self::save_escrowed_shares(e, &escrow.seller, escrowed_shares - escrow.shares_amount);
+
+        let key = DataKey::Escrow(escrow.buyer.clone(), escrow.seller.clone());
+        e.storage().persistent().remove(&key);
+    }
+
+    /// Returns the shares of the seller reserved by escrowed purchases, defaults to 0
+    pub fn get_escrowed_shares(e: &Env, seller: &Address) -> i128 {
+        let key = ExtDataKey::EscrowedShares(seller.clone());
+        let res = e.storage().persistent().get(&key);
+        match res {
+            Some(escrowed_shares) => {
+                bump_persistent(e, &key);
+                escrowed_shares
+            }
+            None => 0,
+        }
+    }
+
+    fn save_escrowed_shares(e: &Env, seller: &Address, escrowed_shares: i128) {
+        let key = ExtDataKey::EscrowedShares(seller.clone());
+        if escrowed_shares <= 0 {
+            e.storage().persistent().remove(&key);
+        } else {
+            e.storage().persistent().set(&key, &escrowed_shares);
+            bump_persistent(e, &key);
+        }
+    }
+
+    /// Returns the total amount of a token held in escrow, defaults to 0
+    pub fn get_total_escrow(e: &Env, token: &Address) -> i128 {
+        let key = DataKey::TotalEscrow(token.clone());
+        let res = e.storage().persistent().get(&key);
+        match res {
+            Some(total_escrow) => {
+                bump_persistent(e, &key);
+                total_escrow
+            }
+            None => 0,
+        }
+    }
+
+    fn save_total_escrow(e: &Env, token: &Address, total_escrow: i128) {
+        let key = DataKey::TotalEscrow(token.clone());
+        if total_escrow <= 0 {
+            e.storage().persistent().remove(&key);
+        } else {
+            e.storage().persistent().set(&key, &total_escrow);
+            bump_persistent(e, &key);
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct PoolContribution {
+    pub seller: Address,
+    pub shares: i128, // Shares still for sale from this contribution
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PooledListingDataKey {}
+
+impl PooledListingDataKey {
+    /// Gets the contributions to the pool of the token and price, oldest first
+    pub fn get_contributions(
+        e: &Env,
+        token: &Address,
+        price_per_share: i128,
+    ) -> Vec<PoolContribution> {
+        let key = DataKey::PooledListing(token.clone(), price_per_share);
+        let res = e.storage().persistent().get(&key);
+        match res {
+            Some(contributions) => {
+                bump_persistent(e, &key);
+                contributions
+            }
+            None => Vec::new(e),
+        }
+    }
+
+    /// Saves the contributions to the pool of the token and price, an empty pool is removed
+    pub fn save_contributions(
+        e: &Env,
+        token: &Address,
+        price_per_share: i128,
+        contributions: &Vec<PoolContribution>,
+    ) {
+        let key = DataKey::PooledListing(token.clone(), price_per_share);
+        if contributions.is_empty() {
+            e.storage().persistent().remove(&key);
+        } else {
+            e.storage().persistent().set(&key, contributions);
+            bump_persistent(e, &key);
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct ShareLock {
+    pub amount: i128,       // Shares that cannot be listed for sale
+    pub unlock_ledger: u32, // Ledger from which the shares are free again
+}
+
+impl ShareLock {
+    /// Gets the share lock of the shareholder, if any
+    pub fn get(e: &Env, shareholder: &Address) -> Option<ShareLock> {
+        let key = DataKey::ShareLock(shareholder.clone());
+        let res = e.storage().persistent().get(&key);
+        match res {
+            Some(lock) => {
+                bump_persistent(e, &key);
+                Some(lock)
+            }
+            None => None,
+        }
+    }
+
+    /// Saves the share lock of the shareholder, 0 shares removes it
+    pub fn save(e: &Env, shareholder: &Address, amount: i128, unlock_ledger: u32) {
+        let key = DataKey::ShareLock(shareholder.clone());
+        if amount > 0 {
+            e.storage().persistent().set(&key, &ShareLock { amount, unlock_ledger });
+            bump_persistent(e, &key);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Returns the shares of the shareholder that are still locked, 0 once the lock expired
+    pub fn get_locked(e: &Env, shareholder: &Address) -> i128 {
+        match Self::get(e, shareholder) {
+            Some(lock) if e.ledger().sequence() < lock.unlock_ledger => lock.amount,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct EpochDataKey {
+    pub id: u32,
+    pub amount: i128, // Amount vesting to the shareholders over the epoch
+    pub start_ledger: u32,
+    pub duration_ledgers: u32,
+    pub claimed: i128, // Amount claimed by the shareholders so far
+}
+
+impl EpochDataKey {
+    /// Saves the epoch of the token
+    pub fn save_epoch(e: &Env, token: &Address, epoch: &EpochDataKey) {
+        let key = DataKey::Epoch(token.clone());
+        e.storage().persistent().set(&key, epoch);
+        bump_persistent(e, &key);
+    }
+
+    /// Gets the epoch of the token
+    pub fn get_epoch(e: &Env, token: &Address) -> Option<EpochDataKey> {
+        let key = DataKey::Epoch(token.clone());
+        let res = e.storage().persistent().get(&key);
+        match res {
+            Some(epoch) => {
+                bump_persistent(e, &key);
+                Some(epoch)
+            }
+            None => None,
+        }
+    }
+
+    /// Removes the epoch of the token and its share snapshot
+    pub fn remove_epoch(e: &Env, token: &Address) {
+        let key = DataKey::Epoch(token.clone());
+        e.storage().persistent().remove(&key);
+        e.storage()
+            .persistent()
+            .remove(&ExtDataKey::EpochShares(token.clone()));
+    }
+
+    /// Saves the shares the epoch of the token vests to, taken when it starts
+    pub fn save_epoch_shares(e: &Env, token: &Address, shares: &Map<Address, i128>) {
+        let key = ExtDataKey::EpochShares(token.clone());
+        e.storage().persistent().set(&key, shares);
+        bump_persistent(e, &key);
+    }
+
+    /// Gets the shares the epoch of the token vests to, None for epochs started
+    /// before the shares were snapshotted
+    pub fn get_epoch_shares(e: &Env, token: &Address) -> Option<Map<Address, i128>> {
+        let key = ExtDataKey::EpochShares(token.clone());
+        let res = e.storage().persistent().get(&key);
+        match res {
+            Some(shares) => {
+                bump_persistent(e, &key);
+                Some(shares)
+            }
+            None => None,
+        }
+    }
+
+    /// Returns the amount of the token locked in its epoch and not claimed yet
+    pub fn get_locked(e: &Env, token: &Address) -> i128 {
+        match Self::get_epoch(e, token) {
+            Some(epoch) => epoch.amount - epoch.claimed,
+            None => 0,
+        }
+    }
+
+    /// Returns true if the whole amount of the epoch has vested
+    pub fn is_ended(&self, e: &Env) -> bool {
+        e.ledger().sequence() >= self.start_ledger + self.duration_ledgers
+    }
+
+    /// Returns the amount vested so far, growing linearly over the duration
+    pub fn vested(&self, e: &Env) -> Result<i128, Error> {
+        let elapsed = e
+            .ledger()
+            .sequence()
+            .saturating_sub(self.start_ledger)
+            .min(self.duration_ledgers);
+
+        let vested = self
+            .amount
+            .checked_mul(elapsed as i128)
+            .ok_or(Error::Overflow)?
+            / self.duration_ledgers as i128;
+        Ok(vested)
+    }
+
+    /// Gets how much the shareholder claimed from the epoch
+    pub fn get_claimed(&self, e: &Env, token: &Address, shareholder: &Address) -> i128 {
+        let key = DataKey::EpochClaimed(token.clone(), shareholder.clone());
+        let res = e.storage().persistent().get::<DataKey, (u32, i128)>(&key);
+        match res {
+            // Claims from previous epochs don't count
+            Some((id, claimed)) if id == self.id => {
+                bump_persistent(e, &key);
+                claimed
+            }
+            _ => 0,
+        }
+    }
+
+    /// Saves how much the shareholder claimed from the epoch
+    pub fn save_claimed(&self, e: &Env, token: &Address, shareholder: &Address, claimed: i128) {
+        let key = DataKey::EpochClaimed(token.clone(), shareholder.clone());
+        e.storage().persistent().set(&key, &(self.id, claimed));
+        bump_persistent(e, &key);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct CommissionConfig {
+    pub recipient: Address,
+    pub buy_rate_bps: i128,          // Basis points for share purchases (150 = 1.5%)
+    pub distribution_rate_bps: i128, // Basis points for distributions (50 = 0.5%)
+}
+
+impl CommissionConfig {
+    /// Gets the commission config, falling back to the defaults if not set.
+    /// The defaults are only persisted once one of the setters is called.
+    pub fn get(e: &Env) -> CommissionConfig {
+        bump_instance(e);
+        let key = DataKey::Commission;
+        match e.storage().instance().get::<DataKey, CommissionConfig>(&key) {
+            Some(config) => config,
+            None => {
+                CommissionConfig {
+                    recipient: Self::get_default_recipient(e),
+                    buy_rate_bps: BUY_COMMISSION_BPS,
+                    distribution_rate_bps: DISTRIBUTION_COMMISSION_BPS,
+                }
+            }
+        }
+    }
+
+    /// Saves the commission config given at initialization, in place of the defaults
+    pub fn init(e: &Env, config: &CommissionConfig) -> Result<(), Error> {
+        // Commission sent to the contract itself would inflate its unused balance
+        if config.recipient == e.current_contract_address() {
+            return Err(Error::InvalidRecipient);
+        }
+
+        // Validate rates are reasonable (0-50% max) and not below the protocol floor
+        let floor = CommissionFloor::get(e);
+        if config.buy_rate_bps < 0
+            || config.buy_rate_bps > 5000
+            || config.buy_rate_bps < floor.buy_rate_bps
+        {
+            return Err(Error::InvalidCommissionRate);
+        }
+        if config.distribution_rate_bps < 0
+            || config.distribution_rate_bps > 5000
+            || config.distribution_rate_bps < floor.distribution_rate_bps
+        {
+            return Err(Error::InvalidCommissionRate);
+        }
+
+        let key = DataKey::Commission;
+        e.storage().instance().set(&key, config);
+        bump_instance(e);
+        Ok(())
+    }
+
+    /// Returns the commission recipient used until one is set
+    pub fn get_default_recipient(e: &Env) -> Address {
+        Address::from_string(&String::from_str(e, DEFAULT_COMMISSION_ADDRESS))
+    }
+
+    /// Updates the commission recipient - only current recipient can call
+    pub fn set_recipient(e: &Env, new_recipient: Address) -> Result<(), Error> {
         let config = Self::get(e);
         config.recipient.require_auth();
 
+        // Commission sent to the contract itself would inflate its unused balance
+        if new_recipient == e.current_contract_address() {
+            return Err(Error::InvalidRecipient);
+        }
+
         let new_config = CommissionConfig {
             recipient: new_recipient,
             buy_rate_bps: config.buy_rate_bps,
@@ -399,7 +1411,12 @@ impl CommissionConfig {
         config.recipient.require_auth();
 
         // Validate rate is reasonable (0-50% max)
-        if new_rate_bps < 0 || new_rate_bps > 5000 {
+        if !(0..=5000).contains(&new_rate_bps) {
+            return Err(Error::InvalidCommissionRate);
+        }
+
+        // The recipient cannot go below the protocol floor
+        if new_rate_bps < CommissionFloor::get(e).buy_rate_bps {
             return Err(Error::InvalidCommissionRate);
         }
 
@@ -420,7 +1437,12 @@ impl CommissionConfig {
         config.recipient.require_auth();
 
         // Validate rate is reasonable (0-50% max)
-        if new_rate_bps < 0 || new_rate_bps > 5000 {
+        if !(0..=5000).contains(&new_rate_bps) {
+            return Err(Error::InvalidCommissionRate);
+        }
+
+        // The recipient cannot go below the protocol floor
+        if new_rate_bps < CommissionFloor::get(e).distribution_rate_bps {
             return Err(Error::InvalidCommissionRate);
         }
 
@@ -435,33 +1457,847 @@ impl CommissionConfig {
         Ok(())
     }
 
+    /// Enables or disables accruing buy commissions in the contract - only current recipient can call
+    pub fn set_defer_buy_commission(e: &Env, enabled: bool) -> Result<(), Error> {
+        let config = Self::get(e);
+        config.recipient.require_auth();
+
+        let key = DataKey::DeferBuyCommission;
+        e.storage().instance().set(&key, &enabled);
+        bump_instance(e);
+        Ok(())
+    }
+
+    /// Returns true if buy commissions are accrued in the contract instead of
+    /// being transferred to the recipient on every purchase
+    pub fn is_buy_commission_deferred(e: &Env) -> bool {
+        bump_instance(e);
+        let key = DataKey::DeferBuyCommission;
+        e.storage().instance().get(&key).unwrap_or(false)
+    }
+
+    /// Returns the buy commission accrued in the contract for the payment token
+    pub fn get_accrued_buy_commission(e: &Env, token: &Address) -> i128 {
+        let key = DataKey::AccruedBuyCommission(token.clone());
+        let res = e.storage().persistent().get::<DataKey, i128>(&key);
+        match res {
+            Some(accrued) => {
+                bump_persistent(e, &key);
+                accrued
+            }
+            None => 0,
+        }
+    }
+
+    /// Adds to the buy commission accrued for the payment token
+    pub fn add_accrued_buy_commission(e: &Env, token: &Address, amount: i128) -> Result<(), Error> {
+        let accrued = Self::get_accrued_buy_commission(e, token)
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+
+        let key = DataKey::AccruedBuyCommission(token.clone());
+        e.storage().persistent().set(&key, &accrued);
+        bump_persistent(e, &key);
+        Ok(())
+    }
+
+    /// Returns the commission earned in the token from buys and from distributions
+    pub fn get_commission_earned(e: &Env, token: &Address) -> (i128, i128) {
+        let key = DataKey::CommissionEarned(token.clone());
+        let res = e.storage().persistent().get::<DataKey, (i128, i128)>(&key);
+        match res {
+            Some(earned) => {
+                bump_persistent(e, &key);
+                earned
+            }
+            None => (0, 0),
+        }
+    }
+
+    /// Adds to the commission earned in the token from buys and from distributions
+    pub fn add_commission_earned(
+        e: &Env,
+        token: &Address,
+        from_buys: i128,
+        from_distributions: i128,
+    ) -> Result<(), Error> {
+        let (buys, distributions) = Self::get_commission_earned(e, token);
+        let earned = (
+            buys.checked_add(from_buys).ok_or(Error::Overflow)?,
+            distributions.checked_add(from_distributions).ok_or(Error::Overflow)?,
+        );
+
+        let key = DataKey::CommissionEarned(token.clone());
+        e.storage().persistent().set(&key, &earned);
+        bump_persistent(e, &key);
+        Ok(())
+    }
+
+    /// Clears the buy commission accrued for the payment token
+    pub fn remove_accrued_buy_commission(e: &Env, token: &Address) {
+        let key = DataKey::AccruedBuyCommission(token.clone());
+        e.storage().persistent().remove(&key);
+    }
+
     /// Calculates commission from a total amount
     pub fn calculate_commission(amount: i128, rate_bps: i128) -> i128 {
         (amount * rate_bps) / 10000
     }
 }
 
-#[derive(Clone)]
-#[contracttype]
-pub enum DataKey {
-    Config,
-    // Storage keys for the shareholder and share data
-    //
-    /// Data key for keeping all of the shareholders in the contract
-    Shareholders,
-    /// Data key for keeping the share of a shareholder.
-    /// User addresses are mapped to their shares
-    Share(Address),
-    // Storage keys for the allocations
-    //
-    /// Data key for keeping the total allocation amount for a token.
-    /// Token addresses are mapped to their total allocation amount.
-    TotalAllocation(Address),
-    /// Data key for keeping the allocation amount for a shareholder.
-    /// User addresses with token addresses are mapped to their allocation amount.
-    ///
-    /// (UserAddr, TokenAddr) -> Allocation
+#[derive(Clone, Debug, PartialEq)]
+pub struct SettingsDataKey {}
+impl SettingsDataKey {
+    /// Returns the minimum number of shareholders the contract must keep.
+    /// Defaults to 0, which disables the check.
+    pub fn get_min_shareholders(e: &Env) -> u32 {
+        bump_instance(e);
+        let key = DataKey::MinShareholders;
+        e.storage().instance().get(&key).unwrap_or(0)
+    }
+
+    /// Saves the minimum number of shareholders
+    pub fn save_min_shareholders(e: &Env, min_shareholders: u32) {
+        bump_instance(e);
+        let key = DataKey::MinShareholders;
+        e.storage().instance().set(&key, &min_shareholders);
+    }
+
+    /// Returns the treasury receiving dust no shareholder qualifies for.
+    /// When not set, the commission recipient is used.
+    pub fn get_treasury(e: &Env) -> Option<Address> {
+        bump_instance(e);
+        let key = DataKey::Treasury;
+        e.storage().instance().get(&key)
+    }
+
+    /// Saves the treasury address
+    pub fn save_treasury(e: &Env, treasury: Address) {
+        bump_instance(e);
+        let key = DataKey::Treasury;
+        e.storage().instance().set(&key, &treasury);
+    }
+
+    /// Returns the maximum number of active listings in the marketplace.
+    /// Defaults to 0, which disables the limit.
+    pub fn get_max_active_listings(e: &Env) -> u32 {
+        bump_instance(e);
+        let key = DataKey::MaxActiveListings;
+        e.storage().instance().get(&key).unwrap_or(0)
+    }
+
+    /// Saves the maximum number of active listings
+    pub fn save_max_active_listings(e: &Env, max_active_listings: u32) {
+        bump_instance(e);
+        let key = DataKey::MaxActiveListings;
+        e.storage().instance().set(&key, &max_active_listings);
+    }
+
+    /// Returns the recipients `transfer_tokens` is restricted to, empty if unrestricted
+    pub fn get_transfer_allowlist(e: &Env) -> Vec<Address> {
+        bump_instance(e);
+        let key = DataKey::TransferAllowlist;
+        e.storage().instance().get(&key).unwrap_or(Vec::new(e))
+    }
+
+    /// Saves the recipients `transfer_tokens` is restricted to, an empty list removes it
+    pub fn save_transfer_allowlist(e: &Env, recipients: &Vec<Address>) {
+        bump_instance(e);
+        let key = DataKey::TransferAllowlist;
+        if recipients.is_empty() {
+            e.storage().instance().remove(&key);
+        } else {
+            e.storage().instance().set(&key, recipients);
+        }
+    }
+
+    /// Returns true if unused tokens can be transferred to the recipient
+    pub fn is_transfer_recipient_allowed(e: &Env, recipient: &Address) -> bool {
+        let allowlist = Self::get_transfer_allowlist(e);
+        allowlist.is_empty() || allowlist.contains(recipient)
+    }
+
+    /// Returns the most a single distribution can allocate to one shareholder.
+    /// Defaults to 0, which disables the cap.
+    pub fn get_max_allocation_per_dist(e: &Env) -> i128 {
+        bump_instance(e);
+        let key = DataKey::MaxAllocationPerDist;
+        e.storage().instance().get(&key).unwrap_or(0)
+    }
+
+    /// Saves the per-distribution allocation cap
+    pub fn save_max_allocation_per_dist(e: &Env, max_allocation: i128) {
+        bump_instance(e);
+        let key = DataKey::MaxAllocationPerDist;
+        e.storage().instance().set(&key, &max_allocation);
+    }
+
+    /// Returns true if distributions emit an event for every shareholder. Defaults to true.
+    pub fn get_emit_per_shareholder_events(e: &Env) -> bool {
+        bump_instance(e);
+        let key = DataKey::EmitPerShareholderEvents;
+        e.storage().instance().get(&key).unwrap_or(true)
+    }
+
+    /// Saves whether distributions emit an event for every shareholder
+    pub fn save_emit_per_shareholder_events(e: &Env, enabled: bool) {
+        bump_instance(e);
+        let key = DataKey::EmitPerShareholderEvents;
+        e.storage().instance().set(&key, &enabled);
+    }
+
+    /// Returns the basis points of every distribution kept for reinvestment.
+    /// Defaults to 0, which allocates everything to the shareholders.
+    pub fn get_reinvest_bps(e: &Env) -> i128 {
+        bump_instance(e);
+        let key = DataKey::ReinvestBps;
+        e.storage().instance().get(&key).unwrap_or(0)
+    }
+
+    /// Saves the reinvestment basis points
+    pub fn save_reinvest_bps(e: &Env, bps: i128) {
+        bump_instance(e);
+        let key = DataKey::ReinvestBps;
+        e.storage().instance().set(&key, &bps);
+    }
+
+    /// Returns the amount of the token earmarked for reinvestment
+    pub fn get_reinvest_pool(e: &Env, token: &Address) -> i128 {
+        let key = DataKey::ReinvestPool(token.clone());
+        let res = e.storage().persistent().get::<DataKey, i128>(&key);
+        match res {
+            Some(pool) => {
+                bump_persistent(e, &key);
+                pool
+            }
+            None => 0,
+        }
+    }
+
+    /// Saves the amount of the token earmarked for reinvestment, 0 removes it
+    pub fn save_reinvest_pool(e: &Env, token: &Address, pool: i128) {
+        let key = DataKey::ReinvestPool(token.clone());
+        if pool > 0 {
+            e.storage().persistent().set(&key, &pool);
+            bump_persistent(e, &key);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Returns the number of shares that traded amounts must be a multiple of.
+    /// Defaults to 1, which allows any amount.
+    pub fn get_share_lot_size(e: &Env) -> i128 {
+        bump_instance(e);
+        let key = DataKey::ShareLotSize;
+        e.storage().instance().get(&key).unwrap_or(1)
+    }
+
+    /// Saves the share lot size
+    pub fn save_share_lot_size(e: &Env, share_lot_size: i128) {
+        bump_instance(e);
+        let key = DataKey::ShareLotSize;
+        e.storage().instance().set(&key, &share_lot_size);
+    }
+
+    /// Returns true if the address is allowed to trigger distributions
+    pub fn is_distributor(e: &Env, distributor: &Address) -> bool {
+        let key = DataKey::Distributor(distributor.clone());
+        let res = e.storage().persistent().get::<DataKey, bool>(&key);
+        match res {
+            Some(enabled) => {
+                bump_persistent(e, &key);
+                enabled
+            }
+            None => false,
+        }
+    }
+
+    /// Allows or disallows the address to trigger distributions
+    pub fn save_distributor(e: &Env, distributor: &Address, enabled: bool) {
+        let key = DataKey::Distributor(distributor.clone());
+        if enabled {
+            e.storage().persistent().set(&key, &true);
+            bump_persistent(e, &key);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Returns true if the address is blocked from listing shares for sale
+    pub fn is_seller_blocked(e: &Env, seller: &Address) -> bool {
+        let key = DataKey::SellerBlocklist(seller.clone());
+        let res = e.storage().persistent().get::<DataKey, bool>(&key);
+        match res {
+            Some(blocked) => {
+                bump_persistent(e, &key);
+                blocked
+            }
+            None => false,
+        }
+    }
+
+    /// Blocks or unblocks the address from listing shares for sale
+    pub fn save_seller_blocked(e: &Env, seller: &Address, blocked: bool) {
+        let key = DataKey::SellerBlocklist(seller.clone());
+        if blocked {
+            e.storage().persistent().set(&key, &true);
+            bump_persistent(e, &key);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Returns the identity id the address is linked to, 0 if it is not linked
+    pub fn get_identity(e: &Env, address: &Address) -> u64 {
+        let key = DataKey::Identity(address.clone());
+        let res = e.storage().persistent().get::<DataKey, u64>(&key);
+        match res {
+            Some(identity) => {
+                bump_persistent(e, &key);
+                identity
+            }
+            None => 0,
+        }
+    }
+
+    /// Links the address to an identity id, 0 unlinks it
+    pub fn save_identity(e: &Env, address: &Address, identity: u64) {
+        let key = DataKey::Identity(address.clone());
+        if identity > 0 {
+            e.storage().persistent().set(&key, &identity);
+            bump_persistent(e, &key);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Returns true if both addresses are linked to the same identity
+    pub fn is_same_identity(e: &Env, a: &Address, b: &Address) -> bool {
+        let identity = Self::get_identity(e, a);
+        identity > 0 && identity == Self::get_identity(e, b)
+    }
+
+    /// Returns true if sales by or to the address don't pay the buy commission
+    pub fn is_buy_commission_exempt(e: &Env, address: &Address) -> bool {
+        let key = DataKey::BuyCommissionExempt(address.clone());
+        let res = e.storage().persistent().get::<DataKey, bool>(&key);
+        match res {
+            Some(exempt) => {
+                bump_persistent(e, &key);
+                exempt
+            }
+            None => false,
+        }
+    }
+
+    /// Exempts or stops exempting the address from the buy commission
+    pub fn save_buy_commission_exempt(e: &Env, address: &Address, exempt: bool) {
+        let key = DataKey::BuyCommissionExempt(address.clone());
+        if exempt {
+            e.storage().persistent().set(&key, &true);
+            bump_persistent(e, &key);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Returns the smallest partial withdrawal allowed for the token, 0 if there is none
+    pub fn get_min_claimable(e: &Env, token: &Address) -> i128 {
+        let key = DataKey::MinClaimable(token.clone());
+        let res = e.storage().persistent().get::<DataKey, i128>(&key);
+        match res {
+            Some(min_claimable) => {
+                bump_persistent(e, &key);
+                min_claimable
+            }
+            None => 0,
+        }
+    }
+
+    /// Saves the smallest partial withdrawal allowed for the token, 0 removes it
+    pub fn save_min_claimable(e: &Env, token: &Address, min_claimable: i128) {
+        let key = DataKey::MinClaimable(token.clone());
+        if min_claimable > 0 {
+            e.storage().persistent().set(&key, &min_claimable);
+            bump_persistent(e, &key);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Returns the ledgers a shareholder must wait between share transfers.
+    /// Defaults to 0, which disables the cooldown.
+    pub fn get_transfer_cooldown_ledgers(e: &Env) -> u32 {
+        bump_instance(e);
+        let key = DataKey::TransferCooldownLedgers;
+        e.storage().instance().get(&key).unwrap_or(0)
+    }
+
+    /// Saves the share transfer cooldown
+    pub fn save_transfer_cooldown_ledgers(e: &Env, cooldown_ledgers: u32) {
+        bump_instance(e);
+        let key = DataKey::TransferCooldownLedgers;
+        e.storage().instance().set(&key, &cooldown_ledgers);
+    }
+
+    /// Returns the ledger of the shareholder's last share transfer, if it was recorded
+    pub fn get_last_transfer_ledger(e: &Env, shareholder: &Address) -> Option<u32> {
+        let key = DataKey::LastTransferLedger(shareholder.clone());
+        let res = e.storage().persistent().get(&key);
+        match res {
+            Some(ledger) => {
+                bump_persistent(e, &key);
+                Some(ledger)
+            }
+            None => None,
+        }
+    }
+
+    /// Saves the ledger of the shareholder's last share transfer
+    pub fn save_last_transfer_ledger(e: &Env, shareholder: &Address, ledger: u32) {
+        let key = DataKey::LastTransferLedger(shareholder.clone());
+        e.storage().persistent().set(&key, &ledger);
+        bump_persistent(e, &key);
+    }
+
+    /// Returns true if the commission recipient pays the buy commission on its own purchases.
+    /// Defaults to false, which lets the recipient buy without commission.
+    pub fn get_recipient_pays_commission(e: &Env) -> bool {
+        bump_instance(e);
+        let key = ExtDataKey::RecipientPaysCommission;
+        e.storage().instance().get(&key).unwrap_or(false)
+    }
+
+    /// Saves whether the commission recipient pays the buy commission on its own purchases
+    pub fn save_recipient_pays_commission(e: &Env, enabled: bool) {
+        bump_instance(e);
+        let key = ExtDataKey::RecipientPaysCommission;
+        e.storage().instance().set(&key, &enabled);
+    }
+
+    /// Returns true if the address is frozen by a compliance hold
+    pub fn is_frozen(e: &Env, address: &Address) -> bool {
+        let key = ExtDataKey::Frozen(address.clone());
+        let res = e.storage().persistent().get::<ExtDataKey, bool>(&key);
+        match res {
+            Some(frozen) => {
+                bump_persistent(e, &key);
+                frozen
+            }
+            None => false,
+        }
+    }
+
+    /// Freezes or unfreezes the address
+    pub fn save_frozen(e: &Env, address: &Address, frozen: bool) {
+        let key = ExtDataKey::Frozen(address.clone());
+        if frozen {
+            e.storage().persistent().set(&key, &true);
+            bump_persistent(e, &key);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Returns true if the shareholder opted out of distributions
+    pub fn is_opted_out(e: &Env, shareholder: &Address) -> bool {
+        let key = ExtDataKey::OptOut(shareholder.clone());
+        let res = e.storage().persistent().get::<ExtDataKey, bool>(&key);
+        match res {
+            Some(opted_out) => {
+                bump_persistent(e, &key);
+                opted_out
+            }
+            None => false,
+        }
+    }
+
+    /// Opts the shareholder out of or back into distributions
+    pub fn save_opted_out(e: &Env, shareholder: &Address, opted_out: bool) {
+        let key = ExtDataKey::OptOut(shareholder.clone());
+        if opted_out {
+            e.storage().persistent().set(&key, &true);
+            bump_persistent(e, &key);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Returns the ledgers that must pass between distributions of the token.
+    /// Defaults to 0, which disables the cooldown.
+    pub fn get_distribution_cooldown_ledgers(e: &Env, token: &Address) -> u32 {
+        let key = ExtDataKey::DistributionCooldownLedgers(token.clone());
+        let res = e.storage().persistent().get::<ExtDataKey, u32>(&key);
+        match res {
+            Some(cooldown_ledgers) => {
+                bump_persistent(e, &key);
+                cooldown_ledgers
+            }
+            None => 0,
+        }
+    }
+
+    /// Saves the distribution cooldown of the token, 0 removes it
+    pub fn save_distribution_cooldown_ledgers(e: &Env, token: &Address, cooldown_ledgers: u32) {
+        let key = ExtDataKey::DistributionCooldownLedgers(token.clone());
+        if cooldown_ledgers > 0 {
+            e.storage().persistent().set(&key, &cooldown_ledgers);
+            bump_persistent(e, &key);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Returns the ledger of the token's last distribution, if it was recorded
+    pub fn get_last_distribution_ledger(e: &Env, token: &Address) -> Option<u32> {
+        let key = ExtDataKey::LastDistributionLedger(token.clone());
+        let res = e.storage().persistent().get(&key);
+        match res {
+            Some(ledger) => {
+                bump_persistent(e, &key);
+                Some(ledger)
+            }
+            None => None,
+        }
+    }
+
+    /// Saves the ledger of the token's last distribution
+    pub fn save_last_distribution_ledger(e: &Env, token: &Address, ledger: u32) {
+        let key = ExtDataKey::LastDistributionLedger(token.clone());
+        e.storage().persistent().set(&key, &ledger);
+        bump_persistent(e, &key);
+    }
+
+    /// Returns the least a seller must receive from a sale paid in the token, after commission.
+    /// Defaults to 1, so sales that leave the seller with nothing are always rejected.
+    pub fn get_min_seller_proceeds(e: &Env, token: &Address) -> i128 {
+        let key = DataKey::MinSellerProceeds(token.clone());
+        let res = e.storage().persistent().get::<DataKey, i128>(&key);
+        match res {
+            Some(min_proceeds) => {
+                bump_persistent(e, &key);
+                min_proceeds
+            }
+            None => 1,
+        }
+    }
+
+    /// Saves the least a seller must receive from a sale paid in the token, 1 or less removes it
+    pub fn save_min_seller_proceeds(e: &Env, token: &Address, min_proceeds: i128) {
+        let key = DataKey::MinSellerProceeds(token.clone());
+        if min_proceeds > 1 {
+            e.storage().persistent().set(&key, &min_proceeds);
+            bump_persistent(e, &key);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct CommissionFloor {
+    pub buy_rate_bps: i128,          // Minimum basis points for share purchases
+    pub distribution_rate_bps: i128, // Minimum basis points for distributions
+}
+
+impl CommissionFloor {
+    /// Gets the commission floor set by the admin, defaults to no floor
+    pub fn get(e: &Env) -> CommissionFloor {
+        bump_instance(e);
+        let key = DataKey::CommissionFloor;
+        e.storage()
+            .instance()
+            .get::<DataKey, CommissionFloor>(&key)
+            .unwrap_or(CommissionFloor {
+                buy_rate_bps: 0,
+                distribution_rate_bps: 0,
+            })
+    }
+
+    /// Updates the commission floor
+    pub fn set(e: &Env, buy_rate_bps: i128, distribution_rate_bps: i128) -> Result<(), Error> {
+        // Validate floors are within the allowed rate range (0-50% max)
+        if !(0..=5000).contains(&buy_rate_bps) {
+            return Err(Error::InvalidCommissionRate);
+        }
+        if !(0..=5000).contains(&distribution_rate_bps) {
+            return Err(Error::InvalidCommissionRate);
+        }
+
+        let key = DataKey::CommissionFloor;
+        e.storage().instance().set(
+            &key,
+            &CommissionFloor {
+                buy_rate_bps,
+                distribution_rate_bps,
+            },
+        );
+        bump_instance(e);
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct FixedPayee {
+    pub address: Address,
+    pub bps: i128, // Basis points of every distribution, taken after commission
+}
+
+impl FixedPayee {
+    /// Gets the fixed payee set by the admin, if any
+    pub fn get(e: &Env) -> Option<FixedPayee> {
+        bump_instance(e);
+        let key = DataKey::FixedPayee;
+        e.storage().instance().get(&key)
+    }
+
+    /// Updates the fixed payee, 0 bps removes it
+    pub fn set(e: &Env, address: Address, bps: i128) -> Result<(), Error> {
+        // Validate the payee share is within 0-100%
+        if !(0..=10000).contains(&bps) {
+            return Err(Error::InvalidPayeeShare);
+        }
+
+        let key = DataKey::FixedPayee;
+        if bps == 0 {
+            e.storage().instance().remove(&key);
+        } else {
+            e.storage().instance().set(&key, &FixedPayee { address, bps });
+        }
+        bump_instance(e);
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct TransferCommission {
+    pub token: Address,
+    pub bps: i128, // Basis points of the transferred shares charged to the sender in the fee token
+}
+
+impl TransferCommission {
+    /// Gets the share transfer commission set by the admin, if any
+    pub fn get(e: &Env) -> Option<TransferCommission> {
+        bump_instance(e);
+        let key = DataKey::TransferCommission;
+        e.storage().instance().get(&key)
+    }
+
+    /// Updates the share transfer commission, 0 bps removes it
+    pub fn set(e: &Env, token: Address, bps: i128) -> Result<(), Error> {
+        // Validate the commission is within 0-100%
+        if !(0..=10000).contains(&bps) {
+            return Err(Error::InvalidCommissionRate);
+        }
+
+        let key = DataKey::TransferCommission;
+        if bps == 0 {
+            e.storage().instance().remove(&key);
+        } else {
+            e.storage().instance().set(&key, &TransferCommission { token, bps });
+        }
+        bump_instance(e);
+        Ok(())
+    }
+
+    /// Returns the fee for transferring `amount` shares
+    pub fn calculate(&self, amount: i128) -> Result<i128, Error> {
+        Ok(amount.checked_mul(self.bps).ok_or(Error::Overflow)? / 10000)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct CancelPenalty {
+    pub bps: i128, // Basis points of the listing value charged to the seller
+    pub min_age_ledgers: u32, // Listings cancelled before this age are penalized
+}
+
+impl CancelPenalty {
+    /// Gets the early cancellation penalty set by the admin, if any
+    pub fn get(e: &Env) -> Option<CancelPenalty> {
+        bump_instance(e);
+        let key = DataKey::CancelPenalty;
+        e.storage().instance().get(&key)
+    }
+
+    /// Updates the early cancellation penalty, 0 bps removes it
+    pub fn set(e: &Env, bps: i128, min_age_ledgers: u32) -> Result<(), Error> {
+        // Validate the penalty is within 0-100%
+        if !(0..=10000).contains(&bps) {
+            return Err(Error::InvalidCancelPenalty);
+        }
+
+        let key = DataKey::CancelPenalty;
+        if bps == 0 {
+            e.storage().instance().remove(&key);
+        } else {
+            e.storage().instance().set(&key, &CancelPenalty { bps, min_age_ledgers });
+        }
+        bump_instance(e);
+        Ok(())
+    }
+
+    /// Returns the penalty for cancelling the listing now, 0 if it is old enough
+    pub fn calculate(&self, e: &Env, listing: &SaleListingDataKey) -> Result<i128, Error> {
+        if e.ledger().sequence() >= listing.created_ledger.saturating_add(self.min_age_ledgers) {
+            return Ok(0);
+        }
+
+        let listing_value = listing
+            .shares_for_sale
+            .checked_mul(listing.price_per_share)
+            .ok_or(Error::Overflow)?;
+        Ok(listing_value.checked_mul(self.bps).ok_or(Error::Overflow)? / 10000)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct ListingFee {
+    pub bps: i128, // Basis points of the listing value charged to the seller on creation
+    pub refund_window_ledgers: u32, // Listings cancelled within this age get a pro-rated refund
+}
+
+impl ListingFee {
+    /// Gets the listing fee set by the admin, if any
+    pub fn get(e: &Env) -> Option<ListingFee> {
+        bump_instance(e);
+        let key = DataKey::ListingFee;
+        e.storage().instance().get(&key)
+    }
+
+    /// Updates the listing fee, 0 bps removes it
+    pub fn set(e: &Env, bps: i128, refund_window_ledgers: u32) -> Result<(), Error> {
+        // Validate the fee is within 0-100%
+        if !(0..=10000).contains(&bps) {
+            return Err(Error::InvalidListingFee);
+        }
+
+        let key = DataKey::ListingFee;
+        if bps == 0 {
+            e.storage().instance().remove(&key);
+        } else {
+            e.storage().instance().set(&key, &ListingFee { bps, refund_window_ledgers });
+        }
+        bump_instance(e);
+        Ok(())
+    }
+
+    /// Returns the fee for listing `shares_amount` at `price_per_share`
+    pub fn calculate(&self, shares_amount: i128, price_per_share: i128) -> Result<i128, Error> {
+        let listing_value = shares_amount
+            .checked_mul(price_per_share)
+            .ok_or(Error::Overflow)?;
+        Ok(listing_value.checked_mul(self.bps).ok_or(Error::Overflow)? / 10000)
+    }
+
+    /// Returns the part of the paid fee refunded for cancelling the listing now.
+    /// The refund shrinks linearly with the listing age, down to 0 at the end of the window.
+    pub fn calculate_refund(&self, e: &Env, listing: &SaleListingDataKey) -> Result<i128, Error> {
+        let age = e.ledger().sequence().saturating_sub(listing.created_ledger);
+        if listing.fee_paid <= 0 || age >= self.refund_window_ledgers {
+            return Ok(0);
+        }
+
+        let remaining = (self.refund_window_ledgers - age) as i128;
+        Ok(listing.fee_paid.checked_mul(remaining).ok_or(Error::Overflow)?
+            / self.refund_window_ledgers as i128)
+    }
+
+    /// Returns the listing fees held in the contract for the commission recipient
+    pub fn get_accrued(e: &Env, token: &Address) -> i128 {
+        let key = ExtDataKey::AccruedListingFees(token.clone());
+        let res = e.storage().persistent().get::<ExtDataKey, i128>(&key);
+        match res {
+            Some(accrued) => {
+                bump_persistent(e, &key);
+                accrued
+            }
+            None => 0,
+        }
+    }
+
+    /// Adds to the listing fees held for the token
+    pub fn add_accrued(e: &Env, token: &Address, amount: i128) -> Result<(), Error> {
+        let accrued = Self::get_accrued(e, token)
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+
+        let key = ExtDataKey::AccruedListingFees(token.clone());
+        if accrued > 0 {
+            e.storage().persistent().set(&key, &accrued);
+            bump_persistent(e, &key);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+        Ok(())
+    }
+
+    /// Returns the part of the held listing fees of the token that active listings
+    /// would be refunded if they were cancelled now
+    pub fn get_refundable(e: &Env, token: &Address) -> Result<i128, Error> {
+        let listing_fee = match Self::get(e) {
+            Some(listing_fee) => listing_fee,
+            None => return Ok(0),
+        };
+
+        let mut refundable: i128 = 0;
+        for seller in SaleListingDataKey::get_active_listings(e).iter() {
+            if let Some(listing) = SaleListingDataKey::get_listing(e, &seller) {
+                if listing.fee_token == *token {
+                    refundable = refundable
+                        .checked_add(listing_fee.calculate_refund(e, &listing)?)
+                        .ok_or(Error::Overflow)?;
+                }
+            }
+        }
+        Ok(refundable)
+    }
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    Config,
+    /// Data key for keeping the storage layout version
+    Version,
+    /// Data key for keeping whether the contract was used since its initialization
+    ActivityFlag,
+    // Storage keys for the shareholder and share data
+    //
+    /// Data key for keeping all of the shareholders in the contract
+    Shareholders,
+    /// Data key for keeping the total all shares sum up to
+    ShareDenominator,
+    /// Data key for keeping the share of a shareholder.
+    /// User addresses are mapped to their shares
+    Share(Address),
+    /// Data key for keeping the shares of a shareholder locked until a ledger.
+    /// User addresses are mapped to their lock
+    ShareLock(Address),
+    // Storage keys for the allocations
+    //
+    /// Data key for keeping the total allocation amount for a token.
+    /// Token addresses are mapped to their total allocation amount.
+    TotalAllocation(Address),
+    /// Data key for keeping the allocation amount for a shareholder.
+    /// User addresses with token addresses are mapped to their allocation amount.
+    ///
+    /// (UserAddr, TokenAddr) -> Allocation
     Allocation(Address, Address),
+    /// Data key for keeping the tokens a shareholder has an allocation in.
+    ///
+    /// UserAddr -> Vec<TokenAddr>
+    ShareholderTokens(Address),
+    /// Data key for keeping how much of an allocation an operator can withdraw for a shareholder.
+    ///
+    /// (UserAddr, OperatorAddr, TokenAddr) -> Allowance
+    WithdrawAllowance(Address, Address, Address),
     // Storage keys for the share marketplace
     //
     /// Data key for keeping the sale listing for a seller.
@@ -469,8 +2305,169 @@ pub enum DataKey {
     SaleListing(Address),
     /// Data key for keeping all active listings in the marketplace.
     ActiveListings,
+    /// Data key for keeping the sellers with an active listing priced in a token.
+    ///
+    /// TokenAddr -> Vec<SellerAddr>
+    ListingsByToken(Address),
+    /// Data key for keeping the listing price history of a seller.
+    ///
+    /// SellerAddr -> Vec<(Ledger, PricePerShare)>
+    PriceHistory(Address),
+    /// Data key for keeping how much a buyer has spent on share purchases in a token.
+    ///
+    /// (BuyerAddr, TokenAddr) -> Spent
+    BuyerSpent(Address, Address),
+    /// Data key for keeping how many shares a buyer bought from a seller's current listing.
+    ///
+    /// (BuyerAddr, SellerAddr) -> (ListingId, Filled)
+    BuyerListingFilled(Address, Address),
+    /// Data key for keeping an escrowed purchase.
+    ///
+    /// (BuyerAddr, SellerAddr) -> EscrowDataKey
+    Escrow(Address, Address),
+    /// Data key for keeping the total amount of a token held in escrow.
+    /// Token addresses are mapped to their total escrowed amount.
+    TotalEscrow(Address),
     // Storage keys for commission
     //
     /// Data key for keeping the commission configuration
     Commission,
+    /// Data key for keeping the minimum commission rates set by the admin
+    CommissionFloor,
+    // Storage keys for admin managed settings
+    //
+    /// Data key for keeping the minimum number of shareholders
+    MinShareholders,
+    /// Data key for keeping the treasury receiving undistributable dust
+    Treasury,
+    /// Data key for keeping the maximum number of active listings in the marketplace
+    MaxActiveListings,
+    /// Data key for keeping the addresses allowed to trigger distributions besides the admin
+    Distributor(Address),
+    /// Data key for keeping the addresses blocked from listing shares for sale.
+    /// User addresses are mapped to true
+    SellerBlocklist(Address),
+    /// Data key for linking addresses controlled by the same entity.
+    ///
+    /// Addr -> IdentityId
+    Identity(Address),
+    /// Data key for keeping the number of shares traded amounts must be a multiple of
+    ShareLotSize,
+    /// Data key for keeping the ledgers a shareholder must wait between share transfers
+    TransferCooldownLedgers,
+    /// Data key for keeping the ledger of a shareholder's last share transfer.
+    /// User addresses are mapped to their ledger
+    LastTransferLedger(Address),
+    /// Data key for keeping the most a single distribution can allocate to one shareholder
+    MaxAllocationPerDist,
+    /// Data key for keeping whether distributions emit an event for every shareholder
+    EmitPerShareholderEvents,
+    /// Data key for keeping the basis points of every distribution kept for reinvestment
+    ReinvestBps,
+    /// Data key for keeping the amount of a token earmarked for reinvestment.
+    /// Token addresses are mapped to their pool.
+    ReinvestPool(Address),
+    /// Data key for keeping the smallest partial withdrawal allowed for a token.
+    /// Token addresses are mapped to their minimum.
+    MinClaimable(Address),
+    /// Data key for keeping the least a seller must receive from a sale.
+    /// Token addresses are mapped to their minimum.
+    MinSellerProceeds(Address),
+    /// Data key for keeping the addresses whose sales don't pay the buy commission
+    BuyCommissionExempt(Address),
+    /// Data key for keeping the recipients `transfer_tokens` is restricted to
+    TransferAllowlist,
+    /// Data key for keeping the id of the next sale listing
+    NextListingId,
+    /// Data key for keeping the seller of the open listing with an id.
+    ///
+    /// ListingId -> SellerAddr
+    ListingSeller(u64),
+    /// Data key for keeping the shares contributed to a pooled listing.
+    ///
+    /// (TokenAddr, PricePerShare) -> Vec<PoolContribution>
+    PooledListing(Address, i128),
+    /// Data key for keeping the vesting distribution epoch of a token.
+    ///
+    /// TokenAddr -> Epoch
+    Epoch(Address),
+    /// Data key for keeping how much a shareholder claimed from the epoch of a token.
+    ///
+    /// (TokenAddr, UserAddr) -> (EpochId, Claimed)
+    EpochClaimed(Address, Address),
+    /// Data key for keeping the external payee receiving a fixed part of every distribution
+    FixedPayee,
+    /// Data key for keeping the commission charged on share transfers
+    TransferCommission,
+    /// Data key for keeping the penalty charged for cancelling a listing too early
+    CancelPenalty,
+    /// Data key for keeping the fee charged for creating a listing
+    ListingFee,
+    /// Data key for keeping whether buy commissions are accrued instead of transferred
+    DeferBuyCommission,
+    /// Data key for keeping the buy commission accrued for a payment token.
+    ///
+    /// TokenAddr -> Amount
+    AccruedBuyCommission(Address),
+    /// Data key for keeping the commission earned in a token, by source.
+    ///
+    /// TokenAddr -> (FromBuys, FromDistributions)
+    CommissionEarned(Address),
+}
+
+/// Data keys added after `DataKey` reached the 50 cases a contract type can have
+#[derive(Clone)]
+#[contracttype]
+pub enum ExtDataKey {
+    /// Data key for keeping the ledgers that must pass between distributions of a token.
+    ///
+    /// TokenAddr -> CooldownLedgers
+    DistributionCooldownLedgers(Address),
+    /// Data key for keeping the ledger of the last distribution of a token.
+    /// Only recorded while the token has a distribution cooldown.
+    ///
+    /// TokenAddr -> Ledger
+    LastDistributionLedger(Address),
+    /// Data key for keeping whether the commission recipient pays the buy commission
+    /// on its own purchases.
+    RecipientPaysCommission,
+    /// Data key for keeping the addresses frozen by a compliance hold.
+    /// Frozen addresses keep accruing allocations but can't move shares or withdraw.
+    ///
+    /// Address -> bool
+    Frozen(Address),
+    /// Data key for keeping the time weight a shareholder accumulated in a period, as the
+    /// sum of share * ledgers held until the ledger the share last changed.
+    ///
+    /// Shareholder -> (Period, Weight, Ledger)
+    ShareTimeWeight(Address),
+    /// Data key for keeping the number of the current time weight period
+    TimeWeightPeriod,
+    /// Data key for keeping the ledger the current time weight period started at
+    TimeWeightStart,
+    /// Data key for keeping the shareholders that opted out of distributions.
+    /// Their portion is split among the other shareholders.
+    ///
+    /// Shareholder -> bool
+    OptOut(Address),
+    /// Data key for keeping the latest share checkpoints of a shareholder,
+    /// at most `MAX_SHARE_CHECKPOINTS`.
+    ///
+    /// Shareholder -> Vec<(Ledger, Share)>
+    ShareCheckpoints(Address),
+    /// Data key for keeping the shares an epoch vests to, snapshotted when it starts,
+    /// so moving shares during the epoch can't claim the same slice twice.
+    ///
+    /// TokenAddr -> Map<Shareholder, Share>
+    EpochShares(Address),
+    /// Data key for keeping the shares of a seller reserved by escrowed purchases,
+    /// which can't be listed or moved until the escrow is released or refunded.
+    ///
+    /// SellerAddr -> Shares
+    EscrowedShares(Address),
+    /// Data key for keeping the listing fees held in the contract for the commission
+    /// recipient, apart from the accrued buy commission.
+    ///
+    /// TokenAddr -> Amount
+    AccruedListingFees(Address),
 }
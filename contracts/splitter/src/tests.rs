@@ -1,7 +1,19 @@
+mod claim_initial_admin;
+mod commission;
 mod distribute;
+mod distributor;
+mod epoch;
+mod errors;
+mod fixed_payee;
+mod freeze_shareholder;
 mod helpers;
 mod init;
 mod lock_contract;
+mod migrate;
+mod migrate_shareholder;
+mod rebuild_shareholders;
+mod min_shareholders;
+mod share_lot_size;
 mod transfer_tokens;
 mod transfer_shares;
 mod update_shares;
@@ -13,3 +25,6 @@ mod marketplace_cancel;
 mod marketplace_buy;
 mod marketplace_queries;
 mod marketplace_integration;
+mod marketplace_escrow;
+mod marketplace_expiration;
+mod marketplace_pool;
@@ -0,0 +1,61 @@
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+use crate::{
+    errors::Error,
+    tests::helpers::{
+        create_splitter, create_splitter_with_default_shares, create_token,
+        setup_test_commission_recipient,
+    },
+};
+
+#[test]
+fn happy_path() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_default_shares(&env, &admin);
+
+    splitter.claim_initial_admin(&new_admin);
+    assert_eq!(splitter.get_config().admin, new_admin);
+
+    // The admin can only be claimed once
+    assert_eq!(
+        splitter.try_claim_initial_admin(&Address::generate(&env)),
+        Err(Ok(Error::InitialAdminClaimDisabled))
+    );
+}
+
+#[test]
+fn test_not_initialized() {
+    let env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    assert_eq!(
+        splitter.try_claim_initial_admin(&Address::generate(&env)),
+        Err(Ok(Error::NotInitialized))
+    );
+}
+
+#[test]
+fn test_disabled_after_distribution() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_default_shares(&env, &admin);
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    assert_eq!(
+        splitter.try_claim_initial_admin(&Address::generate(&env)),
+        Err(Ok(Error::InitialAdminClaimDisabled))
+    );
+    assert_eq!(splitter.get_config().admin, admin);
+}
@@ -0,0 +1,553 @@
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::{CommissionConfig, DataKey, ShareDataKey},
+    tests::helpers::{
+        create_splitter, create_splitter_with_default_shares, create_splitter_with_shares,
+        create_token, setup_test_commission_recipient,
+    },
+};
+
+#[test]
+fn commission_floor_rejects_lower_rates() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_default_shares(&env, &admin);
+    splitter.set_commission_recipient(&Address::generate(&env));
+
+    splitter.set_commission_floor(&100, &25);
+    let floor = splitter.get_commission_floor();
+    assert_eq!(floor.buy_rate_bps, 100);
+    assert_eq!(floor.distribution_rate_bps, 25);
+
+    // The recipient cannot go below the floor
+    assert_eq!(
+        splitter.try_set_buy_commission_rate(&99),
+        Err(Ok(Error::InvalidCommissionRate))
+    );
+    assert_eq!(
+        splitter.try_set_distribution_commission_rate(&0),
+        Err(Ok(Error::InvalidCommissionRate))
+    );
+
+    // Rates at or above the floor are accepted
+    splitter.set_buy_commission_rate(&100);
+    splitter.set_distribution_commission_rate(&30);
+    let config = splitter.get_commission_config();
+    assert_eq!(config.buy_rate_bps, 100);
+    assert_eq!(config.distribution_rate_bps, 30);
+}
+
+#[test]
+fn commission_floor_defaults_to_zero() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_default_shares(&env, &admin);
+    splitter.set_commission_recipient(&Address::generate(&env));
+
+    let floor = splitter.get_commission_floor();
+    assert_eq!(floor.buy_rate_bps, 0);
+    assert_eq!(floor.distribution_rate_bps, 0);
+
+    splitter.set_buy_commission_rate(&0);
+    assert_eq!(splitter.get_commission_config().buy_rate_bps, 0);
+}
+
+#[test]
+fn test_invalid_commission_floor() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_default_shares(&env, &admin);
+
+    assert_eq!(
+        splitter.try_set_commission_floor(&5001, &0),
+        Err(Ok(Error::InvalidCommissionRate))
+    );
+    assert_eq!(
+        splitter.try_set_commission_floor(&0, &-1),
+        Err(Ok(Error::InvalidCommissionRate))
+    );
+}
+
+#[test]
+fn test_commission_floor_not_initialized() {
+    let env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    assert_eq!(
+        splitter.try_set_commission_floor(&100, &25),
+        Err(Ok(Error::NotInitialized))
+    );
+}
+
+#[test]
+fn test_commission_floor_unauthorized() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_default_shares(&env, &admin);
+
+    assert!(splitter.try_set_commission_floor(&100, &25).is_err());
+}
+
+#[test]
+fn deferred_buy_commission_is_accrued_and_swept() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    let recipient = setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &3_000_000);
+
+    splitter.set_defer_buy_commission(&true);
+    splitter.list_shares_for_sale(&seller, &3000, &1000, &payment_token_address);
+
+    // Each buy costs 1_000_000 with 15_000 (1.5%) of commission
+    splitter.buy_shares(&buyer, &seller, &1000);
+    splitter.buy_shares(&buyer, &seller, &1000);
+    splitter.buy_shares(&buyer, &seller, &1000);
+
+    assert_eq!(splitter.get_accrued_buy_commission(&payment_token_address), 45_000);
+    assert_eq!(payment_token.balance(&recipient), 0);
+    assert_eq!(payment_token.balance(&seller), 2_955_000);
+    assert_eq!(payment_token.balance(&splitter_address), 45_000);
+
+    // The accrued commission is not distributed to the shareholders
+    splitter.distribute_tokens(&payment_token_address);
+    assert_eq!(splitter.get_allocation(&buyer, &payment_token_address), 0);
+    assert_eq!(payment_token.balance(&splitter_address), 45_000);
+
+    splitter.sweep_buy_commission(&payment_token_address);
+    assert_eq!(payment_token.balance(&recipient), 45_000);
+    assert_eq!(payment_token.balance(&splitter_address), 0);
+    assert_eq!(splitter.get_accrued_buy_commission(&payment_token_address), 0);
+
+    // Sweeping again does nothing
+    splitter.sweep_buy_commission(&payment_token_address);
+    assert_eq!(payment_token.balance(&recipient), 45_000);
+}
+
+#[test]
+fn buy_commission_is_transferred_by_default() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    let recipient = setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    splitter.list_shares_for_sale(&seller, &1000, &1000, &payment_token_address);
+    splitter.buy_shares(&buyer, &seller, &1000);
+
+    assert_eq!(payment_token.balance(&recipient), 15_000);
+    assert_eq!(splitter.get_accrued_buy_commission(&payment_token_address), 0);
+}
+
+#[test]
+fn reading_default_commission_config_does_not_persist_it() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_default_shares(&env, &admin);
+
+    let config = splitter.get_commission_config();
+    assert_eq!(config.buy_rate_bps, 150);
+    assert_eq!(config.distribution_rate_bps, 50);
+
+    let has_commission = || {
+        env.as_contract(&splitter_address, || {
+            env.storage().instance().has(&DataKey::Commission)
+        })
+    };
+    assert!(!has_commission());
+
+    // The setters persist the config
+    splitter.set_commission_recipient(&Address::generate(&env));
+    assert!(has_commission());
+    env.as_contract(&splitter_address, || {
+        assert_eq!(CommissionConfig::get(&env).buy_rate_bps, 150);
+    });
+}
+
+#[test]
+fn exempt_sales_pay_no_buy_commission() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let founder_1 = Address::generate(&env);
+    let founder_2 = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: founder_1.clone(),
+                share: 5000,
+            },
+            ShareDataKey {
+                shareholder: founder_2.clone(),
+                share: 5000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    let recipient = setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&founder_1, &0);
+    payment_sudo_token.mint(&founder_2, &1_000_000);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    assert!(!splitter.is_buy_commission_exempt(&founder_1));
+    splitter.set_buy_commission_exempt(&founder_1, &true);
+    assert!(splitter.is_buy_commission_exempt(&founder_1));
+
+    // Exempt seller: the full 100_000 goes to the seller
+    splitter.list_shares_for_sale(&founder_1, &2000, &100, &payment_token_address);
+    splitter.buy_shares(&founder_2, &founder_1, &1000);
+    assert_eq!(payment_token.balance(&founder_1), 100_000);
+    assert_eq!(payment_token.balance(&recipient), 0);
+
+    // Non-exempt seller and buyer: 1.5% commission
+    splitter.set_buy_commission_exempt(&founder_1, &false);
+    assert!(!splitter.is_buy_commission_exempt(&founder_1));
+    splitter.buy_shares(&buyer, &founder_1, &1000);
+    assert_eq!(payment_token.balance(&founder_1), 100_000 + 98_500);
+    assert_eq!(payment_token.balance(&recipient), 1_500);
+}
+
+#[test]
+fn test_buy_commission_exempt_unauthorized() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_default_shares(&env, &admin);
+
+    assert!(splitter
+        .try_set_buy_commission_exempt(&Address::generate(&env), &true)
+        .is_err());
+}
+
+#[test]
+fn set_recipient_rejects_contract_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_default_shares(&env, &admin);
+
+    assert_eq!(
+        splitter.try_set_commission_recipient(&splitter_address),
+        Err(Ok(Error::InvalidRecipient))
+    );
+}
+
+#[test]
+fn self_recipient_commission_stays_unused() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_default_shares(&env, &admin);
+
+    // A recipient set to the contract before the guard existed
+    env.as_contract(&splitter_address, || {
+        env.storage().instance().set(
+            &DataKey::Commission,
+            &CommissionConfig {
+                recipient: splitter_address.clone(),
+                buy_rate_bps: 150,
+                distribution_rate_bps: 50,
+            },
+        );
+    });
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    sudo_token.mint(&splitter_address, &1_000_000);
+
+    splitter.distribute_tokens(&token_address);
+
+    // The 0.5% commission is left in the contract as unused balance
+    assert_eq!(token.balance(&splitter_address), 1_000_000);
+    assert_eq!(splitter.get_unused_balance(&token_address), 5_000);
+    assert!(splitter.verify_integrity());
+}
+
+#[test]
+fn commission_summary_by_source() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+    assert_eq!(splitter.get_commission_summary(&token_address), (0, 0, 0));
+
+    // 1.5% of the 100_000 purchase
+    sudo_token.mint(&buyer, &100_000);
+    splitter.list_shares_for_sale(&seller, &1000, &100, &token_address);
+    splitter.buy_shares(&buyer, &seller, &1000);
+
+    // 0.5% of the 1_000_000 distribution
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    assert_eq!(
+        splitter.get_commission_summary(&token_address),
+        (1_500, 5_000, 6_500)
+    );
+}
+
+#[test]
+fn recipient_buys_without_commission_by_default() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    let recipient = setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+    sudo_token.mint(&recipient, &100_000);
+
+    splitter.list_shares_for_sale(&seller, &1000, &100, &token_address);
+    splitter.buy_shares(&recipient, &seller, &1000);
+
+    // The seller gets the full price
+    assert_eq!(token.balance(&seller), 100_000);
+    assert_eq!(token.balance(&recipient), 0);
+    assert_eq!(splitter.get_commission_summary(&token_address), (0, 0, 0));
+}
+
+#[test]
+fn recipient_pays_commission_to_treasury_when_enabled() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    let recipient = setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+    sudo_token.mint(&recipient, &100_000);
+    sudo_token.mint(&treasury, &0);
+
+    splitter.set_recipient_pays_commission(&true);
+    splitter.set_treasury(&treasury);
+
+    splitter.list_shares_for_sale(&seller, &1000, &100, &token_address);
+    splitter.buy_shares(&recipient, &seller, &1000);
+
+    // 1.5% goes to the treasury, the recipient doesn't earn its own commission
+    assert_eq!(token.balance(&seller), 98_500);
+    assert_eq!(token.balance(&treasury), 1_500);
+    assert_eq!(token.balance(&recipient), 0);
+    assert_eq!(splitter.get_commission_summary(&token_address), (0, 0, 0));
+}
+
+#[test]
+fn self_recipient_buy_commission_stays_unused_in_every_purchase_path() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    // A recipient set to the contract before the guard existed
+    env.as_contract(&splitter_address, || {
+        env.storage().instance().set(
+            &DataKey::Commission,
+            &CommissionConfig {
+                recipient: splitter_address.clone(),
+                buy_rate_bps: 150,
+                distribution_rate_bps: 50,
+            },
+        );
+    });
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    sudo_token.mint(&buyer, &300_000);
+    splitter.set_defer_buy_commission(&true);
+
+    // A direct buy, an escrowed purchase and a pool fill, each paying 1_500 commission
+    splitter.list_shares_for_sale(&seller, &2000, &100, &token_address);
+    splitter.buy_shares(&buyer, &seller, &1000);
+    splitter.deposit_for_purchase(&buyer, &seller, &1000);
+    splitter.release_purchase(&seller, &buyer);
+    splitter.contribute_to_pool(&seller, &token_address, &100, &1000);
+    splitter.buy_from_pool(&buyer, &token_address, &100, &1000);
+
+    // The commission is left as unused balance, not accrued for sweeping
+    assert_eq!(token.balance(&splitter_address), 4_500);
+    assert_eq!(splitter.get_accrued_buy_commission(&token_address), 0);
+    assert_eq!(splitter.get_unused_balance(&token_address), 4_500);
+    assert_eq!(splitter.get_commission_summary(&token_address), (0, 0, 0));
+    assert!(splitter.verify_integrity());
+}
+
+#[test]
+fn recipient_pays_commission_in_every_purchase_path() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    let recipient = setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+    sudo_token.mint(&recipient, &300_000);
+
+    splitter.set_recipient_pays_commission(&true);
+
+    // A direct buy, an escrowed purchase and a pool fill, each paying 1_500 commission
+    splitter.list_shares_for_sale(&seller, &2000, &100, &token_address);
+    splitter.buy_shares(&recipient, &seller, &1000);
+    splitter.deposit_for_purchase(&recipient, &seller, &1000);
+    splitter.release_purchase(&seller, &recipient);
+    splitter.contribute_to_pool(&seller, &token_address, &100, &1000);
+    splitter.buy_from_pool(&recipient, &token_address, &100, &1000);
+
+    // Without a treasury the commission stays in the contract as unused balance
+    assert_eq!(token.balance(&seller), 98_500 * 3);
+    assert_eq!(token.balance(&recipient), 0);
+    assert_eq!(token.balance(&splitter_address), 4_500);
+    assert_eq!(splitter.get_unused_balance(&token_address), 4_500);
+    assert_eq!(splitter.get_commission_summary(&token_address), (0, 0, 0));
+    assert!(splitter.verify_integrity());
+}
+
+#[test]
+fn test_set_recipient_pays_commission_not_initialized() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (splitter, _) = create_splitter(&env);
+
+    assert_eq!(
+        splitter.try_set_recipient_pays_commission(&true),
+        Err(Ok(Error::NotInitialized))
+    );
+}
@@ -1,9 +1,16 @@
-use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke},
+    vec, Address, Env, IntoVal, Symbol, Vec,
+};
 
 use crate::{
     errors::Error,
     storage::ShareDataKey,
-    tests::helpers::{create_splitter, create_splitter_with_shares, create_token, setup_test_commission_recipient},
+    tests::helpers::{
+        create_splitter, create_splitter_with_shares, create_token, get_default_share_data,
+        get_events_by_name, setup_test_commission_recipient,
+    },
 };
 
 #[test]
@@ -386,3 +393,1107 @@ fn test_complex_distribution_scenario() {
     let actual_balance = token_client.balance(&splitter_address);
     assert_eq!(total_allocated, actual_balance);
 }
+
+/// Test that commission plus allocations always reconcile exactly with the distributable amount
+#[test]
+fn test_exact_reconciliation_across_share_configurations() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let configurations: [&[i128]; 4] = [
+        &[10000],
+        &[5000, 5000],
+        &[3333, 3333, 3334],
+        &[1, 999, 4500, 4500],
+    ];
+    let deposits: [i128; 3] = [7, 999_999, 1_000_000_007];
+
+    for configuration in configurations.iter() {
+        for deposit in deposits.iter() {
+            let admin = Address::generate(&env);
+            let mut shares = Vec::new(&env);
+            for share in configuration.iter() {
+                shares.push_back(ShareDataKey {
+                    shareholder: Address::generate(&env),
+                    share: *share,
+                });
+            }
+
+            let (splitter, splitter_address) =
+                create_splitter_with_shares(&env, &admin, &shares, &true);
+
+            let token_admin = Address::generate(&env);
+            let (token_client, sudo_token, token_address) = create_token(&env, &token_admin);
+            let commission_recipient =
+                setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+            sudo_token.mint(&splitter_address, deposit);
+            splitter.distribute_tokens(&token_address);
+
+            let mut total_allocated = 0;
+            for share in shares.iter() {
+                total_allocated += splitter.get_allocation(&share.shareholder, &token_address);
+            }
+            let commission = token_client.balance(&commission_recipient);
+
+            assert_eq!(commission + total_allocated, *deposit);
+            assert_eq!(token_client.balance(&splitter_address), total_allocated);
+        }
+    }
+}
+
+#[test]
+fn test_no_shareholders() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (token_client, sudo_token, token_address) = create_token(&env, &token_admin);
+    let commission_recipient = setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    // Simulate a corrupted state with an empty shareholder set
+    env.as_contract(&splitter_address, || {
+        ShareDataKey::remove_shareholders(&env);
+    });
+
+    sudo_token.mint(&splitter_address, &1_000_000_000);
+
+    assert_eq!(
+        splitter.try_distribute_tokens(&token_address),
+        Err(Ok(Error::NoShareholders))
+    );
+
+    // No commission was taken
+    assert_eq!(token_client.balance(&commission_recipient), 0);
+    assert_eq!(token_client.balance(&splitter_address), 1_000_000_000);
+}
+
+/// Test that dust goes to the treasury when no shareholder qualifies for it
+#[test]
+fn test_dust_treasury_fallback() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder_1 = Address::generate(&env);
+    let shareholder_2 = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder_1.clone(),
+                share: 5000,
+            },
+            ShareDataKey {
+                shareholder: shareholder_2.clone(),
+                share: 5000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (token_client, sudo_token, token_address) = create_token(&env, &token_admin);
+    let commission_recipient = setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    // Degenerate share set where every shareholder has zero shares
+    env.as_contract(&splitter_address, || {
+        ShareDataKey::save_share(&env, shareholder_1.clone(), 0);
+        ShareDataKey::save_share(&env, shareholder_2.clone(), 0);
+    });
+
+    // Without a treasury the dust goes to the commission recipient
+    // 1_000_000 - 0.5% = 995_000 of dust
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(token_client.balance(&commission_recipient), 1_000_000);
+    assert_eq!(token_client.balance(&splitter_address), 0);
+
+    // With a treasury the dust goes there
+    let treasury = Address::generate(&env);
+    splitter.set_treasury(&treasury);
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(token_client.balance(&treasury), 995_000);
+    assert_eq!(token_client.balance(&commission_recipient), 1_005_000);
+
+    assert_eq!(splitter.get_allocation(&shareholder_1, &token_address), 0);
+    assert_eq!(splitter.get_allocation(&shareholder_2, &token_address), 0);
+}
+
+#[test]
+fn test_distribute_partial_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder_1 = Address::generate(&env);
+    let shareholder_2 = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder_1.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: shareholder_2.clone(),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    sudo_token.mint(&splitter_address, &1_000_000);
+
+    // After 0.5% commission: 400_000 - 2_000 = 398_000 to distribute
+    // shareholder_1: 398_000 * 8050 / 10000 = 320_390
+    // shareholder_2: 398_000 * 1950 / 10000 = 77_610
+    splitter.distribute_amount(&token_address, &400_000);
+
+    assert_eq!(splitter.get_allocation(&shareholder_1, &token_address), 320_390);
+    assert_eq!(splitter.get_allocation(&shareholder_2, &token_address), 77_610);
+    assert_eq!(token.balance(&splitter_address), 998_000);
+
+    // The remaining 600_000 is still unused
+    let recipient = Address::generate(&env);
+    assert_eq!(
+        splitter.try_distribute_amount(&token_address, &600_001),
+        Err(Ok(Error::TransferAmountAboveUnusedBalance))
+    );
+    splitter.transfer_tokens(&token_address, &recipient, &600_000);
+    assert_eq!(token.balance(&recipient), 600_000);
+    assert_eq!(token.balance(&splitter_address), 398_000);
+}
+
+#[test]
+fn test_distribute_invalid_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    sudo_token.mint(&splitter_address, &1_000);
+
+    assert_eq!(
+        splitter.try_distribute_amount(&token_address, &0),
+        Err(Ok(Error::ZeroTransferAmount))
+    );
+    assert_eq!(
+        splitter.try_distribute_amount(&token_address, &1_001),
+        Err(Ok(Error::TransferAmountAboveUnusedBalance))
+    );
+}
+
+#[test]
+fn test_distribution_summary_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    let events = get_events_by_name(&env, &splitter_address, symbol_short!("dist_v2"));
+    assert_eq!(events.len(), 1);
+
+    let (topics, data) = events.get(0).unwrap();
+    let token: Address = topics.get(1).unwrap().into_val(&env);
+    let (distributable, shareholders_count, commission): (i128, u32, i128) = data.into_val(&env);
+    assert_eq!(token, token_address);
+    assert_eq!(distributable, 1_000_000);
+    assert_eq!(shareholders_count, 2);
+    assert_eq!(commission, 5_000);
+}
+
+#[test]
+fn test_zero_share_shareholder_is_skipped() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder = Address::generate(&env);
+    let zero_shareholder = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: zero_shareholder.clone(),
+                share: 5000,
+            },
+            ShareDataKey {
+                shareholder: shareholder.clone(),
+                share: 5000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    // Inject a zero-share entry
+    env.as_contract(&splitter_address, || {
+        ShareDataKey::save_share(&env, zero_shareholder.clone(), 0);
+    });
+
+    // 1_000_003 - 0.5% = 995_003, 995_003 * 5000 / 10000 = 497_501 plus 497_502 of dust
+    sudo_token.mint(&splitter_address, &1_000_003);
+    splitter.distribute_tokens(&token_address);
+
+    assert_eq!(splitter.get_allocation(&zero_shareholder, &token_address), 0);
+    assert_eq!(splitter.get_allocation(&shareholder, &token_address), 995_003);
+
+    // The zero-share entry gets neither a distribution nor a dust event
+    for name in [symbol_short!("distrib"), symbol_short!("dust")] {
+        for (topics, _) in get_events_by_name(&env, &splitter_address, name).iter() {
+            let recipient: Address = topics.get(1).unwrap().into_val(&env);
+            assert_ne!(recipient, zero_shareholder);
+        }
+    }
+}
+
+#[test]
+fn test_distribution_only_needs_admin_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder_1 = Address::generate(&env);
+    let shareholder_2 = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder_1.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: shareholder_2.clone(),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    let commission_recipient = setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+    sudo_token.mint(&splitter_address, &1_000_000_000);
+
+    // Without any authorization the distribution is rejected
+    env.mock_auths(&[]);
+    assert!(splitter.try_distribute_tokens(&token_address).is_err());
+
+    // Only the admin authorizes the distribution, the commission transfer is
+    // authorized by the contract itself as the token source
+    env.mock_auths(&[MockAuth {
+        address: &admin,
+        invoke: &MockAuthInvoke {
+            contract: &splitter_address,
+            fn_name: "distribute_tokens",
+            args: (&token_address,).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    splitter.distribute_tokens(&token_address);
+
+    assert_eq!(token.balance(&commission_recipient), 5_000_000);
+    assert_eq!(splitter.get_allocation(&shareholder_1, &token_address), 800_975_000);
+    assert_eq!(splitter.get_allocation(&shareholder_2, &token_address), 194_025_000);
+}
+
+#[test]
+fn test_allocation_cap_keeps_overflow_unused() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let whale = Address::generate(&env);
+    let shareholder = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: whale.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: shareholder.clone(),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    assert_eq!(
+        splitter.try_set_max_allocation_per_dist(&-1),
+        Err(Ok(Error::InvalidAllocationCap))
+    );
+    splitter.set_max_allocation_per_dist(&500_000);
+
+    // 1_000_000 - 0.5% = 995_000 to distribute
+    // whale: 800_975 capped to 500_000, shareholder: 194_025
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(splitter.get_allocation(&whale, &token_address), 500_000);
+    assert_eq!(splitter.get_allocation(&shareholder, &token_address), 194_025);
+
+    // The 300_975 above the cap is not redistributed, it stays unused
+    assert_eq!(splitter.get_unused_balance(&token_address), 300_975);
+
+    // Without the cap the overflow is distributed like any other unused balance
+    // 300_975 - 0.5% = 299_471, whale: 241_074 plus 1 of dust, shareholder: 58_396
+    splitter.set_max_allocation_per_dist(&0);
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(splitter.get_allocation(&whale, &token_address), 500_000 + 241_075);
+    assert_eq!(splitter.get_allocation(&shareholder, &token_address), 194_025 + 58_396);
+    assert_eq!(splitter.get_unused_balance(&token_address), 0);
+}
+
+#[test]
+fn test_list_shares_with_allocation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder_1 = Address::generate(&env);
+    let shareholder_2 = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder_1.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: shareholder_2.clone(),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    let joined = splitter.list_shares_with_allocation(&token_address);
+    assert_eq!(joined.len(), 2);
+    for (shareholder, share, allocation) in joined.iter() {
+        assert_eq!(Some(share), splitter.get_share(&shareholder));
+        assert_eq!(allocation, splitter.get_allocation(&shareholder, &token_address));
+    }
+    assert_eq!(joined.get(0).unwrap(), (shareholder_1, 8050, 800_975));
+    assert_eq!(joined.get(1).unwrap(), (shareholder_2, 1950, 194_025));
+}
+
+#[test]
+fn test_list_allocations() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder_1 = Address::generate(&env);
+    let shareholder_2 = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder_1.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: shareholder_2.clone(),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    let allocations = splitter.list_allocations(&token_address);
+    assert_eq!(allocations.len(), 2);
+    for (shareholder, allocation) in allocations.iter() {
+        assert_eq!(allocation, splitter.get_allocation(&shareholder, &token_address));
+    }
+
+    // Shareholders without an allocation are left out
+    splitter.withdraw_allocation(&token_address, &shareholder_1, &800_975);
+    let allocations = splitter.list_allocations(&token_address);
+    assert_eq!(allocations, vec![&env, (shareholder_2, 194_025)]);
+}
+
+#[test]
+fn test_single_shareholder_gets_everything() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    // 1_234_567 - 0.5% (6_172) = 1_228_395, all of it to the only shareholder
+    sudo_token.mint(&splitter_address, &1_234_567);
+    splitter.distribute_tokens(&token_address);
+
+    assert_eq!(splitter.get_allocation(&shareholder, &token_address), 1_228_395);
+    assert_eq!(token.balance(&splitter_address), 1_228_395);
+    assert_eq!(splitter.get_unused_balance(&token_address), 0);
+
+    // No rounding dust with a single shareholder
+    assert_eq!(get_events_by_name(&env, &splitter_address, symbol_short!("dust")).len(), 0);
+}
+
+#[test]
+fn test_dust_tie_break_smallest_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let address_1 = Address::generate(&env);
+    let address_2 = Address::generate(&env);
+    let (smaller, larger) = if address_1 < address_2 {
+        (address_1, address_2)
+    } else {
+        (address_2, address_1)
+    };
+
+    // The larger address comes first, so the vector order alone would pick it
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: larger.clone(),
+                share: 5000,
+            },
+            ShareDataKey {
+                shareholder: smaller.clone(),
+                share: 5000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    // 1_000 - 0.5% (5) = 995, 497 each and 1 of dust
+    sudo_token.mint(&splitter_address, &1_000);
+    splitter.distribute_tokens(&token_address);
+
+    assert_eq!(splitter.get_allocation(&smaller, &token_address), 498);
+    assert_eq!(splitter.get_allocation(&larger, &token_address), 497);
+
+    // Same result on the next distribution
+    sudo_token.mint(&splitter_address, &1_000);
+    splitter.distribute_tokens(&token_address);
+
+    assert_eq!(splitter.get_allocation(&smaller, &token_address), 996);
+    assert_eq!(splitter.get_allocation(&larger, &token_address), 994);
+}
+
+#[test]
+fn test_reinvest_bps() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shares = get_default_share_data(&env);
+    let shareholder_1 = shares.get(0).unwrap().shareholder;
+    let shareholder_2 = shares.get(1).unwrap().shareholder;
+
+    let (splitter, splitter_address) = create_splitter_with_shares(&env, &admin, &shares, &true);
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    assert_eq!(
+        splitter.try_set_reinvest_bps(&10001),
+        Err(Ok(Error::InvalidReinvestShare))
+    );
+    assert_eq!(
+        splitter.try_set_reinvest_bps(&-1),
+        Err(Ok(Error::InvalidReinvestShare))
+    );
+    splitter.set_reinvest_bps(&1000);
+
+    // 1_000_000 - 0.5% (5_000) = 995_000, 10% (99_500) is kept for reinvestment
+    // shareholder_1: 895_500 * 8050 / 10000 = 720_877 plus 1 of dust
+    // shareholder_2: 895_500 * 1950 / 10000 = 174_622
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    assert_eq!(splitter.get_allocation(&shareholder_1, &token_address), 720_878);
+    assert_eq!(splitter.get_allocation(&shareholder_2, &token_address), 174_622);
+    assert_eq!(splitter.get_reinvest_pool(&token_address), 99_500);
+
+    // The pool is not unused balance, so it is not distributed again
+    assert_eq!(splitter.get_unused_balance(&token_address), 0);
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(splitter.get_allocation(&shareholder_1, &token_address), 720_878);
+    assert_eq!(token.balance(&splitter_address), 720_878 + 174_622 + 99_500);
+
+    // The pool grows with every distribution
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(splitter.get_reinvest_pool(&token_address), 199_000);
+
+    // The pool can only be withdrawn by the admin, up to what it holds
+    let reinvestment = Address::generate(&env);
+    assert_eq!(
+        splitter.try_withdraw_reinvest_pool(&token_address, &reinvestment, &199_001),
+        Err(Ok(Error::TransferAmountAboveBalance))
+    );
+    splitter.withdraw_reinvest_pool(&token_address, &reinvestment, &150_000);
+    assert_eq!(token.balance(&reinvestment), 150_000);
+    assert_eq!(splitter.get_reinvest_pool(&token_address), 49_000);
+
+    // Withdrawing the pool leaves the allocations and the unused balance alone
+    assert_eq!(splitter.get_unused_balance(&token_address), 0);
+    splitter.withdraw_reinvest_pool(&token_address, &reinvestment, &49_000);
+    assert_eq!(splitter.get_reinvest_pool(&token_address), 0);
+    assert_eq!(token.balance(&reinvestment), 199_000);
+}
+
+#[test]
+fn test_suppress_per_shareholder_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &get_default_share_data(&env), &true);
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    splitter.set_emit_per_shareholder_events(&false);
+
+    // 1_000 leaves 1 of dust after the commission
+    sudo_token.mint(&splitter_address, &1_000);
+    splitter.distribute_tokens(&token_address);
+
+    assert_eq!(get_events_by_name(&env, &splitter_address, symbol_short!("distrib")).len(), 0);
+    assert_eq!(get_events_by_name(&env, &splitter_address, symbol_short!("dist_all")).len(), 1);
+    assert_eq!(get_events_by_name(&env, &splitter_address, symbol_short!("dist_com")).len(), 1);
+    assert_eq!(get_events_by_name(&env, &splitter_address, symbol_short!("dust")).len(), 1);
+}
+
+#[test]
+fn test_zero_distribution_rate() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shares = get_default_share_data(&env);
+    let shareholder_1 = shares.get(0).unwrap().shareholder;
+    let shareholder_2 = shares.get(1).unwrap().shareholder;
+
+    let (splitter, splitter_address) = create_splitter_with_shares(&env, &admin, &shares, &true);
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    let commission_recipient = setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    splitter.set_distribution_commission_rate(&0);
+
+    // Everything is allocated, 805_000 and 195_000
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    assert_eq!(get_events_by_name(&env, &splitter_address, symbol_short!("dist_com")).len(), 0);
+    assert_eq!(token.balance(&commission_recipient), 0);
+    assert_eq!(splitter.get_allocation(&shareholder_1, &token_address), 805_000);
+    assert_eq!(splitter.get_allocation(&shareholder_2, &token_address), 195_000);
+    assert_eq!(splitter.get_unused_balance(&token_address), 0);
+}
+
+#[test]
+fn test_no_op_distribution_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &get_default_share_data(&env), &true);
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(
+        get_events_by_name(&env, &splitter_address, Symbol::new(&env, "no_distrib")).len(),
+        0
+    );
+
+    // Nothing new was deposited, so the second run only signals the no-op
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(
+        get_events_by_name(&env, &splitter_address, Symbol::new(&env, "no_distrib")).len(),
+        1
+    );
+    assert_eq!(get_events_by_name(&env, &splitter_address, symbol_short!("dist_all")).len(), 0);
+}
+
+#[test]
+fn test_distribute_with_custom_share_denominator() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder_1 = Address::generate(&env);
+    let shareholder_2 = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter(&env);
+
+    // The shares must sum up to the custom denominator, not 10000
+    assert_eq!(
+        splitter.try_init_with_share_denominator(
+            &admin,
+            &vec![
+                &env,
+                ShareDataKey {
+                    shareholder: shareholder_1.clone(),
+                    share: 8050,
+                },
+                ShareDataKey {
+                    shareholder: shareholder_2.clone(),
+                    share: 1950,
+                },
+            ],
+            &true,
+            &1_000_000,
+        ),
+        Err(Ok(Error::InvalidShareTotal))
+    );
+
+    splitter.init_with_share_denominator(
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder_1.clone(),
+                share: 333_333,
+            },
+            ShareDataKey {
+                shareholder: shareholder_2.clone(),
+                share: 666_667,
+            },
+        ],
+        &true,
+        &1_000_000,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    sudo_token.mint(&splitter_address, &1_000_000_000);
+
+    splitter.distribute_tokens(&token_address);
+
+    // The commission is still 0.5%: 995_000_000 to distribute
+    // shareholder_1: 995_000_000 * 333_333 / 1_000_000 = 331_666_335
+    // shareholder_2: 995_000_000 * 666_667 / 1_000_000 = 663_333_665
+    assert_eq!(
+        splitter.get_allocation(&shareholder_1, &token_address),
+        331_666_335
+    );
+    assert_eq!(
+        splitter.get_allocation(&shareholder_2, &token_address),
+        663_333_665
+    );
+    assert!(splitter.verify_integrity());
+}
+
+#[test]
+fn test_distribute_to_subset() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder_1 = Address::generate(&env);
+    let shareholder_2 = Address::generate(&env);
+    let shareholder_3 = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder_1.clone(),
+                share: 5000,
+            },
+            ShareDataKey {
+                shareholder: shareholder_2.clone(),
+                share: 3000,
+            },
+            ShareDataKey {
+                shareholder: shareholder_3.clone(),
+                share: 2000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    sudo_token.mint(&splitter_address, &1_000_000);
+
+    // Non-shareholders, empty subsets and duplicates are rejected
+    assert_eq!(
+        splitter.try_distribute_to_subset(
+            &token_address,
+            &vec![&env, shareholder_1.clone(), Address::generate(&env)],
+            &400_000
+        ),
+        Err(Ok(Error::NotAShareholder))
+    );
+    assert_eq!(
+        splitter.try_distribute_to_subset(&token_address, &vec![&env], &400_000),
+        Err(Ok(Error::NoShareholders))
+    );
+    assert_eq!(
+        splitter.try_distribute_to_subset(
+            &token_address,
+            &vec![&env, shareholder_1.clone(), shareholder_1.clone()],
+            &400_000
+        ),
+        Err(Ok(Error::DuplicateShareholder))
+    );
+
+    // After 0.5% commission: 400_000 - 2_000 = 398_000 to distribute within the subset
+    // shareholder_1: 398_000 * 5000 / 8000 = 248_750
+    // shareholder_2: 398_000 * 3000 / 8000 = 149_250
+    splitter.distribute_to_subset(
+        &token_address,
+        &vec![&env, shareholder_1.clone(), shareholder_2.clone()],
+        &400_000,
+    );
+
+    assert_eq!(splitter.get_allocation(&shareholder_1, &token_address), 248_750);
+    assert_eq!(splitter.get_allocation(&shareholder_2, &token_address), 149_250);
+    assert_eq!(splitter.get_allocation(&shareholder_3, &token_address), 0);
+    assert!(splitter.verify_integrity());
+}
+
+#[test]
+fn test_distribution_cooldown() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &get_default_share_data(&env), &true);
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    splitter.set_distribution_cooldown(&token_address, &50);
+
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    // Within the cooldown window
+    sudo_token.mint(&splitter_address, &1_000_000);
+    env.ledger().set_sequence_number(149);
+    assert_eq!(
+        splitter.try_distribute_tokens(&token_address),
+        Err(Ok(Error::DistributionCooldownActive))
+    );
+
+    // Outside of it
+    env.ledger().set_sequence_number(150);
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(splitter.get_unused_balance(&token_address), 0);
+
+    // 0 disables the cooldown
+    splitter.set_distribution_cooldown(&token_address, &0);
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(splitter.get_unused_balance(&token_address), 0);
+}
+
+#[test]
+fn time_weighted_distribution_rewards_holding_over_the_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(0);
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let shares = vec![
+        &env,
+        ShareDataKey {
+            shareholder: seller.clone(),
+            share: 5000,
+        },
+        ShareDataKey {
+            shareholder: buyer.clone(),
+            share: 5000,
+        },
+    ];
+    let (snapshot_splitter, snapshot_address) =
+        create_splitter_with_shares(&env, &admin, &shares, &true);
+    let (weighted_splitter, weighted_address) =
+        create_splitter_with_shares(&env, &admin, &shares, &true);
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &snapshot_splitter, &[&sudo_token]);
+    setup_test_commission_recipient(&env, &weighted_splitter, &[&sudo_token]);
+
+    // Halfway through the period the seller moves most of their shares to the buyer
+    env.ledger().set_sequence_number(100);
+    snapshot_splitter.transfer_shares(&seller, &buyer, &4000);
+    weighted_splitter.transfer_shares(&seller, &buyer, &4000);
+
+    env.ledger().set_sequence_number(200);
+    sudo_token.mint(&snapshot_address, &1_000_000);
+    sudo_token.mint(&weighted_address, &1_000_000);
+    snapshot_splitter.distribute_tokens(&token_address);
+    weighted_splitter.distribute_time_weighted(&token_address);
+
+    // The snapshot only sees the 1000 / 9000 split at distribution time
+    assert_eq!(snapshot_splitter.get_allocation(&seller, &token_address), 99_500);
+    assert_eq!(snapshot_splitter.get_allocation(&buyer, &token_address), 895_500);
+
+    // Weighted by share * ledgers: 5000 * 100 + 1000 * 100 vs 5000 * 100 + 9000 * 100
+    assert_eq!(weighted_splitter.get_allocation(&seller, &token_address), 298_500);
+    assert_eq!(weighted_splitter.get_allocation(&buyer, &token_address), 696_500);
+
+    // The weights were reset, the next period only counts the current shares
+    env.ledger().set_sequence_number(300);
+    sudo_token.mint(&weighted_address, &1_000_000);
+    weighted_splitter.distribute_time_weighted(&token_address);
+    assert_eq!(
+        weighted_splitter.get_allocation(&seller, &token_address),
+        298_500 + 99_500
+    );
+    assert_eq!(
+        weighted_splitter.get_allocation(&buyer, &token_address),
+        696_500 + 895_500
+    );
+}
+
+#[test]
+fn time_weighted_distribution_needs_elapsed_ledgers() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &get_default_share_data(&env), &true);
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+    sudo_token.mint(&splitter_address, &1_000_000);
+
+    // Shares saved at initialization only weigh from the initialization ledger on
+    assert_eq!(
+        splitter.try_distribute_time_weighted(&token_address),
+        Err(Ok(Error::NoShareholders))
+    );
+}
+
+#[test]
+fn opted_out_shareholder_portion_goes_to_the_others() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder_1 = Address::generate(&env);
+    let shareholder_2 = Address::generate(&env);
+    let opted_out = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder_1.clone(),
+                share: 5000,
+            },
+            ShareDataKey {
+                shareholder: shareholder_2.clone(),
+                share: 3000,
+            },
+            ShareDataKey {
+                shareholder: opted_out.clone(),
+                share: 2000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    splitter.set_distribution_optout(&opted_out, &true);
+
+    // 995_000 after commission, split 5000 / 8000 and 3000 / 8000
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    assert_eq!(splitter.get_allocation(&shareholder_1, &token_address), 621_875);
+    assert_eq!(splitter.get_allocation(&shareholder_2, &token_address), 373_125);
+    assert_eq!(splitter.get_allocation(&opted_out, &token_address), 0);
+    assert_eq!(splitter.get_unused_balance(&token_address), 0);
+
+    // Opting back in restores the regular split
+    splitter.set_distribution_optout(&opted_out, &false);
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    assert_eq!(
+        splitter.get_allocation(&shareholder_1, &token_address),
+        621_875 + 497_500
+    );
+    assert_eq!(splitter.get_allocation(&opted_out, &token_address), 199_000);
+}
+
+#[test]
+fn test_distribute_with_every_shareholder_opted_out() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    sudo_token.mint(&splitter_address, &1_000_000);
+
+    for share in share_data.iter() {
+        splitter.set_distribution_optout(&share.shareholder, &true);
+    }
+
+    assert_eq!(
+        splitter.try_distribute_tokens(&token_address),
+        Err(Ok(Error::NoShareholders))
+    );
+}
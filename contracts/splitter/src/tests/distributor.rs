@@ -0,0 +1,165 @@
+use soroban_sdk::{
+    testutils::{Address as _, MockAuth, MockAuthInvoke},
+    vec, Address, Env, IntoVal,
+};
+
+use crate::{
+    errors::Error,
+    storage::ShareDataKey,
+    tests::helpers::{
+        create_splitter, create_splitter_with_shares, create_token, get_default_share_data,
+        setup_test_commission_recipient,
+    },
+};
+
+#[test]
+fn distributor_can_distribute() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let distributor = Address::generate(&env);
+    let shareholder_1 = Address::generate(&env);
+    let shareholder_2 = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder_1.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: shareholder_2.clone(),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+    sudo_token.mint(&splitter_address, &1_000_000_000);
+
+    splitter.set_distributor(&distributor, &true);
+
+    // Only the distributor authorizes the distribution
+    env.mock_auths(&[MockAuth {
+        address: &distributor,
+        invoke: &MockAuthInvoke {
+            contract: &splitter_address,
+            fn_name: "distribute_as",
+            args: (&distributor, &token_address).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    splitter.distribute_as(&distributor, &token_address);
+
+    // After 0.5% commission: 995_000_000 to distribute
+    assert_eq!(splitter.get_allocation(&shareholder_1, &token_address), 800_975_000);
+    assert_eq!(splitter.get_allocation(&shareholder_2, &token_address), 194_025_000);
+}
+
+#[test]
+fn distributor_cannot_call_admin_functions() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let distributor = Address::generate(&env);
+
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &get_default_share_data(&env), &true);
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    sudo_token.mint(&splitter_address, &1_000);
+
+    splitter.set_distributor(&distributor, &true);
+
+    let new_shares = vec![
+        &env,
+        ShareDataKey {
+            shareholder: distributor.clone(),
+            share: 10000,
+        },
+    ];
+    env.mock_auths(&[MockAuth {
+        address: &distributor,
+        invoke: &MockAuthInvoke {
+            contract: &splitter_address,
+            fn_name: "update_shares",
+            args: (&new_shares, false).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(splitter.try_update_shares(&new_shares, &false, &false).is_err());
+
+    env.mock_auths(&[MockAuth {
+        address: &distributor,
+        invoke: &MockAuthInvoke {
+            contract: &splitter_address,
+            fn_name: "transfer_tokens",
+            args: (&token_address, &distributor, 1_000_i128).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(splitter
+        .try_transfer_tokens(&token_address, &distributor, &1_000)
+        .is_err());
+}
+
+#[test]
+fn test_not_a_distributor() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let distributor = Address::generate(&env);
+
+    let (splitter, _) =
+        create_splitter_with_shares(&env, &admin, &get_default_share_data(&env), &true);
+
+    let token_admin = Address::generate(&env);
+    let (_, _, token_address) = create_token(&env, &token_admin);
+
+    assert_eq!(
+        splitter.try_distribute_as(&distributor, &token_address),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    // Disabled distributors lose access
+    splitter.set_distributor(&distributor, &true);
+    splitter.set_distributor(&distributor, &false);
+    assert_eq!(
+        splitter.try_distribute_as(&distributor, &token_address),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_set_distributor_not_initialized() {
+    let env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    assert_eq!(
+        splitter.try_set_distributor(&Address::generate(&env), &true),
+        Err(Ok(Error::NotInitialized))
+    );
+}
+
+#[test]
+fn test_set_distributor_unauthorized() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) =
+        create_splitter_with_shares(&env, &admin, &get_default_share_data(&env), &true);
+
+    assert!(splitter
+        .try_set_distributor(&Address::generate(&env), &true)
+        .is_err());
+}
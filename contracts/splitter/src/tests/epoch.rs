@@ -0,0 +1,229 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, vec, Address, Env,
+};
+
+use crate::{
+    contract::SplitterClient,
+    errors::Error,
+    storage::{CommissionConfig, DataKey, ShareDataKey},
+    tests::helpers::{create_splitter_with_shares, create_token, setup_test_commission_recipient},
+};
+
+fn setup<'a>(env: &'a Env) -> (SplitterClient<'a>, Address, Address, Address, Address) {
+    let admin = Address::generate(env);
+    let shareholder_1 = Address::generate(env);
+    let shareholder_2 = Address::generate(env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        env,
+        &admin,
+        &vec![
+            env,
+            ShareDataKey {
+                shareholder: shareholder_1.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: shareholder_2.clone(),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(env);
+    let (_, sudo_token, token_address) = create_token(env, &token_admin);
+    setup_test_commission_recipient(env, &splitter, &[&sudo_token]);
+    sudo_token.mint(&shareholder_1, &0);
+    sudo_token.mint(&shareholder_2, &0);
+    sudo_token.mint(&splitter_address, &1_000_000);
+
+    (splitter, splitter_address, token_address, shareholder_1, shareholder_2)
+}
+
+#[test]
+fn claim_partway_through_and_at_the_end() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let (splitter, _, token_address, shareholder_1, shareholder_2) = setup(&env);
+    let token = token::Client::new(&env, &token_address);
+
+    // 1_000_000 - 0.5% = 995_000 vests over 100 ledgers
+    splitter.start_epoch(&token_address, &1_000_000, &100);
+
+    // The commission counts as earned from distributions
+    assert_eq!(splitter.get_commission_summary(&token_address), (0, 5_000, 5_000));
+
+    // Halfway: 497_500 vested, shareholder_1 gets 497_500 * 8050 / 10000 = 400_487
+    env.ledger().set_sequence_number(150);
+    assert_eq!(splitter.claim_epoch(&shareholder_1, &token_address), 400_487);
+    assert_eq!(splitter.claim_epoch(&shareholder_1, &token_address), 0);
+    assert_eq!(token.balance(&shareholder_1), 400_487);
+
+    // A running epoch cannot be replaced
+    assert_eq!(
+        splitter.try_start_epoch(&token_address, &1, &100),
+        Err(Ok(Error::EpochActive))
+    );
+
+    // At the end everything has vested: 800_975 and 194_025
+    env.ledger().set_sequence_number(200);
+    assert_eq!(splitter.claim_epoch(&shareholder_1, &token_address), 400_488);
+    assert_eq!(splitter.claim_epoch(&shareholder_2, &token_address), 194_025);
+    assert_eq!(token.balance(&shareholder_1), 800_975);
+    assert_eq!(token.balance(&shareholder_2), 194_025);
+
+    // Vesting doesn't continue past the end
+    env.ledger().set_sequence_number(300);
+    assert_eq!(splitter.claim_epoch(&shareholder_1, &token_address), 0);
+}
+
+#[test]
+fn ended_epoch_is_settled_into_allocations() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let (splitter, splitter_address, token_address, shareholder_1, shareholder_2) = setup(&env);
+    let sudo_token = token::StellarAssetClient::new(&env, &token_address);
+
+    splitter.start_epoch(&token_address, &1_000_000, &100);
+
+    // The vesting amount is not unused balance anymore
+    assert_eq!(
+        splitter.try_distribute_amount(&token_address, &1),
+        Err(Ok(Error::TransferAmountAboveUnusedBalance))
+    );
+
+    // Only shareholder_2 claims before the next epoch starts
+    env.ledger().set_sequence_number(250);
+    assert_eq!(splitter.claim_epoch(&shareholder_2, &token_address), 194_025);
+
+    // Starting the next epoch moves the unclaimed 800_975 to shareholder_1's allocation
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.start_epoch(&token_address, &1_000_000, &100);
+    assert_eq!(splitter.get_allocation(&shareholder_1, &token_address), 800_975);
+    assert_eq!(splitter.get_allocation(&shareholder_2, &token_address), 0);
+
+    // Claims from the previous epoch don't count against the new one
+    env.ledger().set_sequence_number(350);
+    assert_eq!(splitter.claim_epoch(&shareholder_2, &token_address), 194_025);
+}
+
+#[test]
+fn test_invalid_epoch() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (splitter, _, token_address, _, _) = setup(&env);
+
+    assert_eq!(
+        splitter.try_start_epoch(&token_address, &0, &100),
+        Err(Ok(Error::ZeroTransferAmount))
+    );
+    assert_eq!(
+        splitter.try_start_epoch(&token_address, &1_000, &0),
+        Err(Ok(Error::InvalidEpochDuration))
+    );
+    assert_eq!(
+        splitter.try_start_epoch(&token_address, &1_000_001, &100),
+        Err(Ok(Error::TransferAmountAboveUnusedBalance))
+    );
+}
+
+#[test]
+fn moving_shares_during_an_epoch_does_not_claim_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let (splitter, splitter_address, token_address, shareholder_1, shareholder_2) = setup(&env);
+    let token = token::Client::new(&env, &token_address);
+    let fresh = Address::generate(&env);
+
+    splitter.start_epoch(&token_address, &1_000_000, &100);
+
+    // Claim halfway, then move every share to a fresh address
+    env.ledger().set_sequence_number(150);
+    assert_eq!(splitter.claim_epoch(&shareholder_1, &token_address), 400_487);
+    splitter.transfer_shares(&shareholder_1, &fresh, &8050);
+
+    // The epoch vests to the shares at its start, the fresh address gets nothing
+    env.ledger().set_sequence_number(200);
+    assert_eq!(splitter.claim_epoch(&fresh, &token_address), 0);
+    assert_eq!(splitter.claim_epoch(&shareholder_2, &token_address), 194_025);
+    assert_eq!(token.balance(&shareholder_2), 194_025);
+
+    // Settling allocates the rest to the original holder, not the fresh address
+    let token_admin_client = token::StellarAssetClient::new(&env, &token_address);
+    token_admin_client.mint(&splitter_address, &1_000_000);
+    splitter.start_epoch(&token_address, &1_000_000, &100);
+    assert_eq!(splitter.get_allocation(&shareholder_1, &token_address), 400_488);
+    assert_eq!(splitter.get_allocation(&fresh, &token_address), 0);
+}
+
+#[test]
+fn opted_out_shareholder_portion_vests_to_the_others() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let (splitter, _, token_address, shareholder_1, shareholder_2) = setup(&env);
+    let token = token::Client::new(&env, &token_address);
+
+    // The opt-out at the start counts for the whole epoch, even when opting back in
+    splitter.set_distribution_optout(&shareholder_2, &true);
+    splitter.start_epoch(&token_address, &1_000_000, &100);
+    splitter.set_distribution_optout(&shareholder_2, &false);
+
+    env.ledger().set_sequence_number(200);
+    assert_eq!(splitter.claim_epoch(&shareholder_2, &token_address), 0);
+    assert_eq!(splitter.claim_epoch(&shareholder_1, &token_address), 995_000);
+    assert_eq!(token.balance(&shareholder_1), 995_000);
+    assert_eq!(token.balance(&shareholder_2), 0);
+}
+
+#[test]
+fn start_epoch_with_every_shareholder_opted_out() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (splitter, _, token_address, shareholder_1, shareholder_2) = setup(&env);
+
+    splitter.set_distribution_optout(&shareholder_1, &true);
+    splitter.set_distribution_optout(&shareholder_2, &true);
+    assert_eq!(
+        splitter.try_start_epoch(&token_address, &1_000_000, &100),
+        Err(Ok(Error::NoShareholders))
+    );
+}
+
+#[test]
+fn self_recipient_epoch_commission_stays_unused() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (splitter, splitter_address, token_address, _, _) = setup(&env);
+    let token = token::Client::new(&env, &token_address);
+
+    // A recipient set to the contract before the guard existed
+    env.as_contract(&splitter_address, || {
+        env.storage().instance().set(
+            &DataKey::Commission,
+            &CommissionConfig {
+                recipient: splitter_address.clone(),
+                buy_rate_bps: 150,
+                distribution_rate_bps: 50,
+            },
+        );
+    });
+
+    // The 0.5% commission is left in the contract as unused balance
+    splitter.start_epoch(&token_address, &1_000_000, &100);
+    assert_eq!(token.balance(&splitter_address), 1_000_000);
+    assert_eq!(splitter.get_unused_balance(&token_address), 5_000);
+    assert_eq!(splitter.get_commission_summary(&token_address), (0, 0, 0));
+}
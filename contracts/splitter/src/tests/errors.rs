@@ -0,0 +1,20 @@
+extern crate std;
+
+use soroban_sdk::Error as SorobanError;
+
+use crate::errors::{spec, Error};
+
+#[test]
+fn spec_errors_match_contract_errors() {
+    for code in 1..=100 {
+        let error = SorobanError::from_contract_error(code);
+        let contract_error = Error::try_from(error).ok().map(|e| std::format!("{:?}", e));
+        let spec_error = match spec::Error::try_from(error) {
+            Ok(e) => Some(std::format!("{:?}", e)),
+            Err(_) => spec::ExtError::try_from(error)
+                .ok()
+                .map(|e| std::format!("{:?}", e)),
+        };
+        assert_eq!(contract_error, spec_error, "error code {}", code);
+    }
+}
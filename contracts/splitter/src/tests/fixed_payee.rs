@@ -0,0 +1,115 @@
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::ShareDataKey,
+    tests::helpers::{
+        create_splitter, create_splitter_with_default_shares, create_splitter_with_shares,
+        create_token, setup_test_commission_recipient,
+    },
+};
+
+#[test]
+fn distribution_with_and_without_fixed_payee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder_1 = Address::generate(&env);
+    let shareholder_2 = Address::generate(&env);
+    let payee = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder_1.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: shareholder_2.clone(),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+    sudo_token.mint(&payee, &0);
+
+    // Without a payee: 1_000_000 - 0.5% = 995_000 goes to the shareholders
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(splitter.get_allocation(&shareholder_1, &token_address), 800_975);
+    assert_eq!(splitter.get_allocation(&shareholder_2, &token_address), 194_025);
+
+    splitter.set_fixed_payee(&payee, &1000);
+    let fixed_payee = splitter.get_fixed_payee().unwrap();
+    assert_eq!(fixed_payee.address, payee);
+    assert_eq!(fixed_payee.bps, 1000);
+
+    // With a 10% payee: 995_000 * 10% = 99_500 to the payee, 895_500 to the shareholders
+    // shareholder_1: 895_500 * 8050 / 10000 = 720_877 plus 1 of dust
+    // shareholder_2: 895_500 * 1950 / 10000 = 174_622
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(token.balance(&payee), 99_500);
+    assert_eq!(splitter.get_allocation(&shareholder_1, &token_address), 800_975 + 720_878);
+    assert_eq!(splitter.get_allocation(&shareholder_2, &token_address), 194_025 + 174_622);
+
+    // Removing the payee restores the plain split
+    splitter.set_fixed_payee(&payee, &0);
+    assert_eq!(splitter.get_fixed_payee(), None);
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(token.balance(&payee), 99_500);
+    assert_eq!(
+        splitter.get_allocation(&shareholder_2, &token_address),
+        194_025 + 174_622 + 194_025
+    );
+}
+
+#[test]
+fn test_invalid_payee_share() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_default_shares(&env, &admin);
+
+    assert_eq!(
+        splitter.try_set_fixed_payee(&Address::generate(&env), &10001),
+        Err(Ok(Error::InvalidPayeeShare))
+    );
+    assert_eq!(
+        splitter.try_set_fixed_payee(&Address::generate(&env), &-1),
+        Err(Ok(Error::InvalidPayeeShare))
+    );
+}
+
+#[test]
+fn test_not_initialized() {
+    let env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    assert_eq!(
+        splitter.try_set_fixed_payee(&Address::generate(&env), &1000),
+        Err(Ok(Error::NotInitialized))
+    );
+}
+
+#[test]
+fn test_unauthorized() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_default_shares(&env, &admin);
+
+    assert!(splitter
+        .try_set_fixed_payee(&Address::generate(&env), &1000)
+        .is_err());
+}
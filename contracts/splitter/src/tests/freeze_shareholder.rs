@@ -0,0 +1,208 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env,
+};
+
+use crate::{
+    errors::Error,
+    storage::ShareDataKey,
+    tests::helpers::{
+        create_splitter, create_splitter_with_shares, create_token, get_default_share_data,
+        setup_test_commission_recipient,
+    },
+};
+
+#[test]
+fn frozen_shareholder_cannot_move_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let frozen = share_data.get(0).unwrap().shareholder;
+    let other = share_data.get(1).unwrap().shareholder;
+    let buyer = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+    sudo_token.mint(&frozen, &1_000_000);
+    sudo_token.mint(&buyer, &1_000_000);
+
+    // Listings opened before the freeze
+    splitter.list_shares_for_sale(&frozen, &1000, &100, &token_address);
+    splitter.list_shares_for_sale(&other, &1000, &100, &token_address);
+
+    splitter.freeze_shareholder(&frozen, &true);
+
+    assert_eq!(
+        splitter.try_transfer_shares(&frozen, &buyer, &1000),
+        Err(Ok(Error::ShareholderFrozen))
+    );
+    assert_eq!(
+        splitter.try_list_shares_for_sale(&frozen, &500, &100, &token_address),
+        Err(Ok(Error::ShareholderFrozen))
+    );
+    // Neither as the seller nor as the buyer
+    assert_eq!(
+        splitter.try_buy_shares(&buyer, &frozen, &1000),
+        Err(Ok(Error::ShareholderFrozen))
+    );
+    assert_eq!(
+        splitter.try_buy_shares(&frozen, &other, &1000),
+        Err(Ok(Error::ShareholderFrozen))
+    );
+
+    // Frozen addresses can still receive shares
+    splitter.transfer_shares(&other, &frozen, &50);
+    assert_eq!(splitter.get_share(&frozen), Some(8100));
+
+    // Unfreezing lifts the hold
+    splitter.freeze_shareholder(&frozen, &false);
+    splitter.buy_shares(&buyer, &frozen, &1000);
+    assert_eq!(splitter.get_share(&buyer), Some(1000));
+}
+
+#[test]
+fn frozen_shareholder_accrues_but_cannot_withdraw() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let frozen = share_data.get(0).unwrap().shareholder;
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+    sudo_token.mint(&splitter_address, &1_000_000);
+
+    splitter.freeze_shareholder(&frozen, &true);
+    splitter.distribute_tokens(&token_address);
+
+    // 80.5% of the 1_000_000 after the 0.5% commission
+    assert_eq!(splitter.get_allocation(&frozen, &token_address), 800_975);
+    assert_eq!(
+        splitter.try_withdraw_allocation(&token_address, &frozen, &800_975),
+        Err(Ok(Error::ShareholderFrozen))
+    );
+
+    splitter.freeze_shareholder(&frozen, &false);
+    splitter.withdraw_allocation(&token_address, &frozen, &800_975);
+    assert_eq!(token.balance(&frozen), 800_975);
+}
+
+#[test]
+fn frozen_shareholder_cannot_claim_epoch() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let frozen = share_data.get(0).unwrap().shareholder;
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+    sudo_token.mint(&splitter_address, &1_000_000);
+
+    splitter.start_epoch(&token_address, &1_000_000, &100);
+    splitter.freeze_shareholder(&frozen, &true);
+
+    env.ledger().set_sequence_number(150);
+    assert_eq!(
+        splitter.try_claim_epoch(&frozen, &token_address),
+        Err(Ok(Error::ShareholderFrozen))
+    );
+    assert_eq!(token.balance(&frozen), 0);
+
+    // The vested part waits for the shareholder to be unfrozen
+    splitter.freeze_shareholder(&frozen, &false);
+    assert!(splitter.claim_epoch(&frozen, &token_address) > 0);
+}
+
+#[test]
+fn test_freeze_shareholder_not_initialized() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (splitter, _) = create_splitter(&env);
+
+    assert_eq!(
+        splitter.try_freeze_shareholder(&Address::generate(&env), &true),
+        Err(Ok(Error::NotInitialized))
+    );
+}
+
+#[test]
+fn frozen_shareholder_is_not_paid_out_on_removal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let frozen = share_data.get(0).unwrap().shareholder;
+    let kept = share_data.get(1).unwrap().shareholder;
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    splitter.freeze_shareholder(&frozen, &true);
+    splitter.update_shares(
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: kept.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+        &false,
+    );
+
+    // The allocation is kept instead of being paid out
+    assert_eq!(token.balance(&frozen), 0);
+    assert_eq!(splitter.get_allocation(&frozen, &token_address), 800_975);
+
+    splitter.freeze_shareholder(&frozen, &false);
+    splitter.withdraw_allocation(&token_address, &frozen, &800_975);
+    assert_eq!(token.balance(&frozen), 800_975);
+}
+
+#[test]
+fn freeze_follows_migrated_shareholder() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let frozen = share_data.get(0).unwrap().shareholder;
+    let new = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    splitter.freeze_shareholder(&frozen, &true);
+    splitter.migrate_shareholder(&frozen, &new);
+
+    assert_eq!(
+        splitter.try_transfer_shares(&new, &buyer, &1000),
+        Err(Ok(Error::ShareholderFrozen))
+    );
+
+    // The old address is no longer held
+    splitter.freeze_shareholder(&new, &false);
+    splitter.transfer_shares(&new, &frozen, &1000);
+    splitter.transfer_shares(&frozen, &buyer, &1000);
+    assert_eq!(splitter.get_share(&buyer), Some(1000));
+}
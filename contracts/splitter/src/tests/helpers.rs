@@ -1,4 +1,7 @@
-use soroban_sdk::{testutils::Address as _, token, vec, Address, Env, Vec};
+use soroban_sdk::{
+    testutils::{Address as _, Events},
+    token, vec, Address, Env, Symbol, TryFromVal, Val, Vec,
+};
 use token::{Client as TokenClient, StellarAssetClient as TokenAdminClient};
 
 use crate::{
@@ -89,3 +92,23 @@ pub fn get_default_share_data(env: &Env) -> Vec<ShareDataKey> {
         },
     ]
 }
+
+/// Returns the topics and data of the events published by the contract whose first topic is `name`.
+pub fn get_events_by_name(env: &Env, contract: &Address, name: Symbol) -> Vec<(Vec<Val>, Val)> {
+    let mut events = Vec::new(env);
+    for (address, topics, data) in env.events().all().iter() {
+        if address != *contract {
+            continue;
+        }
+        let matches = match topics.get(0) {
+            Some(topic) => Symbol::try_from_val(env, &topic)
+                .map(|topic| topic == name)
+                .unwrap_or(false),
+            None => false,
+        };
+        if matches {
+            events.push_back((topics, data));
+        }
+    }
+    events
+}
@@ -1,6 +1,10 @@
-use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+use soroban_sdk::{symbol_short, testutils::Address as _, vec, Address, Env, IntoVal};
 
-use crate::{errors::Error, storage::ShareDataKey, tests::helpers::create_splitter};
+use crate::{
+    errors::Error,
+    storage::{CommissionConfig, ShareDataKey},
+    tests::helpers::{create_splitter, get_events_by_name},
+};
 
 #[test]
 fn happy_path() {
@@ -150,3 +154,382 @@ fn test_duplicate_shareholder() {
         Err(Ok(Error::DuplicateShareholder))
     );
 }
+
+#[test]
+fn test_zero_share_not_allowed() {
+    let env: Env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    let admin = Address::generate(&env);
+
+    assert_eq!(
+        splitter.try_init(
+            &admin,
+            &vec![
+                &env,
+                ShareDataKey {
+                    shareholder: Address::generate(&env),
+                    share: 10000,
+                },
+                ShareDataKey {
+                    shareholder: Address::generate(&env),
+                    share: 0,
+                },
+            ],
+            &true
+        ),
+        Err(Ok(Error::ZeroShareNotAllowed))
+    );
+}
+
+#[test]
+fn test_share_overflow() {
+    let env: Env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    let admin = Address::generate(&env);
+
+    // Huge shares must not wrap around to a valid total
+    assert_eq!(
+        splitter.try_init(
+            &admin,
+            &vec![
+                &env,
+                ShareDataKey {
+                    shareholder: Address::generate(&env),
+                    share: i128::MAX,
+                },
+                ShareDataKey {
+                    shareholder: Address::generate(&env),
+                    share: 10001,
+                },
+            ],
+            &true
+        ),
+        Err(Ok(Error::Overflow))
+    );
+
+    assert_eq!(
+        splitter.try_init(
+            &admin,
+            &vec![
+                &env,
+                ShareDataKey {
+                    shareholder: Address::generate(&env),
+                    share: 10001,
+                },
+            ],
+            &true
+        ),
+        Err(Ok(Error::Overflow))
+    );
+}
+
+#[test]
+fn test_init_emits_share_events() {
+    let env: Env = Env::default();
+    let (splitter, splitter_address) = create_splitter(&env);
+
+    let admin = Address::generate(&env);
+    let shares = vec![
+        &env,
+        ShareDataKey {
+            shareholder: Address::generate(&env),
+            share: 5000,
+        },
+        ShareDataKey {
+            shareholder: Address::generate(&env),
+            share: 3000,
+        },
+        ShareDataKey {
+            shareholder: Address::generate(&env),
+            share: 2000,
+        },
+    ];
+
+    splitter.init(&admin, &shares, &true);
+
+    let events = get_events_by_name(&env, &splitter_address, symbol_short!("init_sh"));
+    assert_eq!(events.len(), shares.len());
+    for (i, (topics, data)) in events.iter().enumerate() {
+        let expected = shares.get(i as u32).unwrap();
+        let shareholder: Address = topics.get(1).unwrap().into_val(&env);
+        let share: i128 = data.into_val(&env);
+        assert_eq!(shareholder, expected.shareholder);
+        assert_eq!(share, expected.share);
+    }
+}
+
+#[test]
+fn test_is_shareholder() {
+    let env: Env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    let admin = Address::generate(&env);
+    let shareholder = Address::generate(&env);
+
+    assert_eq!(
+        splitter.try_is_shareholder(&shareholder),
+        Err(Ok(Error::NotInitialized))
+    );
+
+    splitter.init(
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    assert!(splitter.is_shareholder(&shareholder));
+    assert!(!splitter.is_shareholder(&Address::generate(&env)));
+}
+
+#[test]
+fn test_get_share_or_zero() {
+    let env: Env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    let admin = Address::generate(&env);
+    let shareholder = Address::generate(&env);
+
+    assert_eq!(
+        splitter.try_get_share_or_zero(&shareholder),
+        Err(Ok(Error::NotInitialized))
+    );
+
+    splitter.init(
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    assert_eq!(splitter.get_share_or_zero(&shareholder), 10000);
+    assert_eq!(splitter.get_share_or_zero(&Address::generate(&env)), 0);
+}
+
+#[test]
+fn test_list_shares_sorted() {
+    let env: Env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    let admin = Address::generate(&env);
+    let shareholder_1 = Address::generate(&env);
+    let shareholder_2 = Address::generate(&env);
+    let shareholder_3 = Address::generate(&env);
+    let shareholder_4 = Address::generate(&env);
+    splitter.init(
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder_1.clone(),
+                share: 2000,
+            },
+            ShareDataKey {
+                shareholder: shareholder_2.clone(),
+                share: 5000,
+            },
+            ShareDataKey {
+                shareholder: shareholder_3.clone(),
+                share: 2000,
+            },
+            ShareDataKey {
+                shareholder: shareholder_4.clone(),
+                share: 1000,
+            },
+        ],
+        &true,
+    );
+
+    // Descending by share, the tie between shareholder 1 and 3 keeps the stored order
+    let sorted = splitter.list_shares_sorted();
+    assert_eq!(
+        sorted,
+        vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder_2,
+                share: 5000,
+            },
+            ShareDataKey {
+                shareholder: shareholder_1,
+                share: 2000,
+            },
+            ShareDataKey {
+                shareholder: shareholder_3,
+                share: 2000,
+            },
+            ShareDataKey {
+                shareholder: shareholder_4,
+                share: 1000,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_commission_address_as_shareholder() {
+    let env: Env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    let admin = Address::generate(&env);
+
+    assert_eq!(
+        splitter.try_init(
+            &admin,
+            &vec![
+                &env,
+                ShareDataKey {
+                    shareholder: Address::generate(&env),
+                    share: 5000,
+                },
+                ShareDataKey {
+                    shareholder: CommissionConfig::get_default_recipient(&env),
+                    share: 5000,
+                },
+            ],
+            &true
+        ),
+        Err(Ok(Error::RecipientNotAllowed))
+    );
+}
+
+#[test]
+fn test_contract_address_as_shareholder() {
+    let env: Env = Env::default();
+    let (splitter, splitter_address) = create_splitter(&env);
+
+    let admin = Address::generate(&env);
+
+    assert_eq!(
+        splitter.try_init(
+            &admin,
+            &vec![
+                &env,
+                ShareDataKey {
+                    shareholder: splitter_address,
+                    share: 10000,
+                },
+            ],
+            &true
+        ),
+        Err(Ok(Error::RecipientNotAllowed))
+    );
+}
+
+#[test]
+fn init_with_commission() {
+    let env: Env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let commission = CommissionConfig {
+        recipient: recipient.clone(),
+        buy_rate_bps: 200,
+        distribution_rate_bps: 0,
+    };
+
+    splitter.init_with_commission(
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 10000,
+            },
+        ],
+        &true,
+        &commission,
+    );
+
+    assert_eq!(splitter.get_commission_config(), commission);
+}
+
+#[test]
+fn test_init_with_contract_as_commission_recipient() {
+    let env: Env = Env::default();
+    let (splitter, splitter_address) = create_splitter(&env);
+
+    let admin = Address::generate(&env);
+
+    assert_eq!(
+        splitter.try_init_with_commission(
+            &admin,
+            &vec![
+                &env,
+                ShareDataKey {
+                    shareholder: Address::generate(&env),
+                    share: 10000,
+                },
+            ],
+            &true,
+            &CommissionConfig {
+                recipient: splitter_address,
+                buy_rate_bps: 150,
+                distribution_rate_bps: 50,
+            }
+        ),
+        Err(Ok(Error::InvalidRecipient))
+    );
+}
+
+#[test]
+fn test_init_with_invalid_commission_rate() {
+    let env: Env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    let admin = Address::generate(&env);
+    let shares = vec![
+        &env,
+        ShareDataKey {
+            shareholder: Address::generate(&env),
+            share: 10000,
+        },
+    ];
+
+    assert_eq!(
+        splitter.try_init_with_commission(
+            &admin,
+            &shares,
+            &true,
+            &CommissionConfig {
+                recipient: Address::generate(&env),
+                buy_rate_bps: 5001,
+                distribution_rate_bps: 50,
+            }
+        ),
+        Err(Ok(Error::InvalidCommissionRate))
+    );
+    assert_eq!(
+        splitter.try_init_with_commission(
+            &admin,
+            &shares,
+            &true,
+            &CommissionConfig {
+                recipient: Address::generate(&env),
+                buy_rate_bps: 150,
+                distribution_rate_bps: -1,
+            }
+        ),
+        Err(Ok(Error::InvalidCommissionRate))
+    );
+
+    // Nothing was initialized
+    splitter.init(&admin, &shares, &true);
+    assert_eq!(
+        splitter.get_commission_config().recipient,
+        CommissionConfig::get_default_recipient(&env)
+    );
+}
@@ -1,9 +1,16 @@
-use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, IntoVal,
+};
 
 use crate::{
     errors::Error,
-    storage::ShareDataKey,
-    tests::helpers::{create_splitter_with_shares, create_token, setup_test_commission_recipient},
+    storage::{SaleListingDataKey, ShareDataKey, MAX_ALLOWED_BUYERS},
+    tests::helpers::{
+        create_splitter_with_shares, create_token, get_events_by_name,
+        setup_test_commission_recipient,
+    },
 };
 
 #[test]
@@ -580,3 +587,800 @@ fn allocations_stay_with_seller() {
     let buyer_new_allocation = splitter.get_allocation(&buyer, &token_address);
     assert_eq!(buyer_new_allocation, 497_500_000);
 }
+
+#[test]
+fn sold_event_reports_remaining_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token_address) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000_000);
+
+    splitter.list_shares_for_sale(&seller, &5000, &1000, &payment_token_address);
+
+    // Partial fill leaves 3000 shares in the listing
+    splitter.buy_shares(&buyer, &seller, &2000);
+    let events = get_events_by_name(&env, &splitter_address, symbol_short!("sold"));
+    let (_, data) = events.last().unwrap();
+    let data: (i128, i128, Address, i128, i128, i128) = data.into_val(&env);
+    assert_eq!(
+        data,
+        (2000, 2_000_000, payment_token_address.clone(), 3000, 30_000, 150)
+    );
+
+    // Full fill closes the listing
+    splitter.buy_shares(&buyer, &seller, &3000);
+    let events = get_events_by_name(&env, &splitter_address, symbol_short!("sold"));
+    let (_, data) = events.last().unwrap();
+    let data: (i128, i128, Address, i128, i128, i128) = data.into_val(&env);
+    assert_eq!(data, (3000, 3_000_000, payment_token_address.clone(), 0, 45_000, 150));
+}
+
+#[test]
+fn test_sold_event_reports_rate_in_effect() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token_address) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000_000);
+
+    splitter.list_shares_for_sale(&seller, &5000, &1000, &payment_token_address);
+
+    // The rate changes after the buyer saw the listing
+    splitter.set_buy_commission_rate(&300);
+    splitter.buy_shares(&buyer, &seller, &1000);
+
+    let events = get_events_by_name(&env, &splitter_address, symbol_short!("sold"));
+    let (_, data) = events.last().unwrap();
+    let data: (i128, i128, Address, i128, i128, i128) = data.into_val(&env);
+    assert_eq!(data, (1000, 1_000_000, payment_token_address, 4000, 30_000, 300));
+}
+
+#[test]
+fn whitelisted_listing() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let allowed_buyer = Address::generate(&env);
+    let other_buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token_address) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&allowed_buyer, &1_000_000_000);
+    payment_sudo_token.mint(&other_buyer, &1_000_000_000);
+
+    splitter.list_shares_for_sale(&seller, &5000, &1000, &payment_token_address);
+    splitter.set_allowed_buyers(&seller, &vec![&env, allowed_buyer.clone()]);
+
+    // Buyers not on the whitelist are rejected
+    assert_eq!(
+        splitter.try_buy_shares(&other_buyer, &seller, &1000),
+        Err(Ok(Error::BuyerNotWhitelisted))
+    );
+
+    // Whitelisted buyers can buy
+    splitter.buy_shares(&allowed_buyer, &seller, &1000);
+    assert_eq!(splitter.get_share(&allowed_buyer), Some(1000));
+
+    // The whitelist survives partial fills
+    let listing = splitter.get_listing(&seller).unwrap();
+    assert_eq!(listing.allowed_buyers, vec![&env, allowed_buyer.clone()]);
+}
+
+#[test]
+fn empty_whitelist_is_public() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let allowed_buyer = Address::generate(&env);
+    let other_buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token_address) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&other_buyer, &1_000_000_000);
+
+    splitter.list_shares_for_sale(&seller, &5000, &1000, &payment_token_address);
+    assert_eq!(splitter.get_listing(&seller).unwrap().allowed_buyers.len(), 0);
+
+    // Restrict, then make the listing public again
+    splitter.set_allowed_buyers(&seller, &vec![&env, allowed_buyer.clone()]);
+    splitter.set_allowed_buyers(&seller, &vec![&env]);
+
+    splitter.buy_shares(&other_buyer, &seller, &1000);
+    assert_eq!(splitter.get_share(&other_buyer), Some(1000));
+}
+
+#[test]
+fn test_whitelist_too_large() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, _, payment_token_address) = create_token(&env, &payment_token_admin);
+    splitter.list_shares_for_sale(&seller, &5000, &1000, &payment_token_address);
+
+    let mut allowed_buyers = vec![&env];
+    for _ in 0..=MAX_ALLOWED_BUYERS {
+        allowed_buyers.push_back(Address::generate(&env));
+    }
+
+    assert_eq!(
+        splitter.try_set_allowed_buyers(&seller, &allowed_buyers),
+        Err(Ok(Error::TooManyItems))
+    );
+}
+
+#[test]
+fn buy_from_multiple_sellers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller_1 = Address::generate(&env);
+    let seller_2 = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller_1.clone(),
+                share: 6000,
+            },
+            ShareDataKey {
+                shareholder: seller_2.clone(),
+                share: 4000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    splitter.list_shares_for_sale(&seller_1, &2000, &100, &payment_token_address);
+    splitter.list_shares_for_sale(&seller_2, &1000, &200, &payment_token_address);
+
+    // 1000 * 100 + 500 * 200 = 200_000
+    splitter.buy_shares_multi(
+        &buyer,
+        &vec![&env, (seller_1.clone(), 1000), (seller_2.clone(), 500)],
+    );
+
+    assert_eq!(splitter.get_share(&seller_1), Some(5000));
+    assert_eq!(splitter.get_share(&seller_2), Some(3500));
+    assert_eq!(splitter.get_share(&buyer), Some(1500));
+    assert_eq!(payment_token.balance(&buyer), 800_000);
+
+    // Seller receives the price minus the 1.5% commission
+    assert_eq!(payment_token.balance(&seller_1), 98_500);
+    assert_eq!(payment_token.balance(&seller_2), 98_500);
+
+    assert_eq!(splitter.get_listing(&seller_1).unwrap().shares_for_sale, 1000);
+    assert_eq!(splitter.get_listing(&seller_2).unwrap().shares_for_sale, 500);
+}
+
+#[test]
+fn buy_from_multiple_sellers_rejects_whole_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller_1 = Address::generate(&env);
+    let seller_2 = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller_1.clone(),
+                share: 6000,
+            },
+            ShareDataKey {
+                shareholder: seller_2.clone(),
+                share: 4000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    // Only the first seller has an active listing
+    splitter.list_shares_for_sale(&seller_1, &2000, &100, &payment_token_address);
+
+    assert_eq!(
+        splitter.try_buy_shares_multi(
+            &buyer,
+            &vec![&env, (seller_1.clone(), 1000), (seller_2.clone(), 500)],
+        ),
+        Err(Ok(Error::NoActiveListing))
+    );
+
+    // Nothing was bought
+    assert_eq!(splitter.get_share(&seller_1), Some(6000));
+    assert_eq!(splitter.get_share(&buyer), None);
+    assert_eq!(payment_token.balance(&buyer), 1_000_000);
+    assert_eq!(splitter.get_listing(&seller_1).unwrap().shares_for_sale, 2000);
+}
+
+#[test]
+fn credit_proceeds_to_seller_allocation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let other_shareholder = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 8000,
+            },
+            ShareDataKey {
+                shareholder: other_shareholder.clone(),
+                share: 2000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    let recipient = setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &2_000_000);
+
+    splitter.list_shares_for_sale(&seller, &2000, &1000, &payment_token_address);
+    splitter.set_credit_proceeds(&seller, &true);
+
+    // Replacing the listing keeps the setting
+    splitter.list_shares_for_sale(&seller, &1000, &1000, &payment_token_address);
+    assert!(splitter.get_listing(&seller).unwrap().credit_proceeds);
+
+    // Total price 1_000_000, commission 15_000, seller is credited 985_000
+    splitter.buy_shares(&buyer, &seller, &1000);
+
+    assert_eq!(payment_token.balance(&seller), 0);
+    assert_eq!(payment_token.balance(&recipient), 15_000);
+    assert_eq!(payment_token.balance(&splitter_address), 985_000);
+    assert_eq!(splitter.get_allocation(&seller, &payment_token_address), 985_000);
+
+    // The credited proceeds are not distributed to the other shareholders
+    splitter.distribute_tokens(&payment_token_address);
+    assert_eq!(splitter.get_allocation(&other_shareholder, &payment_token_address), 0);
+
+    splitter.withdraw_allocation(&payment_token_address, &seller, &985_000);
+    assert_eq!(payment_token.balance(&seller), 985_000);
+}
+
+#[test]
+fn test_insufficient_payment_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+
+    // The purchase costs 1_000_000, the buyer only has 999_999
+    payment_sudo_token.mint(&buyer, &999_999);
+    splitter.list_shares_for_sale(&seller, &1000, &1000, &payment_token_address);
+
+    assert_eq!(
+        splitter.try_buy_shares(&buyer, &seller, &1000),
+        Err(Ok(Error::InsufficientPaymentBalance))
+    );
+    assert_eq!(payment_token.balance(&buyer), 999_999);
+    assert_eq!(splitter.get_share(&buyer), None);
+}
+
+#[test]
+fn total_shares_stay_intact_across_buys() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let other_holder = Address::generate(&env);
+    let buyer_1 = Address::generate(&env);
+    let buyer_2 = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 6000,
+            },
+            ShareDataKey {
+                shareholder: other_holder.clone(),
+                share: 4000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token_address) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&seller, &0);
+    payment_sudo_token.mint(&other_holder, &1_000_000);
+    payment_sudo_token.mint(&buyer_1, &1_000_000);
+    payment_sudo_token.mint(&buyer_2, &1_000_000);
+
+    let assert_intact = || {
+        assert!(splitter.verify_integrity());
+        let total: i128 = splitter.list_shares().iter().map(|share| share.share).sum();
+        assert_eq!(total, 10000);
+    };
+    assert_intact();
+
+    splitter.list_shares_for_sale(&seller, &6000, &10, &payment_token_address);
+    splitter.buy_shares(&buyer_1, &seller, &1000);
+    assert_intact();
+    splitter.buy_shares(&buyer_2, &seller, &2500);
+    assert_intact();
+    splitter.buy_shares(&buyer_1, &seller, &2500);
+    assert_intact();
+
+    splitter.list_shares_for_sale(&buyer_1, &3500, &10, &payment_token_address);
+    splitter.buy_shares(&other_holder, &buyer_1, &3500);
+    assert_intact();
+
+    assert_eq!(splitter.get_share(&seller), None);
+    assert_eq!(splitter.get_share(&other_holder), Some(7500));
+}
+
+#[test]
+fn buy_by_id_rejects_relisted_listing() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token_address) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&seller, &0);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    splitter.list_shares_for_sale(&seller, &5000, &10, &payment_token_address);
+    let first_id = splitter.get_listing(&seller).unwrap().listing_id;
+    splitter.buy_shares_by_id(&buyer, &first_id, &100);
+
+    // Partial fills keep the id
+    assert_eq!(splitter.get_listing(&seller).unwrap().listing_id, first_id);
+
+    // Relisting at other terms assigns a new id
+    splitter.list_shares_for_sale(&seller, &4900, &20, &payment_token_address);
+    let second_id = splitter.get_listing(&seller).unwrap().listing_id;
+    assert!(second_id > first_id);
+
+    // Only the open listing's id maps to the seller
+    let listing_seller = |listing_id: u64| {
+        env.as_contract(&splitter_address, || {
+            SaleListingDataKey::get_listing_seller(&env, listing_id)
+        })
+    };
+    assert_eq!(listing_seller(first_id), None);
+    assert_eq!(listing_seller(second_id), Some(seller.clone()));
+
+    assert_eq!(
+        splitter.try_buy_shares_by_id(&buyer, &first_id, &100),
+        Err(Ok(Error::ListingChanged))
+    );
+    splitter.buy_shares_by_id(&buyer, &second_id, &100);
+    assert_eq!(splitter.get_share(&buyer), Some(200));
+
+    // Cancelling and relisting changes the id as well
+    splitter.cancel_listing(&seller);
+    assert_eq!(listing_seller(second_id), None);
+    assert_eq!(
+        splitter.try_buy_shares_by_id(&buyer, &second_id, &100),
+        Err(Ok(Error::ListingChanged))
+    );
+    splitter.list_shares_for_sale(&seller, &4800, &20, &payment_token_address);
+    assert_eq!(
+        splitter.try_buy_shares_by_id(&buyer, &second_id, &100),
+        Err(Ok(Error::ListingChanged))
+    );
+
+    // Unknown ids have no listing
+    assert_eq!(
+        splitter.try_buy_shares_by_id(&buyer, &999, &100),
+        Err(Ok(Error::NoActiveListing))
+    );
+}
+
+#[test]
+fn test_min_seller_proceeds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000);
+
+    // At the maximum 50% buy commission half of every payment goes to the recipient
+    splitter.set_buy_commission_rate(&5000);
+
+    assert_eq!(
+        splitter.try_set_min_seller_proceeds(&payment_token_address, &0),
+        Err(Ok(Error::InvalidPrice))
+    );
+    splitter.set_min_seller_proceeds(&payment_token_address, &2);
+
+    // Total price 2, commission 1, so the seller would only receive 1
+    splitter.list_shares_for_sale(&seller, &100, &2, &payment_token_address);
+    assert_eq!(
+        splitter.try_buy_shares(&buyer, &seller, &1),
+        Err(Ok(Error::SellerProceedsTooLow))
+    );
+    assert_eq!(splitter.get_share(&buyer), None);
+
+    // Total price 4, commission 2, the seller receives the minimum
+    splitter.buy_shares(&buyer, &seller, &2);
+    assert_eq!(payment_token.balance(&seller), 2);
+    assert_eq!(splitter.get_share(&buyer).unwrap(), 2);
+}
+
+#[test]
+fn test_per_buyer_cap_across_buys() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer_1 = Address::generate(&env);
+    let buyer_2 = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer_1, &1_000_000);
+    payment_sudo_token.mint(&buyer_2, &1_000_000);
+
+    splitter.list_shares_for_sale(&seller, &5000, &100, &payment_token);
+    assert_eq!(
+        splitter.try_set_max_per_buyer(&seller, &-1),
+        Err(Ok(Error::InvalidShareAmount))
+    );
+    splitter.set_max_per_buyer(&seller, &2000);
+
+    // The cap applies to the buys of a buyer combined
+    splitter.buy_shares(&buyer_1, &seller, &1500);
+    assert_eq!(
+        splitter.try_buy_shares(&buyer_1, &seller, &1000),
+        Err(Ok(Error::PerBuyerCapExceeded))
+    );
+    splitter.buy_shares(&buyer_1, &seller, &500);
+    assert_eq!(splitter.get_share(&buyer_1), Some(2000));
+
+    // Other buyers have their own cap
+    splitter.buy_shares(&buyer_2, &seller, &2000);
+    assert_eq!(splitter.get_share(&buyer_2), Some(2000));
+
+    // A replaced listing keeps its cap, the buys from the earlier listing don't count
+    splitter.list_shares_for_sale(&seller, &3000, &100, &payment_token);
+    assert_eq!(splitter.get_listing(&seller).unwrap().max_per_buyer, 2000);
+    splitter.buy_shares(&buyer_1, &seller, &2000);
+    assert_eq!(splitter.get_share(&buyer_1), Some(4000));
+    assert_eq!(
+        splitter.try_buy_shares(&buyer_1, &seller, &1000),
+        Err(Ok(Error::PerBuyerCapExceeded))
+    );
+
+    // A new listing starts without a cap
+    splitter.cancel_listing(&seller);
+    splitter.list_shares_for_sale(&seller, &1000, &100, &payment_token);
+    assert_eq!(splitter.get_listing(&seller).unwrap().max_per_buyer, 0);
+    splitter.buy_shares(&buyer_1, &seller, &1000);
+    assert_eq!(splitter.get_share(&buyer_1), Some(5000));
+}
+
+#[test]
+fn test_replaced_listing_keeps_settings() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    splitter.list_shares_for_sale(&seller, &5000, &100, &payment_token);
+    splitter.set_max_per_buyer(&seller, &2000);
+    splitter.set_listing_expiration(&seller, &500);
+    splitter.set_listing_activation(&seller, &200);
+
+    // Replacing the listing changes the amount and the price only
+    splitter.list_shares_for_sale(&seller, &4000, &50, &payment_token);
+    let listing = splitter.get_listing(&seller).unwrap();
+    assert_eq!(listing.shares_for_sale, 4000);
+    assert_eq!(listing.price_per_share, 50);
+    assert_eq!(listing.max_per_buyer, 2000);
+    assert_eq!(listing.expiration_ledger, 500);
+    assert_eq!(listing.activation_ledger, 200);
+
+    // The replacement is still delayed
+    assert_eq!(
+        splitter.try_buy_shares(&buyer, &seller, &1000),
+        Err(Ok(Error::NoActiveListing))
+    );
+
+    // And capped once active
+    env.ledger().set_sequence_number(200);
+    assert_eq!(
+        splitter.try_buy_shares(&buyer, &seller, &3000),
+        Err(Ok(Error::PerBuyerCapExceeded))
+    );
+    splitter.buy_shares(&buyer, &seller, &2000);
+
+    // And expires as before
+    env.ledger().set_sequence_number(501);
+    assert_eq!(
+        splitter.try_buy_shares(&buyer, &seller, &1000),
+        Err(Ok(Error::NoActiveListing))
+    );
+}
+
+#[test]
+fn test_same_identity_cannot_buy() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let linked_buyer = Address::generate(&env);
+    let other_buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&linked_buyer, &1_000_000);
+    payment_sudo_token.mint(&other_buyer, &1_000_000);
+
+    splitter.list_shares_for_sale(&seller, &5000, &100, &payment_token);
+
+    // Both addresses belong to the same entity
+    splitter.set_identity(&seller, &7);
+    splitter.set_identity(&linked_buyer, &7);
+    assert_eq!(
+        splitter.try_buy_shares(&linked_buyer, &seller, &1000),
+        Err(Ok(Error::SameIdentity))
+    );
+    assert_eq!(
+        splitter.try_deposit_for_purchase(&linked_buyer, &seller, &1000),
+        Err(Ok(Error::SameIdentity))
+    );
+
+    // Unlinked addresses and other identities are unrestricted
+    splitter.buy_shares(&other_buyer, &seller, &1000);
+    splitter.set_identity(&other_buyer, &8);
+    splitter.buy_shares(&other_buyer, &seller, &1000);
+    assert_eq!(splitter.get_share(&other_buyer), Some(2000));
+
+    // Unlinking lifts the restriction
+    splitter.set_identity(&linked_buyer, &0);
+    splitter.buy_shares(&linked_buyer, &seller, &1000);
+    assert_eq!(splitter.get_share(&linked_buyer), Some(1000));
+}
@@ -1,9 +1,16 @@
-use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, IntoVal,
+};
 
 use crate::{
     errors::Error,
     storage::ShareDataKey,
-    tests::helpers::{create_splitter_with_shares, create_token, get_default_share_data},
+    tests::helpers::{
+        create_splitter_with_shares, create_token, get_default_share_data, get_events_by_name,
+        setup_test_commission_recipient,
+    },
 };
 
 #[test]
@@ -167,3 +174,271 @@ fn cancel_removes_from_active_listings() {
     assert_eq!(all_listings.len(), 1);
     assert_eq!(all_listings.get(0).unwrap().seller, shareholder_2);
 }
+
+#[test]
+fn canceled_event_reports_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let shareholder = share_data.get(0).unwrap().shareholder.clone();
+
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, _, payment_token_address) = create_token(&env, &payment_token_admin);
+
+    splitter.list_shares_for_sale(&shareholder, &5000, &100_000_000, &payment_token_address);
+    splitter.cancel_listing(&shareholder);
+
+    let events = get_events_by_name(&env, &splitter_address, symbol_short!("canceled"));
+    assert_eq!(events.len(), 1);
+    let (_, data) = events.get(0).unwrap();
+    let data: (i128, i128) = data.into_val(&env);
+    // The seller still owns 8050 shares and cancelled a listing of 5000
+    assert_eq!(data, (8050, 5000));
+}
+
+#[test]
+fn test_early_cancellation_penalty() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let seller = share_data.get(0).unwrap().shareholder.clone();
+
+    let (splitter, _) = create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, sudo_payment_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    let commission_recipient =
+        setup_test_commission_recipient(&env, &splitter, &[&sudo_payment_token]);
+    sudo_payment_token.mint(&seller, &10_000);
+
+    // 1% of the listing value for listings cancelled within 100 ledgers
+    splitter.set_cancel_penalty(&100, &100);
+    let cancel_penalty = splitter.get_cancel_penalty().unwrap();
+    assert_eq!(cancel_penalty.bps, 100);
+    assert_eq!(cancel_penalty.min_age_ledgers, 100);
+
+    // Listing value is 5000 * 100 = 500_000, so the penalty is 5_000
+    splitter.list_shares_for_sale(&seller, &5000, &100, &payment_token_address);
+    env.ledger().set_sequence_number(150);
+
+    // Replacing the listing doesn't reset its age
+    splitter.list_shares_for_sale(&seller, &5000, &100, &payment_token_address);
+    env.ledger().set_sequence_number(199);
+    splitter.cancel_listing(&seller);
+    assert_eq!(payment_token.balance(&seller), 5_000);
+    assert_eq!(payment_token.balance(&commission_recipient), 5_000);
+
+    // Old enough listings are cancelled for free
+    splitter.list_shares_for_sale(&seller, &5000, &100, &payment_token_address);
+    env.ledger().set_sequence_number(299);
+    splitter.cancel_listing(&seller);
+    assert_eq!(payment_token.balance(&seller), 5_000);
+    assert_eq!(payment_token.balance(&commission_recipient), 5_000);
+
+    // Without a penalty early cancellations are free as well
+    splitter.set_cancel_penalty(&0, &100);
+    assert_eq!(splitter.get_cancel_penalty(), None);
+    splitter.list_shares_for_sale(&seller, &5000, &100, &payment_token_address);
+    splitter.cancel_listing(&seller);
+    assert_eq!(payment_token.balance(&seller), 5_000);
+}
+
+#[test]
+fn test_invalid_cancel_penalty() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+
+    let (splitter, _) = create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    assert_eq!(
+        splitter.try_set_cancel_penalty(&10_001, &100),
+        Err(Ok(Error::InvalidCancelPenalty))
+    );
+    assert_eq!(
+        splitter.try_set_cancel_penalty(&-1, &100),
+        Err(Ok(Error::InvalidCancelPenalty))
+    );
+}
+
+#[test]
+fn test_listing_fee_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let seller = share_data.get(0).unwrap().shareholder.clone();
+
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, sudo_payment_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    let commission_recipient =
+        setup_test_commission_recipient(&env, &splitter, &[&sudo_payment_token]);
+    sudo_payment_token.mint(&seller, &10_000);
+
+    assert_eq!(
+        splitter.try_set_listing_fee(&10_001, &100),
+        Err(Ok(Error::InvalidListingFee))
+    );
+
+    // 1% of the listing value, refunded pro-rata within 100 ledgers
+    splitter.set_listing_fee(&100, &100);
+    assert_eq!(splitter.get_listing_fee().unwrap().refund_window_ledgers, 100);
+
+    // Listing value is 5000 * 100 = 500_000, so the fee is 5_000
+    splitter.list_shares_for_sale(&seller, &5000, &100, &payment_token_address);
+    assert_eq!(payment_token.balance(&seller), 5_000);
+    assert_eq!(payment_token.balance(&splitter_address), 5_000);
+    assert_eq!(splitter.get_listing(&seller).unwrap().fee_paid, 5_000);
+
+    // Replacing the listing doesn't charge the fee again
+    splitter.list_shares_for_sale(&seller, &4000, &100, &payment_token_address);
+    assert_eq!(payment_token.balance(&seller), 5_000);
+
+    // Cancelled after 25 of 100 ledgers, so 75% of the fee is refunded
+    env.ledger().set_sequence_number(125);
+    splitter.cancel_listing(&seller);
+    assert_eq!(payment_token.balance(&seller), 8_750);
+
+    // The recipient sweeps the rest of the fee
+    splitter.sweep_buy_commission(&payment_token_address);
+    assert_eq!(payment_token.balance(&commission_recipient), 1_250);
+    assert_eq!(payment_token.balance(&splitter_address), 0);
+
+    // Cancelled at the end of the window, so nothing is refunded
+    splitter.list_shares_for_sale(&seller, &5000, &100, &payment_token_address);
+    env.ledger().set_sequence_number(225);
+    splitter.cancel_listing(&seller);
+    assert_eq!(payment_token.balance(&seller), 3_750);
+    assert_eq!(payment_token.balance(&splitter_address), 5_000);
+}
+
+#[test]
+fn test_listing_fee_kept_apart_from_commission() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let seller = share_data.get(0).unwrap().shareholder.clone();
+    let buyer = Address::generate(&env);
+
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, sudo_payment_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    let (_, sudo_other_token, other_token_address) = create_token(&env, &payment_token_admin);
+    let commission_recipient = setup_test_commission_recipient(
+        &env,
+        &splitter,
+        &[&sudo_payment_token, &sudo_other_token],
+    );
+    sudo_payment_token.mint(&seller, &10_000);
+    sudo_payment_token.mint(&buyer, &1_000_000);
+
+    splitter.set_listing_fee(&100, &100);
+    splitter.set_defer_buy_commission(&true);
+
+    // The fee is 5_000, and the first buy accrues 1_500 buy commission
+    splitter.list_shares_for_sale(&seller, &5000, &100, &payment_token_address);
+    splitter.buy_shares(&buyer, &seller, &1000);
+    assert_eq!(splitter.get_accrued_buy_commission(&payment_token_address), 1_500);
+
+    // Halfway through the window half of the fee can still be refunded, the rest is swept
+    env.ledger().set_sequence_number(150);
+    splitter.sweep_buy_commission(&payment_token_address);
+    assert_eq!(payment_token.balance(&commission_recipient), 4_000);
+    assert_eq!(splitter.get_accrued_buy_commission(&payment_token_address), 0);
+
+    // The listing is repriced in another token, the fee stays in the token it was paid in
+    splitter.buy_shares(&buyer, &seller, &1000);
+    splitter.list_shares_for_sale(&seller, &3000, &100, &other_token_address);
+    assert_eq!(
+        splitter.get_listing(&seller).unwrap().fee_token,
+        payment_token_address
+    );
+
+    // The refund is paid from the fee, the accrued buy commission is untouched
+    env.ledger().set_sequence_number(175);
+    splitter.cancel_listing(&seller);
+    assert_eq!(payment_token.balance(&seller), 5_000 + 98_500 * 2 + 1_250);
+    assert_eq!(splitter.get_accrued_buy_commission(&payment_token_address), 1_500);
+
+    // The rest of the fee and the commission are swept
+    splitter.sweep_buy_commission(&payment_token_address);
+    assert_eq!(payment_token.balance(&commission_recipient), 4_000 + 1_500 + 1_250);
+    assert_eq!(payment_token.balance(&splitter_address), 0);
+}
+
+#[test]
+fn cancel_all_my_orders() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let seller = share_data.get(0).unwrap().shareholder.clone();
+    let buyer = Address::generate(&env);
+
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    // A listing with an escrowed purchase, and a pool contribution
+    splitter.list_shares_for_sale(&seller, &2000, &100, &payment_token_address);
+    splitter.deposit_for_purchase(&buyer, &seller, &1000);
+    splitter.contribute_to_pool(&seller, &payment_token_address, &200, &500);
+
+    // Pools and buyers without a position are skipped
+    splitter.cancel_all_my_orders(
+        &seller,
+        &vec![
+            &env,
+            (payment_token_address.clone(), 200),
+            (payment_token_address.clone(), 300),
+        ],
+        &vec![&env, buyer.clone(), Address::generate(&env)],
+    );
+
+    let events = get_events_by_name(&env, &splitter_address, symbol_short!("cncl_all"));
+    assert_eq!(events.len(), 1);
+    let (_, data) = events.get(0).unwrap();
+    let data: (i128, i128, u32) = data.into_val(&env);
+    // The escrowed 1000 were already taken off the listing
+    assert_eq!(data, (1000, 500, 1));
+
+    assert!(splitter.get_listing(&seller).is_none());
+    assert_eq!(
+        splitter.try_leave_pool(&seller, &payment_token_address, &200),
+        Err(Ok(Error::NoActiveListing))
+    );
+    assert!(splitter.get_escrow(&buyer, &seller).is_none());
+    assert_eq!(payment_token.balance(&buyer), 1_000_000);
+    assert_eq!(payment_token.balance(&splitter_address), 0);
+
+    // Nothing left to cancel is not an error
+    splitter.cancel_all_my_orders(&seller, &vec![&env], &vec![&env]);
+}
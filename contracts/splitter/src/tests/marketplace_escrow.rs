@@ -0,0 +1,380 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env,
+};
+
+use crate::{
+    errors::Error,
+    storage::ShareDataKey,
+    tests::helpers::{create_splitter_with_shares, create_token, setup_test_commission_recipient},
+};
+
+#[test]
+fn release_path() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    let commission_recipient =
+        setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+
+    payment_sudo_token.mint(&buyer, &1_000_000_000);
+    splitter.list_shares_for_sale(&seller, &2000, &100_000, &payment_token_address);
+
+    // Buyer escrows 2000 * 100_000 = 200_000_000
+    splitter.deposit_for_purchase(&buyer, &seller, &2000);
+
+    let escrow = splitter.get_escrow(&buyer, &seller).unwrap();
+    assert_eq!(escrow.shares_amount, 2000);
+    assert_eq!(escrow.total_price, 200_000_000);
+    assert_eq!(payment_token.balance(&buyer), 800_000_000);
+    assert_eq!(payment_token.balance(&splitter_address), 200_000_000);
+
+    // Shares don't move until the seller confirms
+    assert_eq!(splitter.get_share(&seller), Some(8050));
+    assert_eq!(splitter.get_share(&buyer), None);
+
+    // Escrowed funds are not distributable
+    splitter.distribute_tokens(&payment_token_address);
+    assert_eq!(splitter.get_allocation(&seller, &payment_token_address), 0);
+
+    splitter.release_purchase(&seller, &buyer);
+
+    // Commission (1.5%): 200_000_000 * 150 / 10000 = 3_000_000
+    assert_eq!(splitter.get_share(&seller), Some(6050));
+    assert_eq!(splitter.get_share(&buyer), Some(2000));
+    assert_eq!(payment_token.balance(&seller), 197_000_000);
+    assert_eq!(payment_token.balance(&commission_recipient), 3_000_000);
+    assert_eq!(payment_token.balance(&splitter_address), 0);
+    assert!(splitter.get_escrow(&buyer, &seller).is_none());
+
+    // Released escrows cannot be refunded
+    assert_eq!(
+        splitter.try_refund_purchase(&buyer, &seller),
+        Err(Ok(Error::NoEscrow))
+    );
+}
+
+#[test]
+fn refund_path() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+
+    payment_sudo_token.mint(&buyer, &1_000_000_000);
+    splitter.list_shares_for_sale(&seller, &2000, &100_000, &payment_token_address);
+    splitter.deposit_for_purchase(&buyer, &seller, &2000);
+
+    // The buyer cannot refund before the escrow expires
+    assert_eq!(
+        splitter.try_refund_purchase(&buyer, &seller),
+        Err(Ok(Error::EscrowActive))
+    );
+
+    let escrow = splitter.get_escrow(&buyer, &seller).unwrap();
+    env.ledger().set_sequence_number(escrow.expiration_ledger);
+
+    splitter.refund_purchase(&buyer, &seller);
+
+    assert_eq!(payment_token.balance(&buyer), 1_000_000_000);
+    assert_eq!(payment_token.balance(&splitter_address), 0);
+    assert_eq!(splitter.get_share(&seller), Some(8050));
+    assert_eq!(splitter.get_share(&buyer), None);
+    assert!(splitter.get_escrow(&buyer, &seller).is_none());
+
+    // Refunded escrows cannot be released
+    assert_eq!(
+        splitter.try_release_purchase(&seller, &buyer),
+        Err(Ok(Error::NoEscrow))
+    );
+}
+
+#[test]
+fn test_duplicate_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token_address) = create_token(&env, &payment_token_admin);
+
+    payment_sudo_token.mint(&buyer, &1_000_000_000);
+    splitter.list_shares_for_sale(&seller, &2000, &100_000, &payment_token_address);
+    splitter.deposit_for_purchase(&buyer, &seller, &1000);
+
+    assert_eq!(
+        splitter.try_deposit_for_purchase(&buyer, &seller, &1000),
+        Err(Ok(Error::EscrowActive))
+    );
+}
+
+#[test]
+fn test_no_active_listing() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    assert_eq!(
+        splitter.try_deposit_for_purchase(&buyer, &seller, &1000),
+        Err(Ok(Error::NoActiveListing))
+    );
+}
+
+#[test]
+fn escrow_reserves_listing_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer_1 = Address::generate(&env);
+    let buyer_2 = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token_address) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer_1, &1_000_000);
+    payment_sudo_token.mint(&buyer_2, &1_000_000);
+
+    splitter.list_shares_for_sale(&seller, &2000, &100, &payment_token_address);
+    splitter.deposit_for_purchase(&buyer_1, &seller, &1500);
+
+    // Only the unreserved 500 can still be escrowed or bought
+    assert_eq!(splitter.get_listing(&seller).unwrap().shares_for_sale, 500);
+    assert_eq!(
+        splitter.try_deposit_for_purchase(&buyer_2, &seller, &1000),
+        Err(Ok(Error::InsufficientSharesInListing))
+    );
+    assert_eq!(
+        splitter.try_buy_shares(&buyer_2, &seller, &1000),
+        Err(Ok(Error::InsufficientSharesInListing))
+    );
+
+    // Nor can the reserved shares be listed again
+    assert_eq!(splitter.get_effective_share(&seller).2, 8050 - 1500);
+    assert_eq!(
+        splitter.try_list_shares_for_sale(&seller, &7000, &100, &payment_token_address),
+        Err(Ok(Error::SharesLocked))
+    );
+
+    // Releasing leaves the listing with the unreserved shares only
+    splitter.release_purchase(&seller, &buyer_1);
+    assert_eq!(splitter.get_share(&buyer_1), Some(1500));
+    assert_eq!(splitter.get_listing(&seller).unwrap().shares_for_sale, 500);
+}
+
+#[test]
+fn refund_restores_listing_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token_address) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    splitter.list_shares_for_sale(&seller, &2000, &100, &payment_token_address);
+    splitter.deposit_for_purchase(&buyer, &seller, &1500);
+
+    let escrow = splitter.get_escrow(&buyer, &seller).unwrap();
+    env.ledger().set_sequence_number(escrow.expiration_ledger);
+    splitter.refund_purchase(&buyer, &seller);
+
+    assert_eq!(splitter.get_listing(&seller).unwrap().shares_for_sale, 2000);
+    assert_eq!(splitter.get_effective_share(&seller).2, 8050);
+}
+
+#[test]
+fn update_shares_keeps_escrowed_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let other = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: other.clone(),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    splitter.list_shares_for_sale(&seller, &2000, &100, &payment_token);
+    splitter.deposit_for_purchase(&buyer, &seller, &2000);
+
+    // The seller can't be updated below the escrowed shares
+    assert_eq!(
+        splitter.try_update_shares(
+            &vec![
+                &env,
+                ShareDataKey {
+                    shareholder: seller.clone(),
+                    share: 1000,
+                },
+                ShareDataKey {
+                    shareholder: other.clone(),
+                    share: 9000,
+                },
+            ],
+            &false,
+            &false,
+        ),
+        Err(Ok(Error::EscrowActive))
+    );
+
+    splitter.update_shares(
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 2000,
+            },
+            ShareDataKey {
+                shareholder: other.clone(),
+                share: 8000,
+            },
+        ],
+        &false,
+        &false,
+    );
+
+    // The escrowed shares can still be released to the buyer
+    splitter.release_purchase(&seller, &buyer);
+    assert_eq!(splitter.get_share(&buyer), Some(2000));
+    assert_eq!(splitter.get_share(&seller), None);
+}
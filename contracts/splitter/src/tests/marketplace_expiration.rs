@@ -0,0 +1,345 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, Symbol,
+};
+
+use crate::{
+    errors::Error,
+    storage::ShareDataKey,
+    tests::helpers::{
+        create_splitter_with_shares, create_token, get_events_by_name,
+        setup_test_commission_recipient,
+    },
+};
+
+#[test]
+fn expired_listing_cannot_be_bought() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    splitter.list_shares_for_sale(&seller, &2000, &100, &payment_token);
+    splitter.set_listing_expiration(&seller, &110);
+    assert_eq!(splitter.get_listing(&seller).unwrap().expiration_ledger, 110);
+
+    // The listing can be bought up to the expiration ledger
+    env.ledger().set_sequence_number(110);
+    splitter.buy_shares(&buyer, &seller, &1000);
+
+    env.ledger().set_sequence_number(111);
+    assert_eq!(
+        splitter.try_buy_shares(&buyer, &seller, &1000),
+        Err(Ok(Error::NoActiveListing))
+    );
+
+    // Replacing the listing keeps the expiration, the seller has to lift it
+    splitter.list_shares_for_sale(&seller, &1000, &100, &payment_token);
+    assert_eq!(splitter.get_listing(&seller).unwrap().expiration_ledger, 110);
+    assert_eq!(
+        splitter.try_buy_shares(&buyer, &seller, &1000),
+        Err(Ok(Error::NoActiveListing))
+    );
+    splitter.set_listing_expiration(&seller, &0);
+    splitter.buy_shares(&buyer, &seller, &1000);
+    assert_eq!(splitter.get_share(&buyer), Some(2000));
+}
+
+#[test]
+fn prune_expired_listings() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let seller_1 = Address::generate(&env);
+    let seller_2 = Address::generate(&env);
+    let seller_3 = Address::generate(&env);
+    let seller_4 = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller_1.clone(),
+                share: 2500,
+            },
+            ShareDataKey {
+                shareholder: seller_2.clone(),
+                share: 2500,
+            },
+            ShareDataKey {
+                shareholder: seller_3.clone(),
+                share: 2500,
+            },
+            ShareDataKey {
+                shareholder: seller_4.clone(),
+                share: 2500,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, _, payment_token) = create_token(&env, &payment_token_admin);
+
+    splitter.list_shares_for_sale(&seller_1, &1000, &100, &payment_token);
+    splitter.list_shares_for_sale(&seller_2, &1000, &100, &payment_token);
+    splitter.list_shares_for_sale(&seller_3, &1000, &100, &payment_token);
+    splitter.list_shares_for_sale(&seller_4, &1000, &100, &payment_token);
+
+    // Seller 1 and 3 expire, seller 2 is still live and seller 4 never expires
+    splitter.set_listing_expiration(&seller_1, &150);
+    splitter.set_listing_expiration(&seller_2, &300);
+    splitter.set_listing_expiration(&seller_3, &150);
+
+    env.ledger().set_sequence_number(200);
+
+    // Only the first two listings are scanned
+    assert_eq!(splitter.prune_expired_listings(&2), 1);
+    assert!(splitter.get_listing(&seller_1).is_none());
+    assert_eq!(splitter.list_all_sales().len(), 3);
+
+    assert_eq!(splitter.prune_expired_listings(&10), 1);
+    assert!(splitter.get_listing(&seller_3).is_none());
+
+    let listings = splitter.list_all_sales();
+    assert_eq!(listings.len(), 2);
+    assert!(listings.iter().any(|l| l.seller == seller_2));
+    assert!(listings.iter().any(|l| l.seller == seller_4));
+
+    // Nothing left to prune
+    assert_eq!(splitter.prune_expired_listings(&10), 0);
+}
+
+#[test]
+fn list_expired_listings() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let seller_1 = Address::generate(&env);
+    let seller_2 = Address::generate(&env);
+    let seller_3 = Address::generate(&env);
+    let seller_4 = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller_1.clone(),
+                share: 2500,
+            },
+            ShareDataKey {
+                shareholder: seller_2.clone(),
+                share: 2500,
+            },
+            ShareDataKey {
+                shareholder: seller_3.clone(),
+                share: 2500,
+            },
+            ShareDataKey {
+                shareholder: seller_4.clone(),
+                share: 2500,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, _, payment_token) = create_token(&env, &payment_token_admin);
+
+    splitter.list_shares_for_sale(&seller_1, &1000, &100, &payment_token);
+    splitter.list_shares_for_sale(&seller_2, &1000, &100, &payment_token);
+    splitter.list_shares_for_sale(&seller_3, &1000, &100, &payment_token);
+    splitter.list_shares_for_sale(&seller_4, &1000, &100, &payment_token);
+
+    // Seller 1 and 3 expire, seller 2 is still live and seller 4 never expires
+    splitter.set_listing_expiration(&seller_1, &150);
+    splitter.set_listing_expiration(&seller_2, &300);
+    splitter.set_listing_expiration(&seller_3, &150);
+
+    // Nothing expired yet
+    assert_eq!(splitter.list_expired_listings(&10).len(), 0);
+
+    env.ledger().set_sequence_number(200);
+
+    assert_eq!(
+        splitter.list_expired_listings(&10),
+        vec![&env, seller_1.clone(), seller_3.clone()]
+    );
+    assert_eq!(splitter.list_expired_listings(&1), vec![&env, seller_1.clone()]);
+
+    // Pruning removes them from the list
+    splitter.prune_expired_listings(&10);
+    assert_eq!(splitter.list_expired_listings(&10).len(), 0);
+}
+
+#[test]
+fn test_set_listing_expiration_without_listing() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    assert_eq!(
+        splitter.try_set_listing_expiration(&seller, &100),
+        Err(Ok(Error::NoActiveListing))
+    );
+}
+
+#[test]
+fn delayed_listing_is_activated_by_the_first_buy() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    splitter.list_shares_for_sale(&seller, &2000, &100, &payment_token);
+    splitter.set_listing_activation(&seller, &110);
+    assert_eq!(splitter.get_listing(&seller).unwrap().activation_ledger, 110);
+
+    // The listing can't be bought before the activation ledger
+    env.ledger().set_sequence_number(109);
+    assert_eq!(
+        splitter.try_buy_shares(&buyer, &seller, &1000),
+        Err(Ok(Error::NoActiveListing))
+    );
+
+    // The first buy announces the activation
+    env.ledger().set_sequence_number(110);
+    splitter.buy_shares(&buyer, &seller, &500);
+    let events = get_events_by_name(&env, &splitter_address, Symbol::new(&env, "listing_active"));
+    assert_eq!(events.len(), 1);
+    assert!(splitter.get_listing(&seller).unwrap().activation_emitted);
+
+    // Later buys don't announce it again
+    splitter.buy_shares(&buyer, &seller, &500);
+    let events = get_events_by_name(&env, &splitter_address, Symbol::new(&env, "listing_active"));
+    assert_eq!(events.len(), 0);
+
+    // Replacing the listing keeps the delay, which was already announced
+    splitter.list_shares_for_sale(&seller, &1000, &100, &payment_token);
+    let listing = splitter.get_listing(&seller).unwrap();
+    assert_eq!(listing.activation_ledger, 110);
+    assert!(listing.activation_emitted);
+}
+
+#[test]
+fn keeper_activates_delayed_listing() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    assert_eq!(
+        splitter.try_activate_listing(&seller),
+        Err(Ok(Error::NoActiveListing))
+    );
+
+    splitter.list_shares_for_sale(&seller, &2000, &100, &payment_token);
+    splitter.set_listing_activation(&seller, &110);
+
+    // Too early to activate
+    assert_eq!(
+        splitter.try_activate_listing(&seller),
+        Err(Ok(Error::NoActiveListing))
+    );
+
+    env.ledger().set_sequence_number(110);
+    splitter.activate_listing(&seller);
+    let events = get_events_by_name(&env, &splitter_address, Symbol::new(&env, "listing_active"));
+    assert_eq!(events.len(), 1);
+
+    // Activating again is a no-op
+    splitter.activate_listing(&seller);
+    let events = get_events_by_name(&env, &splitter_address, Symbol::new(&env, "listing_active"));
+    assert_eq!(events.len(), 0);
+
+    // The buy doesn't announce it again either
+    splitter.buy_shares(&buyer, &seller, &1000);
+    let events = get_events_by_name(&env, &splitter_address, Symbol::new(&env, "listing_active"));
+    assert_eq!(events.len(), 0);
+    assert_eq!(splitter.get_share(&buyer), Some(1000));
+}
@@ -1,9 +1,15 @@
-use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env,
+};
 
 use crate::{
     errors::Error,
     storage::ShareDataKey,
-    tests::helpers::{create_splitter_with_shares, create_token, get_default_share_data},
+    tests::helpers::{
+        create_splitter_with_shares, create_token, get_default_share_data,
+        setup_test_commission_recipient,
+    },
 };
 
 #[test]
@@ -212,3 +218,266 @@ fn update_existing_listing() {
     let listing = splitter.get_listing(&shareholder).unwrap();
     assert_eq!(listing.price_per_share, 150_000_000);
 }
+
+#[test]
+fn create_listing_happy_path() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let shareholder = share_data.get(0).unwrap().shareholder.clone();
+
+    let (splitter, _) = create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, _, payment_token_address) = create_token(&env, &payment_token_admin);
+
+    splitter.create_listing(&shareholder, &1000, &50_000_000, &payment_token_address);
+
+    let listing = splitter.get_listing(&shareholder).unwrap();
+    assert_eq!(listing.shares_for_sale, 1000);
+    assert_eq!(listing.price_per_share, 50_000_000);
+}
+
+#[test]
+fn test_create_listing_over_existing() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let shareholder = share_data.get(0).unwrap().shareholder.clone();
+
+    let (splitter, _) = create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, _, payment_token_address) = create_token(&env, &payment_token_admin);
+
+    splitter.create_listing(&shareholder, &1000, &50_000_000, &payment_token_address);
+
+    // Creating again must not silently replace the listing
+    assert_eq!(
+        splitter.try_create_listing(&shareholder, &2000, &75_000_000, &payment_token_address),
+        Err(Ok(Error::ListingAlreadyExists))
+    );
+    let listing = splitter.get_listing(&shareholder).unwrap();
+    assert_eq!(listing.shares_for_sale, 1000);
+    assert_eq!(listing.price_per_share, 50_000_000);
+
+    // The replace path still works
+    splitter.list_shares_for_sale(&shareholder, &2000, &75_000_000, &payment_token_address);
+    let listing = splitter.get_listing(&shareholder).unwrap();
+    assert_eq!(listing.shares_for_sale, 2000);
+    assert_eq!(listing.price_per_share, 75_000_000);
+}
+
+#[test]
+fn test_max_active_listings() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller_1 = Address::generate(&env);
+    let seller_2 = Address::generate(&env);
+    let seller_3 = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller_1.clone(),
+                share: 4000,
+            },
+            ShareDataKey {
+                shareholder: seller_2.clone(),
+                share: 3000,
+            },
+            ShareDataKey {
+                shareholder: seller_3.clone(),
+                share: 3000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, _, payment_token) = create_token(&env, &token_admin);
+
+    splitter.set_max_active_listings(&2);
+
+    // Fill the marketplace up to the limit
+    splitter.list_shares_for_sale(&seller_1, &1000, &100, &payment_token);
+    splitter.list_shares_for_sale(&seller_2, &1000, &100, &payment_token);
+
+    // A new seller can't list
+    assert_eq!(
+        splitter.try_list_shares_for_sale(&seller_3, &1000, &100, &payment_token),
+        Err(Ok(Error::TooManyListings))
+    );
+
+    // Existing sellers can still update their own listing
+    splitter.list_shares_for_sale(&seller_1, &2000, &150, &payment_token);
+    assert_eq!(splitter.get_listing(&seller_1).unwrap().shares_for_sale, 2000);
+
+    // A cancelled listing frees up room
+    splitter.cancel_listing(&seller_2);
+    splitter.list_shares_for_sale(&seller_3, &1000, &100, &payment_token);
+    assert_eq!(splitter.list_all_sales().len(), 2);
+
+    // Setting the limit to 0 disables it
+    splitter.set_max_active_listings(&0);
+    splitter.list_shares_for_sale(&seller_2, &1000, &100, &payment_token);
+    assert_eq!(splitter.list_all_sales().len(), 3);
+}
+
+#[test]
+fn test_max_active_listings_unauthorized() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) =
+        create_splitter_with_shares(&env, &admin, &get_default_share_data(&env), &true);
+
+    assert!(splitter.try_set_max_active_listings(&2).is_err());
+}
+
+#[test]
+fn test_list_locked_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let seller = share_data.get(0).unwrap().shareholder;
+
+    let (splitter, _) = create_splitter_with_shares(&env, &admin, &share_data, &true);
+    let payment_token = Address::generate(&env);
+
+    // All of the seller's 8050 shares are locked
+    splitter.lock_shares(&seller, &8050, &1000);
+    assert_eq!(
+        splitter.try_list_shares_for_sale(&seller, &1, &100, &payment_token),
+        Err(Ok(Error::SharesLocked))
+    );
+
+    // Only the unlocked part can be listed
+    splitter.lock_shares(&seller, &5000, &1000);
+    assert_eq!(
+        splitter.try_list_shares_for_sale(&seller, &3051, &100, &payment_token),
+        Err(Ok(Error::SharesLocked))
+    );
+    splitter.list_shares_for_sale(&seller, &3050, &100, &payment_token);
+    assert_eq!(splitter.get_listing(&seller).unwrap().shares_for_sale, 3050);
+
+    // Once the lock expires everything can be listed again
+    env.ledger().set_sequence_number(1000);
+    splitter.list_shares_for_sale(&seller, &8050, &100, &payment_token);
+    assert_eq!(splitter.get_listing(&seller).unwrap().shares_for_sale, 8050);
+}
+
+#[test]
+fn test_transfer_locked_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let sender = share_data.get(0).unwrap().shareholder;
+    let recipient = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    // Only the 3050 unlocked shares can be transferred
+    splitter.lock_shares(&sender, &5000, &1000);
+    assert_eq!(
+        splitter.try_transfer_shares(&sender, &recipient, &3051),
+        Err(Ok(Error::SharesLocked))
+    );
+    splitter.transfer_shares(&sender, &recipient, &3050);
+    assert_eq!(splitter.get_share(&sender), Some(5000));
+
+    // Once the lock expires the rest can be transferred too
+    env.ledger().set_sequence_number(1000);
+    splitter.transfer_shares(&sender, &recipient, &5000);
+    assert_eq!(splitter.get_share(&recipient), Some(8050));
+}
+
+#[test]
+fn test_get_effective_share() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let shareholder = share_data.get(0).unwrap().shareholder;
+
+    let (splitter, _) = create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    // Without a lock all of the shares are free
+    assert_eq!(splitter.get_effective_share(&shareholder), (8050, 0, 8050));
+
+    // Fully locked
+    splitter.lock_shares(&shareholder, &8050, &1000);
+    assert_eq!(splitter.get_effective_share(&shareholder), (8050, 8050, 0));
+
+    // Partially locked
+    splitter.lock_shares(&shareholder, &5000, &1000);
+    assert_eq!(splitter.get_effective_share(&shareholder), (8050, 5000, 3050));
+
+    // The lock no longer counts from the unlock ledger
+    env.ledger().set_sequence_number(1000);
+    assert_eq!(splitter.get_effective_share(&shareholder), (8050, 0, 8050));
+
+    // Non-shareholders have nothing
+    assert_eq!(
+        splitter.get_effective_share(&Address::generate(&env)),
+        (0, 0, 0)
+    );
+}
+
+#[test]
+fn test_blocked_seller() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let seller = share_data.get(0).unwrap().shareholder;
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, sudo_payment_token, payment_token) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_payment_token]);
+    sudo_payment_token.mint(&buyer, &1_000_000);
+
+    splitter.list_shares_for_sale(&seller, &5000, &100, &payment_token);
+    splitter.set_seller_blocked(&seller, &true);
+
+    // The blocked seller cannot list or replace their listing
+    assert_eq!(
+        splitter.try_list_shares_for_sale(&seller, &1000, &100, &payment_token),
+        Err(Ok(Error::SellerBlocked))
+    );
+    assert_eq!(
+        splitter.try_contribute_to_pool(&seller, &payment_token, &100, &1000),
+        Err(Ok(Error::SellerBlocked))
+    );
+
+    // The open listing can still be bought
+    splitter.buy_shares(&buyer, &seller, &2000);
+    assert_eq!(splitter.get_share(&buyer).unwrap(), 2000);
+    assert_eq!(splitter.get_listing(&seller).unwrap().shares_for_sale, 3000);
+
+    // Unblocked sellers can list again
+    splitter.set_seller_blocked(&seller, &false);
+    splitter.list_shares_for_sale(&seller, &1000, &100, &payment_token);
+    assert_eq!(splitter.get_listing(&seller).unwrap().shares_for_sale, 1000);
+}
@@ -0,0 +1,244 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env,
+};
+
+use crate::{
+    errors::Error,
+    storage::ShareDataKey,
+    tests::helpers::{create_splitter_with_shares, create_token, setup_test_commission_recipient},
+};
+
+#[test]
+fn buyer_fills_across_two_contributors() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contributor_1 = Address::generate(&env);
+    let contributor_2 = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: contributor_1.clone(),
+                share: 4000,
+            },
+            ShareDataKey {
+                shareholder: contributor_2.clone(),
+                share: 3000,
+            },
+            ShareDataKey {
+                shareholder: holder.clone(),
+                share: 3000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    let recipient = setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&contributor_1, &0);
+    payment_sudo_token.mint(&contributor_2, &0);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    splitter.contribute_to_pool(&contributor_1, &payment_token_address, &100, &1000);
+    splitter.contribute_to_pool(&contributor_2, &payment_token_address, &100, &2000);
+
+    // 1000 shares from the first contributor, then 1000 from the second
+    splitter.buy_from_pool(&buyer, &payment_token_address, &100, &2000);
+
+    // Each contributor receives 100_000 - 1.5% for their filled shares
+    assert_eq!(payment_token.balance(&contributor_1), 98_500);
+    assert_eq!(payment_token.balance(&contributor_2), 98_500);
+    assert_eq!(payment_token.balance(&recipient), 3_000);
+    assert_eq!(payment_token.balance(&buyer), 800_000);
+
+    assert_eq!(splitter.get_share(&contributor_1), Some(3000));
+    assert_eq!(splitter.get_share(&contributor_2), Some(2000));
+    assert_eq!(splitter.get_share(&buyer), Some(2000));
+
+    // Only the second contributor's remaining 1000 shares are left
+    assert_eq!(
+        splitter.try_buy_from_pool(&buyer, &payment_token_address, &100, &2000),
+        Err(Ok(Error::InsufficientSharesInListing))
+    );
+
+    // Leaving the pool removes the remaining contribution
+    splitter.leave_pool(&contributor_2, &payment_token_address, &100);
+    assert_eq!(
+        splitter.try_buy_from_pool(&buyer, &payment_token_address, &100, &1000),
+        Err(Ok(Error::NoActiveListing))
+    );
+    assert_eq!(
+        splitter.try_leave_pool(&contributor_2, &payment_token_address, &100),
+        Err(Ok(Error::NoActiveListing))
+    );
+}
+
+#[test]
+fn shares_no_longer_owned_are_dropped_from_the_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contributor_1 = Address::generate(&env);
+    let contributor_2 = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: contributor_1.clone(),
+                share: 5000,
+            },
+            ShareDataKey {
+                shareholder: contributor_2.clone(),
+                share: 5000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&contributor_1, &0);
+    payment_sudo_token.mint(&contributor_2, &0);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    // Contributors cannot pool more than they own
+    assert_eq!(
+        splitter.try_contribute_to_pool(&contributor_1, &payment_token_address, &100, &5001),
+        Err(Ok(Error::NoSharesToSell))
+    );
+
+    splitter.contribute_to_pool(&contributor_1, &payment_token_address, &100, &2000);
+    splitter.contribute_to_pool(&contributor_2, &payment_token_address, &100, &2000);
+
+    // The first contributor keeps only 500 of the pooled shares
+    splitter.transfer_shares(&contributor_1, &contributor_2, &4500);
+
+    splitter.buy_from_pool(&buyer, &payment_token_address, &100, &1500);
+    assert_eq!(payment_token.balance(&contributor_1), 49_250);
+    assert_eq!(payment_token.balance(&contributor_2), 98_500);
+    assert_eq!(splitter.get_share(&contributor_1), None);
+    assert_eq!(splitter.get_share(&buyer), Some(1500));
+}
+
+#[test]
+fn locked_shares_are_not_pooled_or_filled() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let contributor = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: contributor.clone(),
+                share: 4000,
+            },
+            ShareDataKey {
+                shareholder: holder.clone(),
+                share: 6000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token_address) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&contributor, &0);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    // Locked shares can't be pooled
+    splitter.lock_shares(&contributor, &3000, &1000);
+    assert_eq!(
+        splitter.try_contribute_to_pool(&contributor, &payment_token_address, &100, &2000),
+        Err(Ok(Error::SharesLocked))
+    );
+
+    // Pooled shares that got locked afterwards can't be filled
+    splitter.lock_shares(&contributor, &0, &0);
+    splitter.contribute_to_pool(&contributor, &payment_token_address, &100, &2000);
+    splitter.lock_shares(&contributor, &3000, &1000);
+    assert_eq!(
+        splitter.try_buy_from_pool(&buyer, &payment_token_address, &100, &2000),
+        Err(Ok(Error::InsufficientSharesInListing))
+    );
+
+    // The free 1000 still are
+    splitter.buy_from_pool(&buyer, &payment_token_address, &100, &1000);
+    assert_eq!(splitter.get_share(&contributor), Some(3000));
+    assert_eq!(splitter.get_share(&buyer), Some(1000));
+}
+
+#[test]
+fn commission_cant_eat_contributor_proceeds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: contributor.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000);
+
+    // At the maximum 50% buy commission half of every payment goes to the recipient
+    splitter.set_buy_commission_rate(&5000);
+    splitter.set_min_seller_proceeds(&payment_token_address, &2);
+    splitter.contribute_to_pool(&contributor, &payment_token_address, &2, &100);
+
+    // Total price 2, commission 1, so the contributor would only receive 1
+    assert_eq!(
+        splitter.try_buy_from_pool(&buyer, &payment_token_address, &2, &1),
+        Err(Ok(Error::SellerProceedsTooLow))
+    );
+    assert_eq!(splitter.get_share(&buyer), None);
+
+    // Total price 4, commission 2, the contributor receives the minimum
+    splitter.buy_from_pool(&buyer, &payment_token_address, &2, &2);
+    assert_eq!(payment_token.balance(&contributor), 2);
+    assert_eq!(splitter.get_share(&buyer), Some(2));
+}
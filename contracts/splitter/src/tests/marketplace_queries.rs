@@ -1,7 +1,11 @@
-use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env,
+};
 
 use crate::{
-    storage::ShareDataKey,
+    errors::Error,
+    storage::{ShareDataKey, MAX_BATCH_LISTINGS, MAX_PRICE_HISTORY},
     tests::helpers::{create_splitter_with_shares, create_token, setup_test_commission_recipient},
 };
 
@@ -83,6 +87,100 @@ fn get_listing_returns_none_for_non_seller() {
     assert!(listing.is_none());
 }
 
+#[test]
+fn get_listing_total_happy_path() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_address = Address::generate(&env);
+
+    assert_eq!(splitter.get_listing_total(&seller), None);
+
+    splitter.list_shares_for_sale(&seller, &5000, &100_000_000, &payment_token_address);
+    assert_eq!(splitter.get_listing_total(&seller), Some(500_000_000_000));
+}
+
+#[test]
+fn get_listing_payment_token_happy_path() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_address = Address::generate(&env);
+
+    assert_eq!(splitter.get_listing_payment_token(&seller), None);
+
+    splitter.list_shares_for_sale(&seller, &5000, &100_000_000, &payment_token_address);
+    assert_eq!(
+        splitter.get_listing_payment_token(&seller),
+        Some(payment_token_address)
+    );
+
+    splitter.cancel_listing(&seller);
+    assert_eq!(splitter.get_listing_payment_token(&seller), None);
+}
+
+#[test]
+fn get_listing_total_overflow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_address = Address::generate(&env);
+
+    splitter.list_shares_for_sale(&seller, &5000, &(i128::MAX / 2), &payment_token_address);
+    assert_eq!(
+        splitter.try_get_listing_total(&seller),
+        Err(Ok(Error::Overflow))
+    );
+}
+
 #[test]
 fn list_all_sales_empty() {
     let env = Env::default();
@@ -355,3 +453,379 @@ fn list_all_sales_after_cancel() {
     assert_eq!(all_listings.len(), 1);
     assert_eq!(all_listings.get(0).unwrap().seller, seller_2);
 }
+
+#[test]
+fn get_price_history_records_updates() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, _, payment_token_address) = create_token(&env, &payment_token_admin);
+
+    assert_eq!(splitter.get_price_history(&seller).len(), 0);
+
+    env.ledger().set_sequence_number(10);
+    splitter.list_shares_for_sale(&seller, &1000, &100, &payment_token_address);
+    env.ledger().set_sequence_number(20);
+    splitter.list_shares_for_sale(&seller, &1000, &150, &payment_token_address);
+    env.ledger().set_sequence_number(30);
+    splitter.list_shares_for_sale(&seller, &1000, &120, &payment_token_address);
+
+    assert_eq!(
+        splitter.get_price_history(&seller),
+        vec![&env, (10u32, 100i128), (20u32, 150i128), (30u32, 120i128)]
+    );
+}
+
+#[test]
+fn get_price_history_trims_to_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, _, payment_token_address) = create_token(&env, &payment_token_admin);
+
+    // List 12 times, 2 more than the history keeps
+    for i in 1..=12u32 {
+        env.ledger().set_sequence_number(i);
+        splitter.list_shares_for_sale(&seller, &1000, &(i as i128 * 10), &payment_token_address);
+    }
+
+    let history = splitter.get_price_history(&seller);
+    assert_eq!(history.len(), MAX_PRICE_HISTORY);
+    // The two oldest entries were dropped
+    assert_eq!(history.first().unwrap(), (3u32, 30i128));
+    assert_eq!(history.last().unwrap(), (12u32, 120i128));
+}
+
+#[test]
+fn get_buyer_spent_accumulates() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller_1 = Address::generate(&env);
+    let seller_2 = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller_1.clone(),
+                share: 6000,
+            },
+            ShareDataKey {
+                shareholder: seller_2.clone(),
+                share: 4000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token_address) = create_token(&env, &payment_token_admin);
+    let (_, _, other_token_address) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000_000);
+
+    assert_eq!(splitter.get_buyer_spent(&buyer, &payment_token_address), 0);
+
+    splitter.list_shares_for_sale(&seller_1, &1000, &100, &payment_token_address);
+    splitter.list_shares_for_sale(&seller_2, &500, &300, &payment_token_address);
+
+    // 1000 * 100 = 100_000
+    splitter.buy_shares(&buyer, &seller_1, &1000);
+    // 500 * 300 = 150_000
+    splitter.buy_shares(&buyer, &seller_2, &500);
+
+    assert_eq!(splitter.get_buyer_spent(&buyer, &payment_token_address), 250_000);
+    assert_eq!(splitter.get_buyer_spent(&buyer, &other_token_address), 0);
+}
+
+#[test]
+fn list_sales_by_token_filters_listings() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller_1 = Address::generate(&env);
+    let seller_2 = Address::generate(&env);
+    let seller_3 = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller_1.clone(),
+                share: 4000,
+            },
+            ShareDataKey {
+                shareholder: seller_2.clone(),
+                share: 3000,
+            },
+            ShareDataKey {
+                shareholder: seller_3.clone(),
+                share: 3000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin_1 = Address::generate(&env);
+    let (_, _, payment_token_1) = create_token(&env, &payment_token_admin_1);
+
+    let payment_token_admin_2 = Address::generate(&env);
+    let (_, _, payment_token_2) = create_token(&env, &payment_token_admin_2);
+
+    splitter.list_shares_for_sale(&seller_1, &1000, &100, &payment_token_1);
+    splitter.list_shares_for_sale(&seller_2, &1000, &200, &payment_token_2);
+    splitter.list_shares_for_sale(&seller_3, &1000, &300, &payment_token_1);
+
+    let token_1_listings = splitter.list_sales_by_token(&payment_token_1);
+    assert_eq!(token_1_listings.len(), 2);
+    assert!(token_1_listings.iter().all(|l| l.payment_token == payment_token_1));
+    assert!(token_1_listings.iter().any(|l| l.seller == seller_1));
+    assert!(token_1_listings.iter().any(|l| l.seller == seller_3));
+
+    let token_2_listings = splitter.list_sales_by_token(&payment_token_2);
+    assert_eq!(token_2_listings.len(), 1);
+    assert_eq!(token_2_listings.get(0).unwrap().seller, seller_2);
+
+    // Replacing a listing with another payment token moves it to the other index
+    splitter.list_shares_for_sale(&seller_2, &1000, &200, &payment_token_1);
+    assert_eq!(splitter.list_sales_by_token(&payment_token_1).len(), 3);
+    assert!(splitter.list_sales_by_token(&payment_token_2).is_empty());
+}
+
+#[test]
+fn list_sales_by_token_after_cancel_and_purchase() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller_1 = Address::generate(&env);
+    let seller_2 = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller_1.clone(),
+                share: 5000,
+            },
+            ShareDataKey {
+                shareholder: seller_2.clone(),
+                share: 5000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    splitter.list_shares_for_sale(&seller_1, &1000, &100, &payment_token);
+    splitter.list_shares_for_sale(&seller_2, &1000, &100, &payment_token);
+    assert_eq!(splitter.list_sales_by_token(&payment_token).len(), 2);
+
+    // Cancelled listings are removed from the index
+    splitter.cancel_listing(&seller_1);
+    let listings = splitter.list_sales_by_token(&payment_token);
+    assert_eq!(listings.len(), 1);
+    assert_eq!(listings.get(0).unwrap().seller, seller_2);
+
+    // Fully bought listings are removed from the index
+    splitter.buy_shares(&buyer, &seller_2, &1000);
+    assert!(splitter.list_sales_by_token(&payment_token).is_empty());
+}
+
+#[test]
+fn list_all_sales_detailed_after_partial_sale() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    splitter.list_shares_for_sale(&seller, &5000, &100, &payment_token_address);
+    splitter.buy_shares(&buyer, &seller, &2000);
+
+    let listings = splitter.list_all_sales_detailed();
+    assert_eq!(listings.len(), 1);
+
+    let (listing, seller_shares) = listings.get(0).unwrap();
+    assert_eq!(listing.seller, seller);
+    assert_eq!(listing.shares_for_sale, 3000);
+    assert_eq!(seller_shares, 6050);
+}
+
+#[test]
+fn get_listing_fill_after_partial_sales() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    assert_eq!(splitter.get_listing_fill(&seller), None);
+
+    splitter.list_shares_for_sale(&seller, &5000, &100, &payment_token_address);
+    assert_eq!(splitter.get_listing_fill(&seller), Some((5000, 5000)));
+
+    splitter.buy_shares(&buyer, &seller, &2000);
+    assert_eq!(splitter.get_listing_fill(&seller), Some((5000, 3000)));
+
+    splitter.buy_shares(&buyer, &seller, &1000);
+    assert_eq!(splitter.get_listing_fill(&seller), Some((5000, 2000)));
+
+    // Filling the rest removes the listing
+    splitter.buy_shares(&buyer, &seller, &2000);
+    assert_eq!(splitter.get_listing_fill(&seller), None);
+}
+
+#[test]
+fn get_listings_for_sellers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller_1 = Address::generate(&env);
+    let seller_2 = Address::generate(&env);
+    let not_listed = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller_1.clone(),
+                share: 5000,
+            },
+            ShareDataKey {
+                shareholder: seller_2.clone(),
+                share: 3000,
+            },
+            ShareDataKey {
+                shareholder: not_listed.clone(),
+                share: 2000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token = Address::generate(&env);
+    splitter.list_shares_for_sale(&seller_1, &1000, &100, &payment_token);
+    splitter.list_shares_for_sale(&seller_2, &2000, &200, &payment_token);
+
+    let listings = splitter.get_listings(&vec![
+        &env,
+        seller_2.clone(),
+        not_listed.clone(),
+        seller_1.clone(),
+    ]);
+    assert_eq!(listings.len(), 3);
+    assert_eq!(listings.get(0).unwrap().unwrap().shares_for_sale, 2000);
+    assert_eq!(listings.get(1).unwrap(), None);
+    assert_eq!(listings.get(2).unwrap().unwrap().shares_for_sale, 1000);
+
+    // The lookup is bounded
+    let mut sellers = vec![&env];
+    for _ in 0..=MAX_BATCH_LISTINGS {
+        sellers.push_back(Address::generate(&env));
+    }
+    assert_eq!(
+        splitter.try_get_listings(&sellers),
+        Err(Ok(Error::TooManyItems))
+    );
+}
@@ -0,0 +1,115 @@
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, IntoVal, Map, Symbol, Val};
+
+use crate::{
+    errors::Error,
+    storage::{ConfigDataKey, DataKey, CONTRACT_VERSION},
+    tests::helpers::{
+        create_splitter, create_splitter_with_default_shares, create_splitter_with_shares,
+        create_token, get_default_share_data, setup_test_commission_recipient,
+    },
+};
+
+#[test]
+fn version_is_set_on_init() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_default_shares(&env, &admin);
+
+    assert_eq!(splitter.get_version(), CONTRACT_VERSION);
+}
+
+#[test]
+fn happy_path() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_default_shares(&env, &admin);
+
+    // Simulate a contract initialized before versioning was introduced
+    env.as_contract(&splitter_address, || {
+        ConfigDataKey::save_version(&env, 0);
+    });
+    assert_eq!(splitter.get_version(), 0);
+
+    splitter.migrate();
+    assert_eq!(splitter.get_version(), CONTRACT_VERSION);
+
+    // Migrating again keeps the current version
+    splitter.migrate();
+    assert_eq!(splitter.get_version(), CONTRACT_VERSION);
+}
+
+#[test]
+fn listings_of_version_1_are_migrated() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let seller = share_data.get(0).unwrap().shareholder;
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+    sudo_token.mint(&seller, &0);
+    sudo_token.mint(&buyer, &1_000_000);
+
+    // A listing stored in the version 1 layout
+    env.as_contract(&splitter_address, || {
+        let mut listing: Map<Symbol, Val> = Map::new(&env);
+        listing.set(Symbol::new(&env, "seller"), seller.into_val(&env));
+        listing.set(Symbol::new(&env, "shares_for_sale"), 1000_i128.into_val(&env));
+        listing.set(Symbol::new(&env, "price_per_share"), 100_i128.into_val(&env));
+        listing.set(Symbol::new(&env, "payment_token"), token_address.into_val(&env));
+        listing.set(Symbol::new(&env, "allowed_buyers"), vec![&env, buyer.clone()].into_val(&env));
+        listing.set(Symbol::new(&env, "expiration_ledger"), 0_u32.into_val(&env));
+        env.storage()
+            .persistent()
+            .set(&DataKey::SaleListing(seller.clone()), &listing);
+        env.storage()
+            .persistent()
+            .set(&DataKey::ActiveListings, &vec![&env, seller.clone()]);
+        ConfigDataKey::save_version(&env, 1);
+    });
+
+    splitter.migrate();
+    assert_eq!(splitter.get_version(), CONTRACT_VERSION);
+
+    // The stored fields are kept, the new ones get their defaults
+    let listing = splitter.get_listing(&seller).unwrap();
+    assert_eq!(listing.shares_for_sale, 1000);
+    assert_eq!(listing.original_shares, 1000);
+    assert_eq!(listing.allowed_buyers, vec![&env, buyer.clone()]);
+    assert_eq!(listing.max_per_buyer, 0);
+    assert_eq!(listing.fee_token, token_address);
+    assert!(listing.listing_id > 0);
+
+    // The migrated listing can be bought, also by its new id
+    splitter.buy_shares_by_id(&buyer, &listing.listing_id, &500);
+    assert_eq!(splitter.get_share(&buyer), Some(500));
+    assert_eq!(splitter.get_listing(&seller).unwrap().shares_for_sale, 500);
+}
+
+#[test]
+fn test_not_initialized() {
+    let env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    assert_eq!(splitter.try_get_version(), Err(Ok(Error::NotInitialized)));
+    assert_eq!(splitter.try_migrate(), Err(Ok(Error::NotInitialized)));
+}
+
+#[test]
+fn test_unauthorized() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_default_shares(&env, &admin);
+
+    assert!(splitter.try_migrate().is_err());
+}
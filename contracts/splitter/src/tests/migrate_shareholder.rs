@@ -0,0 +1,307 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env,
+};
+
+use crate::{
+    errors::Error,
+    storage::ShareDataKey,
+    tests::helpers::{create_splitter, create_splitter_with_shares, create_token, setup_test_commission_recipient},
+};
+
+#[test]
+fn happy_path() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let old = Address::generate(&env);
+    let other = Address::generate(&env);
+    let new = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: old.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: other.clone(),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (token_1, sudo_token_1, token_address_1) = create_token(&env, &token_admin);
+    let (_, sudo_token_2, token_address_2) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token_1, &sudo_token_2]);
+
+    // old: 995_000_000 * 8050 / 10000 = 800_975_000 in each token
+    sudo_token_1.mint(&splitter_address, &1_000_000_000);
+    sudo_token_2.mint(&splitter_address, &1_000_000_000);
+    splitter.distribute_tokens(&token_address_1);
+    splitter.distribute_tokens(&token_address_2);
+
+    splitter.migrate_shareholder(&old, &new);
+
+    // Shares moved and the shareholder keeps their position in the list
+    assert_eq!(splitter.get_share(&old), None);
+    assert_eq!(splitter.get_share(&new), Some(8050));
+    assert_eq!(
+        splitter.list_shares(),
+        vec![
+            &env,
+            ShareDataKey {
+                shareholder: new.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: other.clone(),
+                share: 1950,
+            },
+        ]
+    );
+
+    // Allocations moved in both tokens
+    assert_eq!(splitter.get_allocation(&old, &token_address_1), 0);
+    assert_eq!(splitter.get_allocation(&old, &token_address_2), 0);
+    assert_eq!(splitter.get_allocation(&new, &token_address_1), 800_975_000);
+    assert_eq!(splitter.get_allocation(&new, &token_address_2), 800_975_000);
+
+    // The new address can withdraw
+    splitter.withdraw_allocation(&token_address_1, &new, &800_975_000);
+    assert_eq!(token_1.balance(&new), 800_975_000);
+
+    // Total allocation tracking is untouched, so a new distribution has nothing to allocate
+    splitter.distribute_tokens(&token_address_2);
+    assert_eq!(splitter.get_allocation(&new, &token_address_2), 800_975_000);
+}
+
+#[test]
+fn test_new_address_is_shareholder() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let old = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: old.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: other.clone(),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    assert_eq!(
+        splitter.try_migrate_shareholder(&old, &other),
+        Err(Ok(Error::ShareholderExists))
+    );
+}
+
+#[test]
+fn test_old_address_not_shareholder() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    assert_eq!(
+        splitter.try_migrate_shareholder(&Address::generate(&env), &Address::generate(&env)),
+        Err(Ok(Error::NoSharesToTransfer))
+    );
+}
+
+#[test]
+fn test_not_initialized() {
+    let env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    assert_eq!(
+        splitter.try_migrate_shareholder(&Address::generate(&env), &Address::generate(&env)),
+        Err(Ok(Error::NotInitialized))
+    );
+}
+
+#[test]
+fn test_unauthorized() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+    let old = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: old.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    assert!(splitter
+        .try_migrate_shareholder(&old, &Address::generate(&env))
+        .is_err());
+}
+
+#[test]
+fn test_locked_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let old = Address::generate(&env);
+    let new = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: old.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    splitter.lock_shares(&old, &1000, &200);
+    assert_eq!(
+        splitter.try_migrate_shareholder(&old, &new),
+        Err(Ok(Error::SharesLocked))
+    );
+
+    // Once the lock expired the shareholder can be migrated
+    env.ledger().set_sequence_number(200);
+    splitter.migrate_shareholder(&old, &new);
+    assert_eq!(splitter.get_share(&new), Some(10000));
+}
+
+#[test]
+fn test_active_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let new = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: buyer.clone(),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+    sudo_token.mint(&buyer, &1_000_000);
+
+    splitter.list_shares_for_sale(&seller, &1000, &100, &token_address);
+    splitter.deposit_for_purchase(&buyer, &seller, &1000);
+
+    // Neither the seller nor the buyer of the escrowed purchase can be migrated
+    assert_eq!(
+        splitter.try_migrate_shareholder(&seller, &new),
+        Err(Ok(Error::EscrowActive))
+    );
+    assert_eq!(
+        splitter.try_migrate_shareholder(&buyer, &new),
+        Err(Ok(Error::EscrowActive))
+    );
+
+    // Once the purchase is released the buyer can be migrated and keeps the bought shares
+    splitter.release_purchase(&seller, &buyer);
+    splitter.migrate_shareholder(&buyer, &new);
+    assert_eq!(splitter.get_share(&new), Some(2950));
+}
+
+#[test]
+fn share_history_moves_to_the_new_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let old = Address::generate(&env);
+    let other = Address::generate(&env);
+    let new = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: old.clone(),
+                share: 8000,
+            },
+            ShareDataKey {
+                shareholder: other.clone(),
+                share: 2000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    env.ledger().set_sequence_number(150);
+    splitter.migrate_shareholder(&old, &new);
+
+    // The new address holds the share from before the migration on
+    assert_eq!(splitter.get_share_at(&new, &120), 8000);
+    assert_eq!(splitter.get_share_at(&old, &120), 0);
+
+    // And the weight the old address accumulated counts for it
+    env.ledger().set_sequence_number(200);
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_time_weighted(&token_address);
+
+    // 1_000_000 - 0.5% = 995_000, split 80/20
+    assert_eq!(splitter.get_allocation(&new, &token_address), 796_000);
+    assert_eq!(splitter.get_allocation(&other, &token_address), 199_000);
+}
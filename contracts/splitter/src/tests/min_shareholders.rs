@@ -0,0 +1,186 @@
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::ShareDataKey,
+    tests::helpers::{create_splitter_with_shares, create_token, setup_test_commission_recipient},
+};
+
+#[test]
+fn transfer_blocked_below_minimum() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder1 = Address::generate(&env);
+    let shareholder2 = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder1.clone(),
+                share: 6000,
+            },
+            ShareDataKey {
+                shareholder: shareholder2.clone(),
+                share: 4000,
+            },
+        ],
+        &true,
+    );
+
+    splitter.set_min_shareholders(&2);
+
+    // Transferring everything to the other shareholder would leave a single shareholder
+    assert_eq!(
+        splitter.try_transfer_shares(&shareholder1, &shareholder2, &6000),
+        Err(Ok(Error::TooFewShareholders))
+    );
+
+    // Partial transfers keep both shareholders
+    splitter.transfer_shares(&shareholder1, &shareholder2, &1000);
+    assert_eq!(splitter.get_share(&shareholder1), Some(5000));
+    assert_eq!(splitter.get_share(&shareholder2), Some(5000));
+}
+
+#[test]
+fn transfer_to_new_shareholder_keeps_count() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder1 = Address::generate(&env);
+    let shareholder2 = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder1.clone(),
+                share: 6000,
+            },
+            ShareDataKey {
+                shareholder: shareholder2.clone(),
+                share: 4000,
+            },
+        ],
+        &true,
+    );
+
+    splitter.set_min_shareholders(&2);
+
+    // The sender is replaced by the recipient, so the count stays at 2
+    splitter.transfer_shares(&shareholder1, &recipient, &6000);
+    assert_eq!(splitter.get_share(&shareholder1), None);
+    assert_eq!(splitter.get_share(&recipient), Some(6000));
+    assert_eq!(splitter.list_shares().len(), 2);
+}
+
+#[test]
+fn buy_blocked_below_minimum() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 3000,
+            },
+            ShareDataKey {
+                shareholder: buyer.clone(),
+                share: 7000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (payment_token, payment_sudo_token, payment_token_address) =
+        create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    splitter.set_min_shareholders(&2);
+    splitter.list_shares_for_sale(&seller, &3000, &100, &payment_token_address);
+
+    // Buying the seller out would leave the buyer as the only shareholder
+    assert_eq!(
+        splitter.try_buy_shares(&buyer, &seller, &3000),
+        Err(Ok(Error::TooFewShareholders))
+    );
+
+    // No payment was taken and the shares did not move
+    assert_eq!(payment_token.balance(&buyer), 1_000_000);
+    assert_eq!(splitter.get_share(&seller), Some(3000));
+
+    // A partial buy keeps the seller in the set
+    splitter.buy_shares(&buyer, &seller, &1000);
+    assert_eq!(splitter.get_share(&seller), Some(2000));
+    assert_eq!(splitter.get_share(&buyer), Some(8000));
+}
+
+#[test]
+fn disabled_by_default() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder1 = Address::generate(&env);
+    let shareholder2 = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder1.clone(),
+                share: 6000,
+            },
+            ShareDataKey {
+                shareholder: shareholder2.clone(),
+                share: 4000,
+            },
+        ],
+        &true,
+    );
+
+    splitter.transfer_shares(&shareholder1, &shareholder2, &6000);
+    assert_eq!(splitter.get_share(&shareholder2), Some(10000));
+    assert_eq!(splitter.list_shares().len(), 1);
+}
+
+#[test]
+fn test_unauthorized() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    assert!(splitter.try_set_min_shareholders(&2).is_err());
+}
@@ -0,0 +1,49 @@
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::ShareDataKey,
+    tests::helpers::{create_splitter, create_splitter_with_default_shares},
+};
+
+#[test]
+fn happy_path() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_default_shares(&env, &admin);
+    let get_shareholders =
+        || env.as_contract(&splitter_address, || ShareDataKey::get_shareholders(&env));
+    let shareholders = get_shareholders();
+
+    // Inject a dangling entry without a share and a duplicated entry
+    let dangling = Address::generate(&env);
+    env.as_contract(&splitter_address, || {
+        let mut injected = shareholders.clone();
+        injected.push_back(dangling.clone());
+        injected.push_back(shareholders.get(0).unwrap());
+        ShareDataKey::save_shareholders(&env, injected);
+    });
+    assert_eq!(get_shareholders().len(), shareholders.len() + 2);
+    assert!(!splitter.verify_integrity());
+
+    assert_eq!(splitter.rebuild_shareholders(), 2);
+    assert_eq!(get_shareholders(), shareholders);
+    assert!(splitter.verify_integrity());
+
+    // Nothing left to prune
+    assert_eq!(splitter.rebuild_shareholders(), 0);
+    assert_eq!(splitter.list_shares().len(), shareholders.len());
+}
+
+#[test]
+fn test_not_initialized() {
+    let env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    assert_eq!(
+        splitter.try_rebuild_shareholders(),
+        Err(Ok(Error::NotInitialized))
+    );
+}
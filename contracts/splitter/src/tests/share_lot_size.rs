@@ -0,0 +1,103 @@
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+use crate::{
+    errors::Error,
+    storage::ShareDataKey,
+    tests::helpers::{
+        create_splitter, create_splitter_with_default_shares, create_splitter_with_shares,
+        create_token, setup_test_commission_recipient,
+    },
+};
+
+#[test]
+fn trades_must_be_whole_lots() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token_admin = Address::generate(&env);
+    let (_, payment_sudo_token, payment_token) = create_token(&env, &payment_token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&payment_sudo_token]);
+    payment_sudo_token.mint(&buyer, &1_000_000);
+
+    splitter.set_share_lot_size(&100);
+
+    // Listing a partial lot is rejected
+    assert_eq!(
+        splitter.try_list_shares_for_sale(&seller, &250, &100, &payment_token),
+        Err(Ok(Error::InvalidShareGranularity))
+    );
+    splitter.list_shares_for_sale(&seller, &300, &100, &payment_token);
+
+    // Buying a partial lot is rejected
+    assert_eq!(
+        splitter.try_buy_shares(&buyer, &seller, &150),
+        Err(Ok(Error::InvalidShareGranularity))
+    );
+    splitter.buy_shares(&buyer, &seller, &200);
+    assert_eq!(splitter.get_share(&buyer), Some(200));
+
+    // Transferring a partial lot is rejected
+    assert_eq!(
+        splitter.try_transfer_shares(&buyer, &seller, &50),
+        Err(Ok(Error::InvalidShareGranularity))
+    );
+    splitter.transfer_shares(&buyer, &seller, &100);
+    assert_eq!(splitter.get_share(&buyer), Some(100));
+    assert_eq!(splitter.get_share(&seller), Some(9900));
+}
+
+#[test]
+fn test_invalid_share_lot_size() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_default_shares(&env, &admin);
+
+    assert_eq!(
+        splitter.try_set_share_lot_size(&0),
+        Err(Ok(Error::InvalidShareAmount))
+    );
+    assert_eq!(
+        splitter.try_set_share_lot_size(&10001),
+        Err(Ok(Error::InvalidShareAmount))
+    );
+}
+
+#[test]
+fn test_not_initialized() {
+    let env = Env::default();
+    let (splitter, _) = create_splitter(&env);
+
+    assert_eq!(
+        splitter.try_set_share_lot_size(&100),
+        Err(Ok(Error::NotInitialized))
+    );
+}
+
+#[test]
+fn test_unauthorized() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_default_shares(&env, &admin);
+
+    assert!(splitter.try_set_share_lot_size(&100).is_err());
+}
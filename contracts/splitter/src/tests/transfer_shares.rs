@@ -1,9 +1,16 @@
-use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    vec, Address, Env, IntoVal,
+};
 
 use crate::{
     errors::Error,
     storage::ShareDataKey,
-    tests::helpers::create_splitter_with_shares,
+    tests::helpers::{
+        create_splitter_with_shares, create_token, get_events_by_name,
+        setup_test_commission_recipient,
+    },
 };
 
 #[test]
@@ -233,3 +240,298 @@ fn fail_invalid_amount() {
     let result = client.try_transfer_shares(&shareholder1, &recipient, &-100);
     assert_eq!(result, Err(Ok(Error::InvalidShareAmount)));
 }
+
+#[test]
+fn transfer_event_includes_resulting_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder1 = Address::generate(&env);
+    let shareholder2 = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder1.clone(),
+                share: 6000,
+            },
+            ShareDataKey {
+                shareholder: shareholder2.clone(),
+                share: 4000,
+            },
+        ],
+        &true,
+    );
+
+    // Partial transfer to a new shareholder
+    splitter.transfer_shares(&shareholder1, &recipient, &1000);
+
+    let events = get_events_by_name(&env, &splitter_address, symbol_short!("transfer"));
+    assert_eq!(events.len(), 1);
+    let (topics, data) = events.get(0).unwrap();
+    let from: Address = topics.get(1).unwrap().into_val(&env);
+    let to: Address = topics.get(2).unwrap().into_val(&env);
+    let data: (i128, i128, i128) = data.into_val(&env);
+    assert_eq!(from, shareholder1);
+    assert_eq!(to, recipient);
+    assert_eq!(data, (1000, 5000, 1000));
+
+    // Full transfer to an existing shareholder removes the sender
+    splitter.transfer_shares(&shareholder2, &recipient, &4000);
+
+    let events = get_events_by_name(&env, &splitter_address, symbol_short!("transfer"));
+    assert_eq!(events.len(), 1);
+    let (_, data) = events.get(0).unwrap();
+    let data: (i128, i128, i128) = data.into_val(&env);
+    assert_eq!(data, (4000, 0, 5000));
+}
+
+#[test]
+fn consolidate_to_self_is_a_no_op() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder1 = Address::generate(&env);
+    let shareholder2 = Address::generate(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder1.clone(),
+                share: 6000,
+            },
+            ShareDataKey {
+                shareholder: shareholder2.clone(),
+                share: 4000,
+            },
+        ],
+        &true,
+    );
+
+    splitter.consolidate_shares(&shareholder1, &shareholder1, &1000);
+
+    // No events and no share changes
+    let events = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(address, _, _)| *address == splitter_address)
+        .count();
+    assert_eq!(events, 0);
+    assert_eq!(splitter.get_share(&shareholder1), Some(6000));
+    assert_eq!(splitter.get_share(&shareholder2), Some(4000));
+
+    // Transfers to others behave like transfer_shares
+    splitter.consolidate_shares(&shareholder1, &shareholder2, &1000);
+    assert_eq!(get_events_by_name(&env, &splitter_address, symbol_short!("transfer")).len(), 1);
+    assert_eq!(splitter.get_share(&shareholder1), Some(5000));
+    assert_eq!(splitter.get_share(&shareholder2), Some(5000));
+
+    // The strict version still rejects transfers to self
+    assert_eq!(
+        splitter.try_transfer_shares(&shareholder1, &shareholder1, &1000),
+        Err(Ok(Error::CannotTransferToSelf))
+    );
+}
+
+#[test]
+fn test_transfer_cooldown() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let shareholder1 = Address::generate(&env);
+    let shareholder2 = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder1.clone(),
+                share: 6000,
+            },
+            ShareDataKey {
+                shareholder: shareholder2.clone(),
+                share: 4000,
+            },
+        ],
+        &true,
+    );
+
+    splitter.set_transfer_cooldown(&50);
+
+    splitter.transfer_shares(&shareholder1, &shareholder2, &1000);
+
+    // Within the cooldown the sender cannot transfer again
+    env.ledger().set_sequence_number(149);
+    assert_eq!(
+        splitter.try_transfer_shares(&shareholder1, &shareholder2, &1000),
+        Err(Ok(Error::TransferCooldownActive))
+    );
+
+    // The cooldown only applies to the sender
+    splitter.transfer_shares(&shareholder2, &shareholder1, &500);
+
+    // Outside the cooldown the transfer goes through
+    env.ledger().set_sequence_number(150);
+    splitter.transfer_shares(&shareholder1, &shareholder2, &1000);
+    assert_eq!(splitter.get_share(&shareholder1).unwrap(), 4500);
+    assert_eq!(splitter.get_share(&shareholder2).unwrap(), 5500);
+
+    // Without a cooldown transfers can follow each other
+    splitter.set_transfer_cooldown(&0);
+    splitter.transfer_shares(&shareholder1, &shareholder2, &1000);
+    assert_eq!(splitter.get_share(&shareholder1).unwrap(), 3500);
+}
+
+#[test]
+fn test_transfer_commission() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder1 = Address::generate(&env);
+    let shareholder2 = Address::generate(&env);
+
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder1.clone(),
+                share: 6000,
+            },
+            ShareDataKey {
+                shareholder: shareholder2.clone(),
+                share: 4000,
+            },
+        ],
+        &true,
+    );
+
+    let fee_token_admin = Address::generate(&env);
+    let (fee_token, sudo_fee_token, fee_token_address) = create_token(&env, &fee_token_admin);
+    let commission_recipient = setup_test_commission_recipient(&env, &splitter, &[&sudo_fee_token]);
+    sudo_fee_token.mint(&shareholder1, &100_000);
+
+    assert_eq!(
+        splitter.try_set_transfer_commission(&fee_token_address, &-1),
+        Err(Ok(Error::InvalidCommissionRate))
+    );
+    assert_eq!(
+        splitter.try_set_transfer_commission(&fee_token_address, &10_001),
+        Err(Ok(Error::InvalidCommissionRate))
+    );
+    splitter.set_transfer_commission(&fee_token_address, &250);
+    assert_eq!(splitter.get_transfer_commission().unwrap().bps, 250);
+
+    // 2.5% of 1000 shares
+    splitter.transfer_shares(&shareholder1, &shareholder2, &1000);
+    assert_eq!(fee_token.balance(&shareholder1), 99_975);
+    assert_eq!(fee_token.balance(&commission_recipient), 25);
+    assert_eq!(splitter.get_share(&shareholder2).unwrap(), 5000);
+
+    // Without a commission transfers are free
+    splitter.set_transfer_commission(&fee_token_address, &0);
+    assert_eq!(splitter.get_transfer_commission(), None);
+    splitter.transfer_shares(&shareholder1, &shareholder2, &1000);
+    assert_eq!(fee_token.balance(&shareholder1), 99_975);
+    assert_eq!(fee_token.balance(&commission_recipient), 25);
+    assert_eq!(splitter.get_share(&shareholder2).unwrap(), 6000);
+}
+
+#[test]
+fn get_share_at_past_ledgers() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(10);
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 6000,
+            },
+            ShareDataKey {
+                shareholder: buyer.clone(),
+                share: 4000,
+            },
+        ],
+        &true,
+    );
+
+    env.ledger().set_sequence_number(20);
+    splitter.transfer_shares(&seller, &buyer, &1000);
+
+    env.ledger().set_sequence_number(30);
+    splitter.transfer_shares(&seller, &buyer, &5000);
+
+    // Before the shareholder held shares
+    assert_eq!(splitter.get_share_at(&seller, &5), 0);
+    assert_eq!(splitter.get_share_at(&seller, &10), 6000);
+    assert_eq!(splitter.get_share_at(&seller, &19), 6000);
+    assert_eq!(splitter.get_share_at(&seller, &20), 5000);
+    assert_eq!(splitter.get_share_at(&seller, &29), 5000);
+    // Sold out
+    assert_eq!(splitter.get_share_at(&seller, &30), 0);
+
+    assert_eq!(splitter.get_share_at(&buyer, &25), 5000);
+    assert_eq!(splitter.get_share_at(&buyer, &100), 10000);
+}
+
+#[test]
+fn get_share_at_keeps_the_latest_checkpoints() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(10);
+
+    let admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller.clone(),
+                share: 6000,
+            },
+            ShareDataKey {
+                shareholder: buyer.clone(),
+                share: 4000,
+            },
+        ],
+        &true,
+    );
+
+    // 25 changes on top of the initial share, only the last 20 are kept
+    for i in 1..=25 {
+        env.ledger().set_sequence_number(100 + i);
+        splitter.transfer_shares(&seller, &buyer, &10);
+    }
+
+    assert_eq!(splitter.get_share_at(&seller, &10), 0);
+    assert_eq!(splitter.get_share_at(&seller, &105), 0);
+    assert_eq!(splitter.get_share_at(&seller, &106), 5940);
+    assert_eq!(splitter.get_share_at(&seller, &125), 5750);
+}
@@ -1,10 +1,10 @@
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{symbol_short, testutils::Address as _, vec, Address, Env, IntoVal};
 
 use crate::{
     errors::Error,
     tests::helpers::{
         create_splitter, create_splitter_with_shares, create_token, get_default_share_data,
-        setup_test_commission_recipient,
+        get_events_by_name, setup_test_commission_recipient,
     },
 };
 
@@ -138,3 +138,198 @@ fn test_transfer_amount_unused_balance() {
         Err(Ok(Error::TransferAmountAboveUnusedBalance))
     );
 }
+
+#[test]
+fn test_transfer_all_unused() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shares = get_default_share_data(&env);
+
+    let (splitter, splitter_address) = create_splitter_with_shares(&env, &admin, &shares, &true);
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+
+    // Set up commission recipient with trustline
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    // Mint and distribute (with 0.5% commission)
+    sudo_token.mint(&splitter_address, &1_000_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    // Mint more (unused)
+    sudo_token.mint(&splitter_address, &1_000_000_000);
+    // Total balance: 1_995_000_000, allocated: 995_000_000, unused: 1_000_000_000
+
+    let transfer_address = Address::generate(&env);
+    splitter.transfer_all_unused(&token_address, &transfer_address);
+
+    assert_eq!(token.balance(&transfer_address), 1_000_000_000);
+    assert_eq!(token.balance(&splitter_address), 995_000_000);
+
+    // The allocations are untouched
+    // 995_000_000 * 8050 / 10000 = 800_975_000, 995_000_000 * 1950 / 10000 = 194_025_000
+    let shareholder_1 = shares.get(0).unwrap().shareholder;
+    let shareholder_2 = shares.get(1).unwrap().shareholder;
+    assert_eq!(splitter.get_allocation(&shareholder_1, &token_address), 800_975_000);
+    assert_eq!(splitter.get_allocation(&shareholder_2, &token_address), 194_025_000);
+
+    // Nothing is left to sweep
+    splitter.transfer_all_unused(&token_address, &transfer_address);
+    assert_eq!(token.balance(&transfer_address), 1_000_000_000);
+    assert_eq!(token.balance(&splitter_address), 995_000_000);
+}
+
+#[test]
+fn test_transfer_all_unused_unauthorized() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+
+    let (splitter, _) =
+        create_splitter_with_shares(&env, &admin, &get_default_share_data(&env), &true);
+
+    let token_admin = Address::generate(&env);
+    let (_, _, token_address) = create_token(&env, &token_admin);
+
+    assert!(splitter
+        .try_transfer_all_unused(&token_address, &Address::generate(&env))
+        .is_err());
+}
+
+#[test]
+fn test_get_unused_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &get_default_share_data(&env), &true);
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    // Everything is unused before the distribution
+    sudo_token.mint(&splitter_address, &1_000_000_000);
+    assert_eq!(splitter.get_unused_balance(&token_address), 1_000_000_000);
+
+    // The 995_000_000 left after commission is allocated
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(splitter.get_unused_balance(&token_address), 0);
+
+    // Only the direct mint is unused
+    sudo_token.mint(&splitter_address, &300_000_000);
+    assert_eq!(splitter.get_unused_balance(&token_address), 300_000_000);
+
+    let transfer_address = Address::generate(&env);
+    splitter.transfer_tokens(&token_address, &transfer_address, &300_000_000);
+    assert_eq!(splitter.get_unused_balance(&token_address), 0);
+}
+
+#[test]
+fn test_get_contract_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &get_default_share_data(&env), &true);
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    assert_eq!(splitter.get_contract_balance(&token_address), 0);
+
+    sudo_token.mint(&splitter_address, &1_000_000);
+    assert_eq!(splitter.get_contract_balance(&token_address), 1_000_000);
+
+    // The commission leaves the contract, the allocations stay in it
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(splitter.get_contract_balance(&token_address), 995_000);
+    assert_eq!(
+        splitter.get_contract_balance(&token_address),
+        token.balance(&splitter_address)
+    );
+
+    let (_, tracked) = splitter.reconcile_allocations(&token_address);
+    assert_eq!(splitter.get_contract_balance(&token_address), tracked);
+}
+
+#[test]
+fn test_transfer_allowlist() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &get_default_share_data(&env), &true);
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    sudo_token.mint(&splitter_address, &1_000);
+
+    let allowed = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    // Without an allowlist any recipient works
+    assert_eq!(splitter.get_transfer_allowlist().len(), 0);
+    splitter.transfer_tokens(&token_address, &other, &100);
+    assert_eq!(token.balance(&other), 100);
+
+    splitter.set_transfer_allowlist(&vec![&env, allowed.clone()]);
+    assert_eq!(splitter.get_transfer_allowlist(), vec![&env, allowed.clone()]);
+
+    assert_eq!(
+        splitter.try_transfer_tokens(&token_address, &other, &100),
+        Err(Ok(Error::RecipientNotAllowed))
+    );
+    assert_eq!(
+        splitter.try_transfer_all_unused(&token_address, &other),
+        Err(Ok(Error::RecipientNotAllowed))
+    );
+
+    splitter.transfer_tokens(&token_address, &allowed, &100);
+    splitter.transfer_all_unused(&token_address, &allowed);
+    assert_eq!(token.balance(&allowed), 900);
+
+    // Emptying the allowlist lifts the restriction again
+    sudo_token.mint(&splitter_address, &1_000);
+    splitter.set_transfer_allowlist(&vec![&env]);
+    splitter.transfer_tokens(&token_address, &other, &100);
+    assert_eq!(token.balance(&other), 200);
+}
+
+#[test]
+fn test_swept_event_reports_remaining_unused() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &get_default_share_data(&env), &true);
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    sudo_token.mint(&splitter_address, &1_000_000_000);
+    splitter.distribute_tokens(&token_address);
+    sudo_token.mint(&splitter_address, &300_000_000);
+
+    // A partial sweep leaves the rest of the new deposit unused
+    let recipient = Address::generate(&env);
+    splitter.transfer_tokens(&token_address, &recipient, &100_000_000);
+
+    let events = get_events_by_name(&env, &splitter_address, symbol_short!("swept"));
+    assert_eq!(events.len(), 1);
+    let (_, data) = events.last().unwrap();
+    let data: (Address, i128, i128) = data.into_val(&env);
+    assert_eq!(data, (recipient, 100_000_000, 200_000_000));
+}
@@ -5,6 +5,7 @@ use crate::{
     storage::ShareDataKey,
     tests::helpers::{
         create_splitter, create_splitter_with_default_shares, create_splitter_with_shares,
+        create_token, setup_test_commission_recipient,
     },
 };
 
@@ -52,7 +53,7 @@ fn test_happy_path() {
         },
     ];
 
-    splitter.update_shares(&new_shares);
+    splitter.update_shares(&new_shares, &false, &false);
 
     assert_eq!(splitter.get_share(&shareholder_1), Some(4260));
     assert_eq!(splitter.get_share(&shareholder_2), Some(2748));
@@ -69,7 +70,7 @@ fn test_not_initialized() {
     let (splitter, _) = create_splitter(&env);
 
     assert_eq!(
-        splitter.try_update_shares(&vec![&env]),
+        splitter.try_update_shares(&vec![&env], &false, &false),
         Err(Ok(Error::NotInitialized))
     );
 }
@@ -81,7 +82,7 @@ fn test_unauthorized() {
     let admin = Address::generate(&env);
     let (splitter, _) = create_splitter_with_default_shares(&env, &admin);
 
-    assert!(splitter.try_update_shares(&vec![&env]).is_err());
+    assert!(splitter.try_update_shares(&vec![&env], &false, &false).is_err());
 }
 
 #[test]
@@ -100,11 +101,36 @@ fn test_low_share_count() {
                 shareholder: Address::generate(&env),
                 share: 8050,
             },
-        ]),
+        ], &false, &false),
         Err(Ok(Error::InvalidShareTotal))
     );
 }
 
+#[test]
+fn test_zero_share_not_allowed() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_default_shares(&env, &admin);
+
+    let zero_shareholder = Address::generate(&env);
+    assert_eq!(
+        splitter.try_update_shares(&vec![
+            &env,
+            ShareDataKey {
+                shareholder: Address::generate(&env),
+                share: 10000,
+            },
+            ShareDataKey {
+                shareholder: zero_shareholder.clone(),
+                share: 0,
+            },
+        ], &false, &false),
+        Err(Ok(Error::ZeroShareNotAllowed))
+    );
+    assert_eq!(splitter.get_share(&zero_shareholder), None);
+}
+
 #[test]
 fn test_invalid_share_total() {
     let env: Env = Env::default();
@@ -123,7 +149,7 @@ fn test_invalid_share_total() {
                 shareholder: Address::generate(&env),
                 share: 8050,
             },
-        ]),
+        ], &false, &false),
         Err(Ok(Error::InvalidShareTotal))
     );
 
@@ -138,7 +164,329 @@ fn test_invalid_share_total() {
                 shareholder: Address::generate(&env),
                 share: 50,
             },
-        ]),
+        ], &false, &false),
+        Err(Ok(Error::InvalidShareTotal))
+    );
+}
+
+#[test]
+fn test_clear_removed_allocations() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let kept_shareholder = Address::generate(&env);
+    let removed_shareholder = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: kept_shareholder.clone(),
+                share: 8000,
+            },
+            ShareDataKey {
+                shareholder: removed_shareholder.clone(),
+                share: 2000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    // 1_000_000 - 0.5% = 995_000 to distribute
+    // kept: 796_000, removed: 199_000
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    splitter.update_shares(
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: kept_shareholder.clone(),
+                share: 10000,
+            },
+        ],
+        &true,
+        &false,
+    );
+
+    // The removed shareholder was paid out
+    assert_eq!(token.balance(&removed_shareholder), 199_000);
+    assert_eq!(splitter.get_allocation(&removed_shareholder, &token_address), 0);
+
+    // The kept shareholder's allocation is untouched
+    assert_eq!(splitter.get_allocation(&kept_shareholder, &token_address), 796_000);
+    assert_eq!(token.balance(&splitter_address), 796_000);
+}
+
+#[test]
+fn test_preserve_removed_allocations() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let kept_shareholder = Address::generate(&env);
+    let removed_shareholder = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: kept_shareholder.clone(),
+                share: 8000,
+            },
+            ShareDataKey {
+                shareholder: removed_shareholder.clone(),
+                share: 2000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    splitter.update_shares(
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: kept_shareholder.clone(),
+                share: 10000,
+            },
+        ],
+        &false,
+        &true,
+    );
+
+    // The removed shareholder keeps a withdrawable allocation
+    assert_eq!(token.balance(&removed_shareholder), 0);
+    assert_eq!(splitter.get_allocation(&removed_shareholder, &token_address), 199_000);
+    splitter.withdraw_allocation(&token_address, &removed_shareholder, &199_000);
+    assert_eq!(token.balance(&removed_shareholder), 199_000);
+}
+
+#[test]
+fn test_removing_shareholder_with_pending_allocation() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let kept_shareholder = Address::generate(&env);
+    let removed_shareholder = Address::generate(&env);
+    let (splitter, splitter_address) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: kept_shareholder.clone(),
+                share: 8000,
+            },
+            ShareDataKey {
+                shareholder: removed_shareholder.clone(),
+                share: 2000,
+            },
+        ],
+        &true,
+    );
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    let new_shares = vec![
+        &env,
+        ShareDataKey {
+            shareholder: kept_shareholder.clone(),
+            share: 10000,
+        },
+    ];
+
+    // The removed shareholder still has 199_000 allocated
+    assert_eq!(
+        splitter.try_update_shares(&new_shares, &false, &false),
+        Err(Ok(Error::ShareholderHasPendingAllocation))
+    );
+    assert_eq!(splitter.get_share(&removed_shareholder), Some(2000));
+
+    // Once withdrawn, nothing is orphaned anymore
+    splitter.withdraw_allocation(&token_address, &removed_shareholder, &199_000);
+    splitter.update_shares(&new_shares, &false, &false);
+    assert_eq!(splitter.get_share(&removed_shareholder), None);
+}
+
+#[test]
+fn test_update_shares_with_remainder() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shareholder_1 = Address::generate(&env);
+    let shareholder_2 = Address::generate(&env);
+    let shareholder_3 = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder_1.clone(),
+                share: 5000,
+            },
+            ShareDataKey {
+                shareholder: shareholder_2.clone(),
+                share: 3000,
+            },
+            ShareDataKey {
+                shareholder: shareholder_3.clone(),
+                share: 2000,
+            },
+        ],
+        &true,
+    );
+
+    // shareholder_2 keeps 3000, shareholder_1 drops to 4000 and a newcomer gets 1000
+    let newcomer = Address::generate(&env);
+    splitter.update_shares_with_remainder(
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: shareholder_1.clone(),
+                share: 4000,
+            },
+            ShareDataKey {
+                shareholder: newcomer.clone(),
+                share: 1000,
+            },
+        ],
+        &shareholder_3,
+    );
+
+    // The remainder absorbs the difference
+    assert_eq!(splitter.get_share(&shareholder_1), Some(4000));
+    assert_eq!(splitter.get_share(&shareholder_2), Some(3000));
+    assert_eq!(splitter.get_share(&newcomer), Some(1000));
+    assert_eq!(splitter.get_share(&shareholder_3), Some(2000));
+    assert!(splitter.verify_integrity());
+
+    splitter.update_shares_with_remainder(
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: newcomer.clone(),
+                share: 0,
+            },
+        ],
+        &shareholder_3,
+    );
+    assert_eq!(splitter.get_share(&newcomer), None);
+    assert_eq!(splitter.get_share(&shareholder_3), Some(3000));
+}
+
+#[test]
+fn test_update_shares_with_remainder_overshoot() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_default_shares(&env, &admin);
+
+    let remainder = Address::generate(&env);
+    assert_eq!(
+        splitter.try_update_shares_with_remainder(
+            &vec![
+                &env,
+                ShareDataKey {
+                    shareholder: Address::generate(&env),
+                    share: 6000,
+                },
+                ShareDataKey {
+                    shareholder: Address::generate(&env),
+                    share: 5000,
+                },
+            ],
+            &remainder,
+        ),
         Err(Ok(Error::InvalidShareTotal))
     );
+
+    // The remainder cannot be changed explicitly
+    assert_eq!(
+        splitter.try_update_shares_with_remainder(
+            &vec![
+                &env,
+                ShareDataKey {
+                    shareholder: remainder.clone(),
+                    share: 1000,
+                },
+            ],
+            &remainder,
+        ),
+        Err(Ok(Error::DuplicateShareholder))
+    );
+}
+
+#[test]
+fn test_update_shares_fits_open_listings() {
+    let env: Env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let seller_1 = Address::generate(&env);
+    let seller_2 = Address::generate(&env);
+    let (splitter, _) = create_splitter_with_shares(
+        &env,
+        &admin,
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller_1.clone(),
+                share: 8050,
+            },
+            ShareDataKey {
+                shareholder: seller_2.clone(),
+                share: 1950,
+            },
+        ],
+        &true,
+    );
+
+    let payment_token = Address::generate(&env);
+    splitter.list_shares_for_sale(&seller_1, &5000, &100, &payment_token);
+    splitter.list_shares_for_sale(&seller_2, &1000, &100, &payment_token);
+
+    // seller_1 shrinks below their listing and seller_2 is removed
+    let shareholder = Address::generate(&env);
+    splitter.update_shares(
+        &vec![
+            &env,
+            ShareDataKey {
+                shareholder: seller_1.clone(),
+                share: 3000,
+            },
+            ShareDataKey {
+                shareholder: shareholder.clone(),
+                share: 7000,
+            },
+        ],
+        &false,
+        &false,
+    );
+
+    assert_eq!(splitter.get_listing(&seller_1).unwrap().shares_for_sale, 3000);
+    assert_eq!(splitter.get_listing(&seller_2), None);
+    assert_eq!(splitter.list_all_sales().len(), 1);
 }
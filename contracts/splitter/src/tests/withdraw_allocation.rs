@@ -1,7 +1,8 @@
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
 
 use crate::{
     errors::Error,
+    storage::{AllocationDataKey, DataKey},
     tests::helpers::{
         create_splitter, create_splitter_with_shares, create_token, get_default_share_data,
         setup_test_commission_recipient,
@@ -107,3 +108,277 @@ fn test_withdrawal_amount_above_allocation() {
         Err(Ok(Error::WithdrawalAmountAboveAllocation))
     );
 }
+
+#[test]
+fn test_withdraw_all_sentinel() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let shareholder = share_data.get(0).unwrap().shareholder;
+
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    // shareholder (80.5%): 995_000_000 * 8050 / 10000 = 800_975_000
+    sudo_token.mint(&splitter_address, &1_000_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    splitter.withdraw_allocation(&token_address, &shareholder, &-1);
+    assert_eq!(splitter.get_allocation(&shareholder, &token_address), 0);
+    assert_eq!(token.balance(&shareholder), 800_975_000);
+}
+
+#[test]
+fn test_withdraw_all_without_allocation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let shareholder = share_data.get(0).unwrap().shareholder;
+
+    let (splitter, _) = create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let token_admin = Address::generate(&env);
+    let (token, _, token_address) = create_token(&env, &token_admin);
+
+    // Nothing allocated, so withdrawing everything is a no-op
+    splitter.withdraw_allocation(&token_address, &shareholder, &-1);
+    assert_eq!(token.balance(&shareholder), 0);
+}
+
+#[test]
+fn test_negative_withdraw_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let (splitter, _) =
+        create_splitter_with_shares(&env, &admin, &get_default_share_data(&env), &true);
+
+    assert_eq!(
+        splitter.try_withdraw_allocation(&Address::generate(&env), &Address::generate(&env), &-2),
+        Err(Ok(Error::ZeroWithdrawalAmount))
+    );
+}
+
+#[test]
+fn test_reconcile_allocations() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let shares = get_default_share_data(&env);
+    let shareholder_1 = shares.get(0).unwrap().shareholder;
+    let shareholder_2 = shares.get(1).unwrap().shareholder;
+
+    let (splitter, splitter_address) = create_splitter_with_shares(&env, &admin, &shares, &true);
+
+    let token_admin = Address::generate(&env);
+    let (_, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    // 1_000_000 - 0.5% = 995_000 allocated
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+    splitter.withdraw_allocation(&token_address, &shareholder_1, &100_000);
+
+    // 333_333 - 1_666 = 331_667 allocated, including the rounding dust
+    sudo_token.mint(&splitter_address, &333_333);
+    splitter.distribute_tokens(&token_address);
+    splitter.withdraw_allocation(&token_address, &shareholder_2, &50_000);
+
+    let (sum, tracked) = splitter.reconcile_allocations(&token_address);
+    assert_eq!(sum, tracked);
+    assert_eq!(tracked, 995_000 - 100_000 + 331_667 - 50_000);
+}
+
+#[test]
+fn test_min_claimable() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let shareholder = share_data.get(0).unwrap().shareholder;
+
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    assert_eq!(
+        splitter.try_set_min_claimable(&token_address, &-1),
+        Err(Ok(Error::ZeroWithdrawalAmount))
+    );
+    splitter.set_min_claimable(&token_address, &500);
+
+    // shareholder (80.5%): 995 * 8050 / 10000 = 800 plus 1 of dust
+    sudo_token.mint(&splitter_address, &1_000);
+    splitter.distribute_tokens(&token_address);
+    assert_eq!(splitter.get_allocation(&shareholder, &token_address), 801);
+
+    assert_eq!(
+        splitter.try_withdraw_allocation(&token_address, &shareholder, &100),
+        Err(Ok(Error::BelowMinimumWithdrawal))
+    );
+
+    splitter.withdraw_allocation(&token_address, &shareholder, &600);
+
+    // The remaining 201 is below the minimum, but can be withdrawn in full
+    assert_eq!(
+        splitter.try_withdraw_allocation(&token_address, &shareholder, &200),
+        Err(Ok(Error::BelowMinimumWithdrawal))
+    );
+    splitter.withdraw_allocation(&token_address, &shareholder, &201);
+    assert_eq!(splitter.get_allocation(&shareholder, &token_address), 0);
+    assert_eq!(token.balance(&shareholder), 801);
+}
+
+#[test]
+fn test_withdraw_allocations_for() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let shareholder_1 = share_data.get(0).unwrap().shareholder;
+    let shareholder_2 = share_data.get(1).unwrap().shareholder;
+    let operator = Address::generate(&env);
+
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    // shareholder_1: 800_975, shareholder_2: 194_025
+    sudo_token.mint(&splitter_address, &1_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    splitter.approve_withdraw_operator(&shareholder_1, &operator, &token_address, &500_000);
+    splitter.approve_withdraw_operator(&shareholder_2, &operator, &token_address, &194_025);
+
+    // Claims of the same shareholder add up against the allowance
+    assert_eq!(
+        splitter.try_withdraw_allocations_for(
+            &operator,
+            &vec![
+                &env,
+                (shareholder_2.clone(), token_address.clone(), 194_025),
+                (shareholder_1.clone(), token_address.clone(), 300_000),
+                (shareholder_1.clone(), token_address.clone(), 300_000),
+            ]
+        ),
+        Err(Ok(Error::InsufficientShareAllowance))
+    );
+
+    // Nothing was withdrawn by the failed batch
+    assert_eq!(token.balance(&shareholder_2), 0);
+
+    // Without an approval the operator cannot claim
+    assert_eq!(
+        splitter.try_withdraw_allocations_for(
+            &Address::generate(&env),
+            &vec![&env, (shareholder_1.clone(), token_address.clone(), 1)]
+        ),
+        Err(Ok(Error::InsufficientShareAllowance))
+    );
+
+    splitter.withdraw_allocations_for(
+        &operator,
+        &vec![
+            &env,
+            (shareholder_2.clone(), token_address.clone(), 194_025),
+            (shareholder_1.clone(), token_address.clone(), 200_000),
+            (shareholder_1.clone(), token_address.clone(), 300_000),
+        ],
+    );
+
+    // The tokens go to the shareholders, not to the operator
+    assert_eq!(token.balance(&shareholder_1), 500_000);
+    assert_eq!(token.balance(&shareholder_2), 194_025);
+    assert_eq!(token.balance(&operator), 0);
+    assert_eq!(splitter.get_allocation(&shareholder_1, &token_address), 300_975);
+    assert_eq!(splitter.get_allocation(&shareholder_2, &token_address), 0);
+
+    // The allowance is used up
+    assert_eq!(
+        splitter.try_withdraw_allocations_for(
+            &operator,
+            &vec![&env, (shareholder_1.clone(), token_address.clone(), 1)]
+        ),
+        Err(Ok(Error::InsufficientShareAllowance))
+    );
+}
+
+#[test]
+fn test_zero_allocation_removes_key() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let shareholder = share_data.get(0).unwrap().shareholder;
+
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &share_data, &true);
+    let token_address = Address::generate(&env);
+
+    env.as_contract(&splitter_address, || {
+        AllocationDataKey::save_allocation(&env, &shareholder, &token_address, 100);
+        AllocationDataKey::save_allocation(&env, &shareholder, &token_address, 0);
+
+        // No dead entries are left behind
+        let key = DataKey::Allocation(shareholder.clone(), token_address.clone());
+        assert!(!env.storage().persistent().has(&key));
+        assert_eq!(AllocationDataKey::get_total_allocation(&env, &token_address), None);
+        assert!(AllocationDataKey::get_shareholder_tokens(&env, &shareholder).is_empty());
+    });
+
+    assert_eq!(splitter.get_allocation(&shareholder, &token_address), 0);
+}
+
+#[test]
+fn test_push_allocation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let share_data = get_default_share_data(&env);
+    let shareholder = share_data.get(0).unwrap().shareholder;
+
+    let (splitter, splitter_address) =
+        create_splitter_with_shares(&env, &admin, &share_data, &true);
+
+    let token_admin = Address::generate(&env);
+    let (token, sudo_token, token_address) = create_token(&env, &token_admin);
+    setup_test_commission_recipient(&env, &splitter, &[&sudo_token]);
+
+    sudo_token.mint(&splitter_address, &1_000_000_000);
+    splitter.distribute_tokens(&token_address);
+
+    // Only the admin signs the payout
+    assert_eq!(splitter.push_allocation(&token_address, &shareholder), 800_975_000);
+    let auths = env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths.first().unwrap().0, admin);
+
+    assert_eq!(token.balance(&shareholder), 800_975_000);
+    assert_eq!(splitter.get_allocation(&shareholder, &token_address), 0);
+
+    // Nothing is left to push
+    assert_eq!(
+        splitter.try_push_allocation(&token_address, &shareholder),
+        Err(Ok(Error::ZeroWithdrawalAmount))
+    );
+}